@@ -1,8 +1,6 @@
 use {
     anyhow::{anyhow, Result},
-    borsh::BorshDeserialize,
-    clap::{command, Parser, Subcommand},
-    serde::Deserialize,
+    clap::{Parser, Subcommand},
     solana_cli_config,
     solana_client::rpc_client::RpcClient,
     solana_sdk::{
@@ -12,8 +10,11 @@ use {
         signature::{read_keypair_file, Signer},
         transaction::Transaction,
     },
-    std::{fs, str::FromStr},
-    yield_tokenizer::state::YieldTokenizerState,
+    yield_tokenizer::{
+        adapter::get_lsu_adapter,
+        resolver::{self, TokenizerAccounts},
+        state::{ProtocolConfig, YieldTokenizerState},
+    },
 };
 
 #[derive(Parser)]
@@ -24,162 +25,213 @@ struct Cli {
     rpc: Option<String>,
     #[arg(short, long)]
     payer: Option<String>,
+    /// Tokenizer program to use, for deployments other than the default
+    #[arg(long, default_value_t = yield_tokenizer::id())]
+    program_id: Pubkey,
     #[command(subcommand)]
     commands: Commands,
 }
 
 #[derive(Subcommand)]
 enum Commands {
+    /// Add one of the LSUs the protocol launched with to the config allowlist
+    AddLsu {
+        lsu_mint: Pubkey,
+        /// Used in PT/YT names, e.g. `mSOL`
+        symbol: String,
+    },
     Init {
-        lsu_mint: &Pubkey,
-        expiry: yield_tokenizer::instruction::Expiry,
+        lsu_mint: Pubkey,
+        /// Month-end maturity date, YYYY-MM-DD
+        maturity: String,
+        /// Create PT and YT as Token-2022 mints with metadata
+        #[arg(long)]
+        token_2022: bool,
     },
     Tokenize {
         amount: u64,
-        lsu_mint: &Pubkey,
-        expiry: yield_tokenizer::instruction::Expiry,
+        lsu_mint: Pubkey,
+        /// Month-end maturity date, YYYY-MM-DD
+        maturity: String,
     },
     Redeem {
         amount: u64,
-        lsu_mint: &Pubkey,
-        expiry: yield_tokenizer::instruction::Expiry,
+        lsu_mint: Pubkey,
+        /// Month-end maturity date, YYYY-MM-DD
+        maturity: String,
     },
     RedeemPt {
         amount: u64,
-        lsu_mint: &Pubkey,
-        expiry: yield_tokenizer::instruction::Expiry,
+        lsu_mint: Pubkey,
+        /// Month-end maturity date, YYYY-MM-DD
+        maturity: String,
     },
     Claim {
-        lsu_mint: &Pubkey,
-        expiry: yield_tokenizer::instruction::Expiry,
+        lsu_mint: Pubkey,
+        /// Month-end maturity date, YYYY-MM-DD
+        maturity: String,
     },
 }
 
+fn parse_maturity(maturity: &str) -> Result<i64> {
+    yield_tokenizer::maturity::parse_maturity_date(maturity)
+        .ok_or_else(|| anyhow!("{} is not a month-end date (YYYY-MM-DD)", maturity))
+}
+
+/// Accounts of an existing series, reading the exchange rate account from the tokenizer and the
+/// PT/YT token program from its PT mint
+fn resolve(
+    client: &RpcClient,
+    program_id: &Pubkey,
+    lsu_mint: &Pubkey,
+    maturity_date: i64,
+    user: &Pubkey,
+) -> Result<TokenizerAccounts> {
+    let yield_tokenizer_addr =
+        yield_tokenizer::get_yield_tokenizer_address(program_id, lsu_mint, maturity_date);
+    let yield_tokenizer_data = YieldTokenizerState::unpack(
+        &client
+            .get_account_data(&yield_tokenizer_addr)
+            .map_err(|_| anyhow!("No tokenizer for {} maturing {}", lsu_mint, maturity_date))?,
+    )?;
+    let yield_token_program = client.get_account(&yield_tokenizer_data.pt)?.owner;
+
+    Ok(TokenizerAccounts::new(
+        program_id,
+        lsu_mint,
+        maturity_date,
+        user,
+        &yield_tokenizer_data.lsu_exchange_rate_account,
+        &yield_token_program,
+    ))
+}
+
 fn main() -> Result<()> {
     let args = Cli::parse();
 
-    let solana_config_file = if let Some(ref config) = *solana_cli_config::CONFIG_FILE {
+    let config_file = args
+        .config
+        .as_ref()
+        .or(solana_cli_config::CONFIG_FILE.as_ref());
+    let solana_config_file = if let Some(config) = config_file {
         solana_cli_config::Config::load(config).unwrap_or_default()
     } else {
         solana_cli_config::Config::default()
     };
 
-    let wallet_keypair =
-        read_keypair_file(solana_config_file.keypair_path).expect("Can't open file-wallet");
+    let program_id = args.program_id;
+    let wallet_keypair = read_keypair_file(
+        args.payer
+            .as_ref()
+            .unwrap_or(&solana_config_file.keypair_path),
+    )
+    .expect("Can't open file-wallet");
     let wallet_pubkey = wallet_keypair.pubkey();
 
     let client = RpcClient::new_with_commitment(
-        solana_config_file.json_rpc_url.to_string(),
+        args.rpc.unwrap_or(solana_config_file.json_rpc_url),
         CommitmentConfig::confirmed(),
     );
 
-    let mut ix: Instruction;
-
-    match args.commands {
-        Commands::Init { lsu_mint, expiry } => {
-            ix = yield_tokenizer::instruction::init_yield_tokenizer(
-                yield_tokenizer::id(),
-                wallet_pubkey,
-            )
+    let ix: Instruction = match args.commands {
+        Commands::AddLsu { lsu_mint, symbol } => {
+            let (adapter, exchange_rate_account) = get_lsu_adapter(&lsu_mint)
+                .ok_or_else(|| anyhow!("No known exchange rate account for {}", lsu_mint))?;
+            yield_tokenizer::instruction::add_lsu(
+                &program_id,
+                &wallet_pubkey,
+                &yield_tokenizer::get_config_address(&program_id),
+                &lsu_mint,
+                &exchange_rate_account,
+                adapter,
+                &symbol,
+            )?
+        }
+        Commands::Init {
+            lsu_mint,
+            maturity,
+            token_2022,
+        } => {
+            let maturity_date = parse_maturity(&maturity)?;
+            let config = ProtocolConfig::unpack(
+                &client.get_account_data(&yield_tokenizer::get_config_address(&program_id))?,
+            )?;
+            let supported_lsu = config
+                .get_lsu(&lsu_mint)
+                .ok_or_else(|| anyhow!("{} is not a supported LSU", lsu_mint))?;
+            let yield_token_program = if token_2022 {
+                spl_token_2022::id()
+            } else {
+                spl_token::id()
+            };
+            resolver::init_yield_tokenizer_for(&TokenizerAccounts::new(
+                &program_id,
+                &lsu_mint,
+                maturity_date,
+                &wallet_pubkey,
+                &supported_lsu.exchange_rate_account,
+                &yield_token_program,
+            ))?
         }
         Commands::Tokenize {
             amount,
             lsu_mint,
-            expiry,
+            maturity,
         } => {
-            let yield_tokenizer_addr =
-                yield_tokenizer::get_yield_tokenizer_address(lsu_mint, expiry);
-            let pt_addr = yield_tokenizer::get_principal_token_address(yield_tokenizer_addr);
-            let yt_addr = yield_tokenizer::get_yield_token_address(yield_tokenizer_addr);
-            ix = yield_tokenizer::instruction::tokenize_yield(
-                wallet_pubkey,
-                yield_tokenizer_addr,
-                lsu_mint,
-                pt_addr,
-                yt_addr,
-                spl_associated_token_account::get_associated_token_account(
-                    yield_tokenizer_addr,
-                    lsu_mint,
-                ),
-                spl_associated_token_account::get_associated_token_account(wallet_pubkey, lsu_mint),
-                spl_associated_token_account::get_associated_token_account(wallet_pubkey, pt_addr),
-                spl_associated_token_account::get_associated_token_account(wallet_pubkey, yt_addr),
-                amount,
-            )
+            let accounts = resolve(
+                &client,
+                &program_id,
+                &lsu_mint,
+                parse_maturity(&maturity)?,
+                &wallet_pubkey,
+            )?;
+            resolver::tokenize_yield_for(&accounts, amount)?
         }
         Commands::Redeem {
             amount,
             lsu_mint,
-            expiry,
+            maturity,
         } => {
-            let yield_tokenizer_addr =
-                yield_tokenizer::get_yield_tokenizer_address(lsu_mint, expiry);
-            let pt_addr = yield_tokenizer::get_principal_token_address(yield_tokenizer_addr);
-            let yt_addr = yield_tokenizer::get_yield_token_address(yield_tokenizer_addr);
-            ix = yield_tokenizer::instruction::redeem(
-                wallet_pubkey,
-                yield_tokenizer_addr,
-                lsu_mint,
-                pt_addr,
-                yt_addr,
-                spl_associated_token_account::get_associated_token_account(
-                    yield_tokenizer_addr,
-                    lsu_mint,
-                ),
-                spl_associated_token_account::get_associated_token_account(wallet_pubkey, lsu_mint),
-                spl_associated_token_account::get_associated_token_account(wallet_pubkey, pt_addr),
-                spl_associated_token_account::get_associated_token_account(wallet_pubkey, yt_addr),
-                amount,
-            )
+            let accounts = resolve(
+                &client,
+                &program_id,
+                &lsu_mint,
+                parse_maturity(&maturity)?,
+                &wallet_pubkey,
+            )?;
+            resolver::redeem_for(&accounts, amount)?
         }
         Commands::RedeemPt {
             amount,
             lsu_mint,
-            expiry,
+            maturity,
         } => {
-            let yield_tokenizer_addr =
-                yield_tokenizer::get_yield_tokenizer_address(lsu_mint, expiry);
-            let pt_addr = yield_tokenizer::get_principal_token_address(yield_tokenizer_addr);
-            ix = yield_tokenizer::instruction::redeem_from_pt(
-                wallet_pubkey,
-                yield_tokenizer_addr,
-                pt_addr,
-                spl_associated_token_account::get_associated_token_account(
-                    yield_tokenizer_addr,
-                    lsu_mint,
-                ),
-                spl_associated_token_account::get_associated_token_account(wallet_pubkey, lsu_mint),
-                spl_associated_token_account::get_associated_token_account(wallet_pubkey, pt_addr),
-                amount,
-            )
+            let accounts = resolve(
+                &client,
+                &program_id,
+                &lsu_mint,
+                parse_maturity(&maturity)?,
+                &wallet_pubkey,
+            )?;
+            resolver::redeem_from_pt_for(&accounts, amount)?
         }
-        Commands::Claim { lsu_mint, expiry } => {
-            let yield_tokenizer_addr =
-                yield_tokenizer::get_yield_tokenizer_address(lsu_mint, expiry);
-            let yt_addr = yield_tokenizer::get_yield_token_address(yield_tokenizer_addr);
-            ix = yield_tokenizer::instruction::redeem(
-                wallet_pubkey,
-                yield_tokenizer_addr,
-                yt_addr,
-                spl_associated_token_account::get_associated_token_account(
-                    yield_tokenizer_addr,
-                    lsu_mint,
-                ),
-                spl_associated_token_account::get_associated_token_account(
-                    yield_tokenizer_addr,
-                    yt_addr,
-                ),
-                spl_associated_token_account::get_associated_token_account(wallet_pubkey, lsu_mint),
-                spl_associated_token_account::get_associated_token_account(wallet_pubkey, yt_addr),
-            )
+        Commands::Claim { lsu_mint, maturity } => {
+            let accounts = resolve(
+                &client,
+                &program_id,
+                &lsu_mint,
+                parse_maturity(&maturity)?,
+                &wallet_pubkey,
+            )?;
+            resolver::claim_yield_for(&accounts)?
         }
-    }
+    };
 
-    let mut tx = Transaction::new_with_payer(&[instruction], Some(&wallet_pubkey));
+    let mut tx = Transaction::new_with_payer(&[ix], Some(&wallet_pubkey));
     let recent_blockhash = client
         .get_latest_blockhash()
         .expect("Cannot retrieve latest blockhash");
-    tx.sign(&vec![&wallet_keypair], recent_blockhash);
+    tx.sign(&[&wallet_keypair], recent_blockhash);
 
     let id = client
         .send_and_confirm_transaction(&tx)
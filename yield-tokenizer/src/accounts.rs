@@ -0,0 +1,2546 @@
+use {
+    crate::{
+        create_pool_address, create_treasury_address, create_yield_tokenizer_address,
+        error::YieldTokenizerError,
+        find_config_address, find_lp_mint_address, find_pool_address, find_principal_token_address,
+        find_treasury_address, find_yield_token_address, find_yield_tokenizer_address,
+        get_config_address,
+        state::{
+            LegacyYieldTokenizerState, ProtocolConfig, PtPool, SupportedLsu, YieldTokenizerState,
+            LEGACY_YIELD_TOKENIZER_LEN,
+        },
+    },
+    borsh::BorshDeserialize,
+    solana_program::{
+        account_info::AccountInfo, bpf_loader_upgradeable, program_error::ProgramError,
+        pubkey::Pubkey, system_program,
+    },
+    spl_associated_token_account::{
+        get_associated_token_address, get_associated_token_address_with_program_id,
+    },
+    spl_token_2022::{extension::StateWithExtensions, state::Account},
+};
+
+/// Accounts of `InitializeYieldTokenizer`. The tokenizer PDA depends on the maturity date in the
+/// instruction data, so the processor derives it. The mint and treasury PDAs hang off the
+/// tokenizer's address and are checked here, their bumps are returned for creating them.
+pub struct InitializeYieldTokenizerAccounts<'a, 'info> {
+    pub authority: &'a AccountInfo<'info>,
+    pub config: &'a AccountInfo<'info>,
+    pub yield_tokenizer: &'a AccountInfo<'info>,
+    pub lsu_mint: &'a AccountInfo<'info>,
+    pub lsu_exchange_rate_account: &'a AccountInfo<'info>,
+    pub pt_mint: &'a AccountInfo<'info>,
+    pub yt_mint: &'a AccountInfo<'info>,
+    pub lsu_vault: &'a AccountInfo<'info>,
+    pub treasury: &'a AccountInfo<'info>,
+    pub token_program: &'a AccountInfo<'info>,
+    pub yield_token_program: &'a AccountInfo<'info>,
+    pub atoken_program: &'a AccountInfo<'info>,
+    pub system_program: &'a AccountInfo<'info>,
+    pub config_data: ProtocolConfig,
+    /// Config entry of the LSU, the series copies its adapter and exchange rate account
+    pub supported_lsu: SupportedLsu,
+    pub pt_bump: u8,
+    pub yt_bump: u8,
+    pub treasury_bump: u8,
+}
+
+impl<'a, 'info> TryFrom<(&'a Pubkey, &'a [AccountInfo<'info>])>
+    for InitializeYieldTokenizerAccounts<'a, 'info>
+{
+    type Error = ProgramError;
+
+    fn try_from(
+        (program_id, accounts): (&'a Pubkey, &'a [AccountInfo<'info>]),
+    ) -> Result<Self, Self::Error> {
+        let [authority, config, yield_tokenizer, lsu_mint, lsu_exchange_rate_account, pt_mint, yt_mint, lsu_vault, treasury, token_program, yield_token_program, atoken_program, system_program, ..] =
+            accounts
+        else {
+            return Err(ProgramError::NotEnoughAccountKeys);
+        };
+
+        check_signer(authority)?;
+        check_writable(authority)?;
+        let config_data = load_config(program_id, config)?;
+        for account in [yield_tokenizer, pt_mint, yt_mint, lsu_vault, treasury] {
+            check_writable(account)?;
+        }
+        // Deploy scripts retry init on timeouts, a retry must not overwrite a live series. Owned
+        // accounts cover tokenizers from before the header.
+        if yield_tokenizer.owner == program_id
+            || YieldTokenizerState::is_initialized(&yield_tokenizer.data.borrow())
+        {
+            return Err(YieldTokenizerError::AlreadyInitialized.into());
+        }
+        check_program(token_program, &spl_token::id())?;
+        let supported_lsu = config_data
+            .get_lsu(lsu_mint.key)
+            .ok_or(YieldTokenizerError::InvalidLSU)?
+            .clone();
+        // PT and YT mirror the LSU's decimals, which are read from the mint
+        check_owner(lsu_mint, token_program.key).map_err(|_| YieldTokenizerError::InvalidLSU)?;
+        check_address(
+            lsu_exchange_rate_account,
+            &supported_lsu.exchange_rate_account,
+            YieldTokenizerError::InvalidExchangeRateAccount,
+        )?;
+        let (pt_mint_addr, pt_bump) = find_principal_token_address(program_id, yield_tokenizer.key);
+        check_address(
+            pt_mint,
+            &pt_mint_addr,
+            YieldTokenizerError::InvalidPrincipalToken,
+        )?;
+        let (yt_mint_addr, yt_bump) = find_yield_token_address(program_id, yield_tokenizer.key);
+        check_address(
+            yt_mint,
+            &yt_mint_addr,
+            YieldTokenizerError::InvalidYieldToken,
+        )?;
+        check_address(
+            lsu_vault,
+            &get_associated_token_address(yield_tokenizer.key, lsu_mint.key),
+            YieldTokenizerError::LSUTokenAccountMismatch,
+        )?;
+        let (treasury_addr, treasury_bump) = find_treasury_address(program_id, yield_tokenizer.key);
+        check_address(
+            treasury,
+            &treasury_addr,
+            YieldTokenizerError::InvalidTreasury,
+        )?;
+        // PT and YT can be created under either token program, the choice is fixed by the mints
+        if yield_token_program.key != &spl_token::id()
+            && yield_token_program.key != &spl_token_2022::id()
+        {
+            return Err(YieldTokenizerError::InvalidProgram.into());
+        }
+        check_program(atoken_program, &spl_associated_token_account::id())?;
+        check_program(system_program, &system_program::id())?;
+
+        Ok(Self {
+            authority,
+            config,
+            yield_tokenizer,
+            lsu_mint,
+            lsu_exchange_rate_account,
+            pt_mint,
+            yt_mint,
+            lsu_vault,
+            treasury,
+            token_program,
+            yield_token_program,
+            atoken_program,
+            system_program,
+            config_data,
+            supported_lsu,
+            pt_bump,
+            yt_bump,
+            treasury_bump,
+        })
+    }
+}
+
+/// Accounts of `TokenizeYield`
+pub struct TokenizeYieldAccounts<'a, 'info> {
+    pub buyer: &'a AccountInfo<'info>,
+    pub config: &'a AccountInfo<'info>,
+    pub yield_tokenizer: &'a AccountInfo<'info>,
+    pub lsu_mint: &'a AccountInfo<'info>,
+    pub lsu_exchange_rate_account: &'a AccountInfo<'info>,
+    pub pt_mint: &'a AccountInfo<'info>,
+    pub yt_mint: &'a AccountInfo<'info>,
+    pub lsu_vault: &'a AccountInfo<'info>,
+    pub buyer_lsu_ata: &'a AccountInfo<'info>,
+    pub buyer_pt_ata: &'a AccountInfo<'info>,
+    pub buyer_yt_ata: &'a AccountInfo<'info>,
+    pub buyer_position_account: &'a AccountInfo<'info>,
+    pub token_program: &'a AccountInfo<'info>,
+    pub yield_token_program: &'a AccountInfo<'info>,
+    pub atoken_program: &'a AccountInfo<'info>,
+    pub system_program: &'a AccountInfo<'info>,
+    pub config_data: ProtocolConfig,
+    pub yield_tokenizer_data: YieldTokenizerState,
+}
+
+impl<'a, 'info> TryFrom<(&'a Pubkey, &'a [AccountInfo<'info>])>
+    for TokenizeYieldAccounts<'a, 'info>
+{
+    type Error = ProgramError;
+
+    fn try_from(
+        (program_id, accounts): (&'a Pubkey, &'a [AccountInfo<'info>]),
+    ) -> Result<Self, Self::Error> {
+        let [buyer, config, yield_tokenizer, lsu_mint, lsu_exchange_rate_account, pt_mint, yt_mint, lsu_vault, buyer_lsu_ata, buyer_pt_ata, buyer_yt_ata, buyer_position_account, token_program, yield_token_program, atoken_program, system_program, ..] =
+            accounts
+        else {
+            return Err(ProgramError::NotEnoughAccountKeys);
+        };
+
+        check_signer(buyer)?;
+        let config_data = load_config(program_id, config)?;
+        let yield_tokenizer_data = load_yield_tokenizer(program_id, yield_tokenizer)?;
+        check_lsu(lsu_mint, lsu_exchange_rate_account, &yield_tokenizer_data)?;
+        check_address(
+            pt_mint,
+            &yield_tokenizer_data.pt,
+            YieldTokenizerError::InvalidPrincipalToken,
+        )?;
+        check_address(
+            yt_mint,
+            &yield_tokenizer_data.yt,
+            YieldTokenizerError::InvalidYieldToken,
+        )?;
+        check_vault(lsu_vault, &yield_tokenizer_data)?;
+        check_program(token_program, &spl_token::id())?;
+        check_yield_token_program(yield_token_program, pt_mint)?;
+        check_token_account(
+            buyer_lsu_ata,
+            buyer.key,
+            lsu_mint,
+            YieldTokenizerError::LSUTokenAccountMismatch,
+        )?;
+        check_token_account(
+            buyer_pt_ata,
+            buyer.key,
+            pt_mint,
+            YieldTokenizerError::InvalidPrincipalToken,
+        )?;
+        check_token_account(
+            buyer_yt_ata,
+            buyer.key,
+            yt_mint,
+            YieldTokenizerError::InvalidYieldToken,
+        )?;
+        for account in [
+            buyer,
+            pt_mint,
+            yt_mint,
+            buyer_lsu_ata,
+            buyer_pt_ata,
+            buyer_yt_ata,
+            buyer_position_account,
+        ] {
+            check_writable(account)?;
+        }
+        check_program(atoken_program, &spl_associated_token_account::id())?;
+        check_program(system_program, &system_program::id())?;
+
+        Ok(Self {
+            buyer,
+            config,
+            yield_tokenizer,
+            lsu_mint,
+            lsu_exchange_rate_account,
+            pt_mint,
+            yt_mint,
+            lsu_vault,
+            buyer_lsu_ata,
+            buyer_pt_ata,
+            buyer_yt_ata,
+            buyer_position_account,
+            token_program,
+            yield_token_program,
+            atoken_program,
+            system_program,
+            config_data,
+            yield_tokenizer_data,
+        })
+    }
+}
+
+/// Accounts of `Redeem`
+pub struct RedeemAccounts<'a, 'info> {
+    pub redeemer: &'a AccountInfo<'info>,
+    pub config: &'a AccountInfo<'info>,
+    pub yield_tokenizer: &'a AccountInfo<'info>,
+    pub lsu_mint: &'a AccountInfo<'info>,
+    pub lsu_exchange_rate_account: &'a AccountInfo<'info>,
+    pub pt_mint: &'a AccountInfo<'info>,
+    pub yt_mint: &'a AccountInfo<'info>,
+    pub lsu_vault: &'a AccountInfo<'info>,
+    pub treasury: &'a AccountInfo<'info>,
+    pub redeemer_lsu_ata: &'a AccountInfo<'info>,
+    pub redeemer_pt_ata: &'a AccountInfo<'info>,
+    pub redeemer_yt_ata: &'a AccountInfo<'info>,
+    pub redeemer_position_account: &'a AccountInfo<'info>,
+    pub token_program: &'a AccountInfo<'info>,
+    pub yield_token_program: &'a AccountInfo<'info>,
+    pub atoken_program: &'a AccountInfo<'info>,
+    pub system_program: &'a AccountInfo<'info>,
+    pub config_data: ProtocolConfig,
+    pub yield_tokenizer_data: YieldTokenizerState,
+}
+
+impl<'a, 'info> TryFrom<(&'a Pubkey, &'a [AccountInfo<'info>])> for RedeemAccounts<'a, 'info> {
+    type Error = ProgramError;
+
+    fn try_from(
+        (program_id, accounts): (&'a Pubkey, &'a [AccountInfo<'info>]),
+    ) -> Result<Self, Self::Error> {
+        let [redeemer, config, yield_tokenizer, lsu_mint, lsu_exchange_rate_account, pt_mint, yt_mint, lsu_vault, treasury, redeemer_lsu_ata, redeemer_pt_ata, redeemer_yt_ata, redeemer_position_account, token_program, yield_token_program, atoken_program, system_program, ..] =
+            accounts
+        else {
+            return Err(ProgramError::NotEnoughAccountKeys);
+        };
+
+        check_signer(redeemer)?;
+        let config_data = load_config(program_id, config)?;
+        let yield_tokenizer_data = load_yield_tokenizer(program_id, yield_tokenizer)?;
+        check_lsu(lsu_mint, lsu_exchange_rate_account, &yield_tokenizer_data)?;
+        check_address(
+            pt_mint,
+            &yield_tokenizer_data.pt,
+            YieldTokenizerError::InvalidPrincipalToken,
+        )?;
+        check_address(
+            yt_mint,
+            &yield_tokenizer_data.yt,
+            YieldTokenizerError::InvalidYieldToken,
+        )?;
+        check_vault(lsu_vault, &yield_tokenizer_data)?;
+        check_treasury(program_id, treasury, yield_tokenizer, &yield_tokenizer_data)?;
+        check_program(token_program, &spl_token::id())?;
+        check_yield_token_program(yield_token_program, pt_mint)?;
+        check_token_account(
+            redeemer_lsu_ata,
+            redeemer.key,
+            lsu_mint,
+            YieldTokenizerError::LSUTokenAccountMismatch,
+        )?;
+        check_token_account(
+            redeemer_pt_ata,
+            redeemer.key,
+            pt_mint,
+            YieldTokenizerError::InvalidPrincipalToken,
+        )?;
+        check_token_account(
+            redeemer_yt_ata,
+            redeemer.key,
+            yt_mint,
+            YieldTokenizerError::InvalidYieldToken,
+        )?;
+        for account in [
+            redeemer,
+            pt_mint,
+            yt_mint,
+            redeemer_lsu_ata,
+            redeemer_pt_ata,
+            redeemer_yt_ata,
+            redeemer_position_account,
+        ] {
+            check_writable(account)?;
+        }
+        check_program(atoken_program, &spl_associated_token_account::id())?;
+        check_program(system_program, &system_program::id())?;
+
+        Ok(Self {
+            redeemer,
+            config,
+            yield_tokenizer,
+            lsu_mint,
+            lsu_exchange_rate_account,
+            pt_mint,
+            yt_mint,
+            lsu_vault,
+            treasury,
+            redeemer_lsu_ata,
+            redeemer_pt_ata,
+            redeemer_yt_ata,
+            redeemer_position_account,
+            token_program,
+            yield_token_program,
+            atoken_program,
+            system_program,
+            config_data,
+            yield_tokenizer_data,
+        })
+    }
+}
+
+/// Accounts of `RedeemFromPt`
+pub struct RedeemFromPtAccounts<'a, 'info> {
+    pub redeemer: &'a AccountInfo<'info>,
+    pub config: &'a AccountInfo<'info>,
+    pub yield_tokenizer: &'a AccountInfo<'info>,
+    pub lsu_mint: &'a AccountInfo<'info>,
+    pub lsu_exchange_rate_account: &'a AccountInfo<'info>,
+    pub pt_mint: &'a AccountInfo<'info>,
+    pub lsu_vault: &'a AccountInfo<'info>,
+    pub treasury: &'a AccountInfo<'info>,
+    pub redeemer_lsu_ata: &'a AccountInfo<'info>,
+    pub redeemer_pt_ata: &'a AccountInfo<'info>,
+    pub token_program: &'a AccountInfo<'info>,
+    pub yield_token_program: &'a AccountInfo<'info>,
+    pub atoken_program: &'a AccountInfo<'info>,
+    pub system_program: &'a AccountInfo<'info>,
+    pub config_data: ProtocolConfig,
+    pub yield_tokenizer_data: YieldTokenizerState,
+}
+
+impl<'a, 'info> TryFrom<(&'a Pubkey, &'a [AccountInfo<'info>])>
+    for RedeemFromPtAccounts<'a, 'info>
+{
+    type Error = ProgramError;
+
+    fn try_from(
+        (program_id, accounts): (&'a Pubkey, &'a [AccountInfo<'info>]),
+    ) -> Result<Self, Self::Error> {
+        let [redeemer, config, yield_tokenizer, lsu_mint, lsu_exchange_rate_account, pt_mint, lsu_vault, treasury, redeemer_lsu_ata, redeemer_pt_ata, token_program, yield_token_program, atoken_program, system_program, ..] =
+            accounts
+        else {
+            return Err(ProgramError::NotEnoughAccountKeys);
+        };
+
+        check_signer(redeemer)?;
+        let config_data = load_config(program_id, config)?;
+        let yield_tokenizer_data = load_yield_tokenizer(program_id, yield_tokenizer)?;
+        check_lsu(lsu_mint, lsu_exchange_rate_account, &yield_tokenizer_data)?;
+        check_address(
+            pt_mint,
+            &yield_tokenizer_data.pt,
+            YieldTokenizerError::InvalidPrincipalToken,
+        )?;
+        check_vault(lsu_vault, &yield_tokenizer_data)?;
+        check_treasury(program_id, treasury, yield_tokenizer, &yield_tokenizer_data)?;
+        check_program(token_program, &spl_token::id())?;
+        check_yield_token_program(yield_token_program, pt_mint)?;
+        check_token_account(
+            redeemer_lsu_ata,
+            redeemer.key,
+            lsu_mint,
+            YieldTokenizerError::LSUTokenAccountMismatch,
+        )?;
+        check_token_account(
+            redeemer_pt_ata,
+            redeemer.key,
+            pt_mint,
+            YieldTokenizerError::InvalidPrincipalToken,
+        )?;
+        for account in [redeemer, pt_mint, redeemer_lsu_ata, redeemer_pt_ata] {
+            check_writable(account)?;
+        }
+        check_program(atoken_program, &spl_associated_token_account::id())?;
+        check_program(system_program, &system_program::id())?;
+
+        Ok(Self {
+            redeemer,
+            config,
+            yield_tokenizer,
+            lsu_mint,
+            lsu_exchange_rate_account,
+            pt_mint,
+            lsu_vault,
+            treasury,
+            redeemer_lsu_ata,
+            redeemer_pt_ata,
+            token_program,
+            yield_token_program,
+            atoken_program,
+            system_program,
+            config_data,
+            yield_tokenizer_data,
+        })
+    }
+}
+
+/// Accounts of `ClaimYield`
+pub struct ClaimYieldAccounts<'a, 'info> {
+    pub claimer: &'a AccountInfo<'info>,
+    pub config: &'a AccountInfo<'info>,
+    pub yield_tokenizer: &'a AccountInfo<'info>,
+    pub lsu_mint: &'a AccountInfo<'info>,
+    pub lsu_exchange_rate_account: &'a AccountInfo<'info>,
+    pub yt_mint: &'a AccountInfo<'info>,
+    pub lsu_vault: &'a AccountInfo<'info>,
+    pub treasury: &'a AccountInfo<'info>,
+    pub claimer_lsu_ata: &'a AccountInfo<'info>,
+    pub claimer_yt_ata: &'a AccountInfo<'info>,
+    pub claimer_position_account: &'a AccountInfo<'info>,
+    pub token_program: &'a AccountInfo<'info>,
+    pub atoken_program: &'a AccountInfo<'info>,
+    pub system_program: &'a AccountInfo<'info>,
+    pub config_data: ProtocolConfig,
+    pub yield_tokenizer_data: YieldTokenizerState,
+}
+
+impl<'a, 'info> TryFrom<(&'a Pubkey, &'a [AccountInfo<'info>])> for ClaimYieldAccounts<'a, 'info> {
+    type Error = ProgramError;
+
+    fn try_from(
+        (program_id, accounts): (&'a Pubkey, &'a [AccountInfo<'info>]),
+    ) -> Result<Self, Self::Error> {
+        let [claimer, config, yield_tokenizer, lsu_mint, lsu_exchange_rate_account, yt_mint, lsu_vault, treasury, claimer_lsu_ata, claimer_yt_ata, claimer_position_account, token_program, atoken_program, system_program, ..] =
+            accounts
+        else {
+            return Err(ProgramError::NotEnoughAccountKeys);
+        };
+
+        check_signer(claimer)?;
+        let config_data = load_config(program_id, config)?;
+        let yield_tokenizer_data = load_yield_tokenizer(program_id, yield_tokenizer)?;
+        check_lsu(lsu_mint, lsu_exchange_rate_account, &yield_tokenizer_data)?;
+        check_address(
+            yt_mint,
+            &yield_tokenizer_data.yt,
+            YieldTokenizerError::InvalidYieldToken,
+        )?;
+        check_vault(lsu_vault, &yield_tokenizer_data)?;
+        check_treasury(program_id, treasury, yield_tokenizer, &yield_tokenizer_data)?;
+        check_program(token_program, &spl_token::id())?;
+        check_token_account(
+            claimer_lsu_ata,
+            claimer.key,
+            lsu_mint,
+            YieldTokenizerError::LSUTokenAccountMismatch,
+        )?;
+        // YT only has its balance read, so the YT token program isn't passed
+        check_token_account(
+            claimer_yt_ata,
+            claimer.key,
+            yt_mint,
+            YieldTokenizerError::InvalidYieldToken,
+        )?;
+        for account in [claimer, claimer_lsu_ata, claimer_position_account] {
+            check_writable(account)?;
+        }
+        check_program(atoken_program, &spl_associated_token_account::id())?;
+        check_program(system_program, &system_program::id())?;
+
+        Ok(Self {
+            claimer,
+            config,
+            yield_tokenizer,
+            lsu_mint,
+            lsu_exchange_rate_account,
+            yt_mint,
+            lsu_vault,
+            treasury,
+            claimer_lsu_ata,
+            claimer_yt_ata,
+            claimer_position_account,
+            token_program,
+            atoken_program,
+            system_program,
+            config_data,
+            yield_tokenizer_data,
+        })
+    }
+}
+
+/// Accounts of `TransferYt`
+pub struct TransferYtAccounts<'a, 'info> {
+    pub sender: &'a AccountInfo<'info>,
+    pub config: &'a AccountInfo<'info>,
+    pub yield_tokenizer: &'a AccountInfo<'info>,
+    pub lsu_mint: &'a AccountInfo<'info>,
+    pub lsu_exchange_rate_account: &'a AccountInfo<'info>,
+    pub yt_mint: &'a AccountInfo<'info>,
+    pub sender_yt_ata: &'a AccountInfo<'info>,
+    pub sender_position_account: &'a AccountInfo<'info>,
+    pub recipient: &'a AccountInfo<'info>,
+    pub recipient_yt_ata: &'a AccountInfo<'info>,
+    pub recipient_position_account: &'a AccountInfo<'info>,
+    pub token_program: &'a AccountInfo<'info>,
+    pub atoken_program: &'a AccountInfo<'info>,
+    pub system_program: &'a AccountInfo<'info>,
+    pub config_data: ProtocolConfig,
+    pub yield_tokenizer_data: YieldTokenizerState,
+}
+
+impl<'a, 'info> TryFrom<(&'a Pubkey, &'a [AccountInfo<'info>])> for TransferYtAccounts<'a, 'info> {
+    type Error = ProgramError;
+
+    fn try_from(
+        (program_id, accounts): (&'a Pubkey, &'a [AccountInfo<'info>]),
+    ) -> Result<Self, Self::Error> {
+        let [sender, config, yield_tokenizer, lsu_mint, lsu_exchange_rate_account, yt_mint, sender_yt_ata, sender_position_account, recipient, recipient_yt_ata, recipient_position_account, token_program, atoken_program, system_program, ..] =
+            accounts
+        else {
+            return Err(ProgramError::NotEnoughAccountKeys);
+        };
+
+        check_signer(sender)?;
+        let config_data = load_config(program_id, config)?;
+        let yield_tokenizer_data = load_yield_tokenizer(program_id, yield_tokenizer)?;
+        check_lsu(lsu_mint, lsu_exchange_rate_account, &yield_tokenizer_data)?;
+        check_address(
+            yt_mint,
+            &yield_tokenizer_data.yt,
+            YieldTokenizerError::InvalidYieldToken,
+        )?;
+        check_yield_token_program(token_program, yt_mint)?;
+        check_token_account(
+            sender_yt_ata,
+            sender.key,
+            yt_mint,
+            YieldTokenizerError::InvalidYieldToken,
+        )?;
+        check_token_account(
+            recipient_yt_ata,
+            recipient.key,
+            yt_mint,
+            YieldTokenizerError::InvalidYieldToken,
+        )?;
+        for account in [
+            sender,
+            sender_yt_ata,
+            sender_position_account,
+            recipient_yt_ata,
+            recipient_position_account,
+        ] {
+            check_writable(account)?;
+        }
+        check_program(atoken_program, &spl_associated_token_account::id())?;
+        check_program(system_program, &system_program::id())?;
+
+        Ok(Self {
+            sender,
+            config,
+            yield_tokenizer,
+            lsu_mint,
+            lsu_exchange_rate_account,
+            yt_mint,
+            sender_yt_ata,
+            sender_position_account,
+            recipient,
+            recipient_yt_ata,
+            recipient_position_account,
+            token_program,
+            atoken_program,
+            system_program,
+            config_data,
+            yield_tokenizer_data,
+        })
+    }
+}
+
+/// Accounts of `InitializeConfig`
+pub struct InitializeConfigAccounts<'a, 'info> {
+    pub authority: &'a AccountInfo<'info>,
+    pub config: &'a AccountInfo<'info>,
+    pub program_data: &'a AccountInfo<'info>,
+    pub system_program: &'a AccountInfo<'info>,
+    pub config_bump: u8,
+}
+
+impl<'a, 'info> TryFrom<(&'a Pubkey, &'a [AccountInfo<'info>])>
+    for InitializeConfigAccounts<'a, 'info>
+{
+    type Error = ProgramError;
+
+    fn try_from(
+        (program_id, accounts): (&'a Pubkey, &'a [AccountInfo<'info>]),
+    ) -> Result<Self, Self::Error> {
+        let [authority, config, program_data, system_program, ..] = accounts else {
+            return Err(ProgramError::NotEnoughAccountKeys);
+        };
+
+        // Only the program's upgrade authority can claim the config
+        if !authority.is_signer
+            || get_upgrade_authority(program_id, program_data)? != Some(*authority.key)
+        {
+            return Err(YieldTokenizerError::InvalidAuthority.into());
+        }
+        check_writable(authority)?;
+        let (config_addr, config_bump) = find_config_address(program_id);
+        check_address(
+            config,
+            &config_addr,
+            YieldTokenizerError::InvalidConfigAddress,
+        )?;
+        if config.owner == program_id {
+            return Err(ProgramError::AccountAlreadyInitialized);
+        }
+        check_writable(config)?;
+        check_program(system_program, &system_program::id())?;
+
+        Ok(Self {
+            authority,
+            config,
+            program_data,
+            system_program,
+            config_bump,
+        })
+    }
+}
+
+/// Accounts of `AddLsu`
+pub struct AddLsuAccounts<'a, 'info> {
+    pub authority: &'a AccountInfo<'info>,
+    pub config: &'a AccountInfo<'info>,
+    pub lsu_mint: &'a AccountInfo<'info>,
+    pub lsu_exchange_rate_account: &'a AccountInfo<'info>,
+    pub config_data: ProtocolConfig,
+}
+
+impl<'a, 'info> TryFrom<(&'a Pubkey, &'a [AccountInfo<'info>])> for AddLsuAccounts<'a, 'info> {
+    type Error = ProgramError;
+
+    fn try_from(
+        (program_id, accounts): (&'a Pubkey, &'a [AccountInfo<'info>]),
+    ) -> Result<Self, Self::Error> {
+        let [authority, config, lsu_mint, lsu_exchange_rate_account, ..] = accounts else {
+            return Err(ProgramError::NotEnoughAccountKeys);
+        };
+
+        let config_data = load_config(program_id, config)?;
+        check_authority(authority, &config_data)?;
+        check_writable(config)?;
+
+        Ok(Self {
+            authority,
+            config,
+            lsu_mint,
+            lsu_exchange_rate_account,
+            config_data,
+        })
+    }
+}
+
+/// Accounts of `RemoveLsu`
+pub struct RemoveLsuAccounts<'a, 'info> {
+    pub authority: &'a AccountInfo<'info>,
+    pub config: &'a AccountInfo<'info>,
+    pub lsu_mint: &'a AccountInfo<'info>,
+    pub config_data: ProtocolConfig,
+}
+
+impl<'a, 'info> TryFrom<(&'a Pubkey, &'a [AccountInfo<'info>])> for RemoveLsuAccounts<'a, 'info> {
+    type Error = ProgramError;
+
+    fn try_from(
+        (program_id, accounts): (&'a Pubkey, &'a [AccountInfo<'info>]),
+    ) -> Result<Self, Self::Error> {
+        let [authority, config, lsu_mint, ..] = accounts else {
+            return Err(ProgramError::NotEnoughAccountKeys);
+        };
+
+        let config_data = load_config(program_id, config)?;
+        check_authority(authority, &config_data)?;
+        check_writable(config)?;
+
+        Ok(Self {
+            authority,
+            config,
+            lsu_mint,
+            config_data,
+        })
+    }
+}
+
+/// Accounts of `SetAuthority`, `SetFees` and `SetPause`, which only write the config
+pub struct UpdateConfigAccounts<'a, 'info> {
+    pub authority: &'a AccountInfo<'info>,
+    pub config: &'a AccountInfo<'info>,
+    pub config_data: ProtocolConfig,
+}
+
+impl<'a, 'info> TryFrom<(&'a Pubkey, &'a [AccountInfo<'info>])>
+    for UpdateConfigAccounts<'a, 'info>
+{
+    type Error = ProgramError;
+
+    fn try_from(
+        (program_id, accounts): (&'a Pubkey, &'a [AccountInfo<'info>]),
+    ) -> Result<Self, Self::Error> {
+        let [authority, config, ..] = accounts else {
+            return Err(ProgramError::NotEnoughAccountKeys);
+        };
+
+        let config_data = load_config(program_id, config)?;
+        check_authority(authority, &config_data)?;
+        check_writable(config)?;
+
+        Ok(Self {
+            authority,
+            config,
+            config_data,
+        })
+    }
+}
+
+/// Accounts of `WithdrawFees`
+pub struct WithdrawFeesAccounts<'a, 'info> {
+    pub authority: &'a AccountInfo<'info>,
+    pub config: &'a AccountInfo<'info>,
+    pub yield_tokenizer: &'a AccountInfo<'info>,
+    pub treasury: &'a AccountInfo<'info>,
+    pub destination: &'a AccountInfo<'info>,
+    pub token_program: &'a AccountInfo<'info>,
+    pub config_data: ProtocolConfig,
+    pub yield_tokenizer_data: YieldTokenizerState,
+}
+
+impl<'a, 'info> TryFrom<(&'a Pubkey, &'a [AccountInfo<'info>])>
+    for WithdrawFeesAccounts<'a, 'info>
+{
+    type Error = ProgramError;
+
+    fn try_from(
+        (program_id, accounts): (&'a Pubkey, &'a [AccountInfo<'info>]),
+    ) -> Result<Self, Self::Error> {
+        let [authority, config, yield_tokenizer, treasury, destination, token_program, ..] =
+            accounts
+        else {
+            return Err(ProgramError::NotEnoughAccountKeys);
+        };
+
+        let config_data = load_config(program_id, config)?;
+        check_authority(authority, &config_data)?;
+        let yield_tokenizer_data = read_yield_tokenizer(program_id, yield_tokenizer)?;
+        check_treasury(program_id, treasury, yield_tokenizer, &yield_tokenizer_data)?;
+        check_writable(destination)?;
+        check_program(token_program, &spl_token::id())?;
+
+        Ok(Self {
+            authority,
+            config,
+            yield_tokenizer,
+            treasury,
+            destination,
+            token_program,
+            config_data,
+            yield_tokenizer_data,
+        })
+    }
+}
+
+/// Accounts of `SetTokenizerPause`
+pub struct SetTokenizerPauseAccounts<'a, 'info> {
+    pub authority: &'a AccountInfo<'info>,
+    pub config: &'a AccountInfo<'info>,
+    pub yield_tokenizer: &'a AccountInfo<'info>,
+    pub config_data: ProtocolConfig,
+    pub yield_tokenizer_data: YieldTokenizerState,
+}
+
+impl<'a, 'info> TryFrom<(&'a Pubkey, &'a [AccountInfo<'info>])>
+    for SetTokenizerPauseAccounts<'a, 'info>
+{
+    type Error = ProgramError;
+
+    fn try_from(
+        (program_id, accounts): (&'a Pubkey, &'a [AccountInfo<'info>]),
+    ) -> Result<Self, Self::Error> {
+        let [authority, config, yield_tokenizer, ..] = accounts else {
+            return Err(ProgramError::NotEnoughAccountKeys);
+        };
+
+        let config_data = load_config(program_id, config)?;
+        check_authority(authority, &config_data)?;
+        let yield_tokenizer_data = load_yield_tokenizer(program_id, yield_tokenizer)?;
+
+        Ok(Self {
+            authority,
+            config,
+            yield_tokenizer,
+            config_data,
+            yield_tokenizer_data,
+        })
+    }
+}
+
+/// Accounts of `InitializePool`. The pool and LP mint PDAs are checked here, their bumps are
+/// returned for creating them.
+pub struct InitializePoolAccounts<'a, 'info> {
+    pub authority: &'a AccountInfo<'info>,
+    pub config: &'a AccountInfo<'info>,
+    pub yield_tokenizer: &'a AccountInfo<'info>,
+    pub lsu_mint: &'a AccountInfo<'info>,
+    pub pt_mint: &'a AccountInfo<'info>,
+    pub pool: &'a AccountInfo<'info>,
+    pub lp_mint: &'a AccountInfo<'info>,
+    pub pool_lsu_vault: &'a AccountInfo<'info>,
+    pub pool_pt_vault: &'a AccountInfo<'info>,
+    pub token_program: &'a AccountInfo<'info>,
+    pub yield_token_program: &'a AccountInfo<'info>,
+    pub atoken_program: &'a AccountInfo<'info>,
+    pub system_program: &'a AccountInfo<'info>,
+    pub config_data: ProtocolConfig,
+    pub yield_tokenizer_data: YieldTokenizerState,
+    pub pool_bump: u8,
+    pub lp_mint_bump: u8,
+}
+
+impl<'a, 'info> TryFrom<(&'a Pubkey, &'a [AccountInfo<'info>])>
+    for InitializePoolAccounts<'a, 'info>
+{
+    type Error = ProgramError;
+
+    fn try_from(
+        (program_id, accounts): (&'a Pubkey, &'a [AccountInfo<'info>]),
+    ) -> Result<Self, Self::Error> {
+        let [authority, config, yield_tokenizer, lsu_mint, pt_mint, pool, lp_mint, pool_lsu_vault, pool_pt_vault, token_program, yield_token_program, atoken_program, system_program, ..] =
+            accounts
+        else {
+            return Err(ProgramError::NotEnoughAccountKeys);
+        };
+
+        let config_data = load_config(program_id, config)?;
+        check_authority(authority, &config_data)?;
+        let yield_tokenizer_data = read_yield_tokenizer(program_id, yield_tokenizer)?;
+        check_address(
+            lsu_mint,
+            &yield_tokenizer_data.lsu_mint,
+            YieldTokenizerError::InvalidLSU,
+        )?;
+        check_address(
+            pt_mint,
+            &yield_tokenizer_data.pt,
+            YieldTokenizerError::InvalidPrincipalToken,
+        )?;
+        let (pool_addr, pool_bump) = find_pool_address(program_id, yield_tokenizer.key);
+        check_address(pool, &pool_addr, YieldTokenizerError::InvalidPoolAddress)?;
+        if pool.owner == program_id {
+            return Err(ProgramError::AccountAlreadyInitialized);
+        }
+        let (lp_mint_addr, lp_mint_bump) = find_lp_mint_address(program_id, pool.key);
+        check_address(lp_mint, &lp_mint_addr, YieldTokenizerError::InvalidLPToken)?;
+        check_program(token_program, &spl_token::id())?;
+        check_yield_token_program(yield_token_program, pt_mint)?;
+        check_address(
+            pool_lsu_vault,
+            &get_associated_token_address(pool.key, lsu_mint.key),
+            YieldTokenizerError::InvalidVault,
+        )?;
+        check_address(
+            pool_pt_vault,
+            &get_associated_token_address_with_program_id(
+                pool.key,
+                pt_mint.key,
+                yield_token_program.key,
+            ),
+            YieldTokenizerError::InvalidVault,
+        )?;
+        for account in [authority, pool, lp_mint, pool_lsu_vault, pool_pt_vault] {
+            check_writable(account)?;
+        }
+        check_program(atoken_program, &spl_associated_token_account::id())?;
+        check_program(system_program, &system_program::id())?;
+
+        Ok(Self {
+            authority,
+            config,
+            yield_tokenizer,
+            lsu_mint,
+            pt_mint,
+            pool,
+            lp_mint,
+            pool_lsu_vault,
+            pool_pt_vault,
+            token_program,
+            yield_token_program,
+            atoken_program,
+            system_program,
+            config_data,
+            yield_tokenizer_data,
+            pool_bump,
+            lp_mint_bump,
+        })
+    }
+}
+
+/// Accounts of `AddLiquidity`
+pub struct AddLiquidityAccounts<'a, 'info> {
+    pub provider: &'a AccountInfo<'info>,
+    pub config: &'a AccountInfo<'info>,
+    pub yield_tokenizer: &'a AccountInfo<'info>,
+    pub lsu_mint: &'a AccountInfo<'info>,
+    pub lsu_exchange_rate_account: &'a AccountInfo<'info>,
+    pub pt_mint: &'a AccountInfo<'info>,
+    pub pool: &'a AccountInfo<'info>,
+    pub lp_mint: &'a AccountInfo<'info>,
+    pub pool_lsu_vault: &'a AccountInfo<'info>,
+    pub pool_pt_vault: &'a AccountInfo<'info>,
+    pub provider_lsu_ata: &'a AccountInfo<'info>,
+    pub provider_pt_ata: &'a AccountInfo<'info>,
+    pub provider_lp_ata: &'a AccountInfo<'info>,
+    pub token_program: &'a AccountInfo<'info>,
+    pub yield_token_program: &'a AccountInfo<'info>,
+    pub atoken_program: &'a AccountInfo<'info>,
+    pub system_program: &'a AccountInfo<'info>,
+    pub config_data: ProtocolConfig,
+    pub yield_tokenizer_data: YieldTokenizerState,
+    pub pool_data: PtPool,
+    pub pool_bump: u8,
+}
+
+impl<'a, 'info> TryFrom<(&'a Pubkey, &'a [AccountInfo<'info>])>
+    for AddLiquidityAccounts<'a, 'info>
+{
+    type Error = ProgramError;
+
+    fn try_from(
+        (program_id, accounts): (&'a Pubkey, &'a [AccountInfo<'info>]),
+    ) -> Result<Self, Self::Error> {
+        let [provider, config, yield_tokenizer, lsu_mint, lsu_exchange_rate_account, pt_mint, pool, lp_mint, pool_lsu_vault, pool_pt_vault, provider_lsu_ata, provider_pt_ata, provider_lp_ata, token_program, yield_token_program, atoken_program, system_program, ..] =
+            accounts
+        else {
+            return Err(ProgramError::NotEnoughAccountKeys);
+        };
+
+        check_signer(provider)?;
+        let config_data = load_config(program_id, config)?;
+        let yield_tokenizer_data = load_yield_tokenizer(program_id, yield_tokenizer)?;
+        check_lsu(lsu_mint, lsu_exchange_rate_account, &yield_tokenizer_data)?;
+        check_address(
+            pt_mint,
+            &yield_tokenizer_data.pt,
+            YieldTokenizerError::InvalidPrincipalToken,
+        )?;
+        let (pool_data, pool_bump) = load_pool(
+            program_id,
+            pool,
+            yield_tokenizer,
+            pool_lsu_vault,
+            pool_pt_vault,
+        )?;
+        check_address(
+            lp_mint,
+            &pool_data.lp_mint,
+            YieldTokenizerError::InvalidLPToken,
+        )?;
+        check_program(token_program, &spl_token::id())?;
+        check_yield_token_program(yield_token_program, pt_mint)?;
+        check_provider_atas(
+            provider,
+            lsu_mint,
+            pt_mint,
+            lp_mint,
+            provider_lsu_ata,
+            provider_pt_ata,
+            provider_lp_ata,
+        )?;
+        check_writable(lp_mint)?;
+        check_program(atoken_program, &spl_associated_token_account::id())?;
+        check_program(system_program, &system_program::id())?;
+
+        Ok(Self {
+            provider,
+            config,
+            yield_tokenizer,
+            lsu_mint,
+            lsu_exchange_rate_account,
+            pt_mint,
+            pool,
+            lp_mint,
+            pool_lsu_vault,
+            pool_pt_vault,
+            provider_lsu_ata,
+            provider_pt_ata,
+            provider_lp_ata,
+            token_program,
+            yield_token_program,
+            atoken_program,
+            system_program,
+            config_data,
+            yield_tokenizer_data,
+            pool_data,
+            pool_bump,
+        })
+    }
+}
+
+/// Accounts of `RemoveLiquidity`
+pub struct RemoveLiquidityAccounts<'a, 'info> {
+    pub provider: &'a AccountInfo<'info>,
+    pub config: &'a AccountInfo<'info>,
+    pub yield_tokenizer: &'a AccountInfo<'info>,
+    pub lsu_mint: &'a AccountInfo<'info>,
+    pub pt_mint: &'a AccountInfo<'info>,
+    pub pool: &'a AccountInfo<'info>,
+    pub lp_mint: &'a AccountInfo<'info>,
+    pub pool_lsu_vault: &'a AccountInfo<'info>,
+    pub pool_pt_vault: &'a AccountInfo<'info>,
+    pub provider_lsu_ata: &'a AccountInfo<'info>,
+    pub provider_pt_ata: &'a AccountInfo<'info>,
+    pub provider_lp_ata: &'a AccountInfo<'info>,
+    pub token_program: &'a AccountInfo<'info>,
+    pub yield_token_program: &'a AccountInfo<'info>,
+    pub config_data: ProtocolConfig,
+    pub yield_tokenizer_data: YieldTokenizerState,
+    pub pool_data: PtPool,
+    pub pool_bump: u8,
+}
+
+impl<'a, 'info> TryFrom<(&'a Pubkey, &'a [AccountInfo<'info>])>
+    for RemoveLiquidityAccounts<'a, 'info>
+{
+    type Error = ProgramError;
+
+    fn try_from(
+        (program_id, accounts): (&'a Pubkey, &'a [AccountInfo<'info>]),
+    ) -> Result<Self, Self::Error> {
+        let [provider, config, yield_tokenizer, lsu_mint, pt_mint, pool, lp_mint, pool_lsu_vault, pool_pt_vault, provider_lsu_ata, provider_pt_ata, provider_lp_ata, token_program, yield_token_program, ..] =
+            accounts
+        else {
+            return Err(ProgramError::NotEnoughAccountKeys);
+        };
+
+        check_signer(provider)?;
+        let config_data = load_config(program_id, config)?;
+        // Withdrawing doesn't observe the exchange rate, the tokenizer is only read
+        let yield_tokenizer_data = read_yield_tokenizer(program_id, yield_tokenizer)?;
+        check_address(
+            lsu_mint,
+            &yield_tokenizer_data.lsu_mint,
+            YieldTokenizerError::InvalidLSU,
+        )?;
+        check_address(
+            pt_mint,
+            &yield_tokenizer_data.pt,
+            YieldTokenizerError::InvalidPrincipalToken,
+        )?;
+        let (pool_data, pool_bump) = load_pool(
+            program_id,
+            pool,
+            yield_tokenizer,
+            pool_lsu_vault,
+            pool_pt_vault,
+        )?;
+        check_address(
+            lp_mint,
+            &pool_data.lp_mint,
+            YieldTokenizerError::InvalidLPToken,
+        )?;
+        check_program(token_program, &spl_token::id())?;
+        check_yield_token_program(yield_token_program, pt_mint)?;
+        check_provider_atas(
+            provider,
+            lsu_mint,
+            pt_mint,
+            lp_mint,
+            provider_lsu_ata,
+            provider_pt_ata,
+            provider_lp_ata,
+        )?;
+        check_writable(lp_mint)?;
+
+        Ok(Self {
+            provider,
+            config,
+            yield_tokenizer,
+            lsu_mint,
+            pt_mint,
+            pool,
+            lp_mint,
+            pool_lsu_vault,
+            pool_pt_vault,
+            provider_lsu_ata,
+            provider_pt_ata,
+            provider_lp_ata,
+            token_program,
+            yield_token_program,
+            config_data,
+            yield_tokenizer_data,
+            pool_data,
+            pool_bump,
+        })
+    }
+}
+
+/// Accounts of `SwapExactLsuForPt` and `SwapExactPtForLsu`
+pub struct SwapPtAccounts<'a, 'info> {
+    pub trader: &'a AccountInfo<'info>,
+    pub config: &'a AccountInfo<'info>,
+    pub yield_tokenizer: &'a AccountInfo<'info>,
+    pub lsu_mint: &'a AccountInfo<'info>,
+    pub lsu_exchange_rate_account: &'a AccountInfo<'info>,
+    pub pt_mint: &'a AccountInfo<'info>,
+    pub pool: &'a AccountInfo<'info>,
+    pub pool_lsu_vault: &'a AccountInfo<'info>,
+    pub pool_pt_vault: &'a AccountInfo<'info>,
+    pub trader_lsu_ata: &'a AccountInfo<'info>,
+    pub trader_pt_ata: &'a AccountInfo<'info>,
+    pub token_program: &'a AccountInfo<'info>,
+    pub yield_token_program: &'a AccountInfo<'info>,
+    pub atoken_program: &'a AccountInfo<'info>,
+    pub system_program: &'a AccountInfo<'info>,
+    pub config_data: ProtocolConfig,
+    pub yield_tokenizer_data: YieldTokenizerState,
+    pub pool_data: PtPool,
+    pub pool_bump: u8,
+}
+
+impl<'a, 'info> TryFrom<(&'a Pubkey, &'a [AccountInfo<'info>])> for SwapPtAccounts<'a, 'info> {
+    type Error = ProgramError;
+
+    fn try_from(
+        (program_id, accounts): (&'a Pubkey, &'a [AccountInfo<'info>]),
+    ) -> Result<Self, Self::Error> {
+        let [trader, config, yield_tokenizer, lsu_mint, lsu_exchange_rate_account, pt_mint, pool, pool_lsu_vault, pool_pt_vault, trader_lsu_ata, trader_pt_ata, token_program, yield_token_program, atoken_program, system_program, ..] =
+            accounts
+        else {
+            return Err(ProgramError::NotEnoughAccountKeys);
+        };
+
+        check_signer(trader)?;
+        let config_data = load_config(program_id, config)?;
+        let yield_tokenizer_data = load_yield_tokenizer(program_id, yield_tokenizer)?;
+        check_lsu(lsu_mint, lsu_exchange_rate_account, &yield_tokenizer_data)?;
+        check_address(
+            pt_mint,
+            &yield_tokenizer_data.pt,
+            YieldTokenizerError::InvalidPrincipalToken,
+        )?;
+        let (pool_data, pool_bump) = load_pool(
+            program_id,
+            pool,
+            yield_tokenizer,
+            pool_lsu_vault,
+            pool_pt_vault,
+        )?;
+        check_program(token_program, &spl_token::id())?;
+        check_yield_token_program(yield_token_program, pt_mint)?;
+        check_token_account(
+            trader_lsu_ata,
+            trader.key,
+            lsu_mint,
+            YieldTokenizerError::LSUTokenAccountMismatch,
+        )?;
+        check_token_account(
+            trader_pt_ata,
+            trader.key,
+            pt_mint,
+            YieldTokenizerError::InvalidPrincipalToken,
+        )?;
+        for account in [trader, trader_lsu_ata, trader_pt_ata] {
+            check_writable(account)?;
+        }
+        check_program(atoken_program, &spl_associated_token_account::id())?;
+        check_program(system_program, &system_program::id())?;
+
+        Ok(Self {
+            trader,
+            config,
+            yield_tokenizer,
+            lsu_mint,
+            lsu_exchange_rate_account,
+            pt_mint,
+            pool,
+            pool_lsu_vault,
+            pool_pt_vault,
+            trader_lsu_ata,
+            trader_pt_ata,
+            token_program,
+            yield_token_program,
+            atoken_program,
+            system_program,
+            config_data,
+            yield_tokenizer_data,
+            pool_data,
+            pool_bump,
+        })
+    }
+}
+
+/// Accounts of `SwapExactLsuForYt`
+pub struct SwapExactLsuForYtAccounts<'a, 'info> {
+    pub trader: &'a AccountInfo<'info>,
+    pub config: &'a AccountInfo<'info>,
+    pub yield_tokenizer: &'a AccountInfo<'info>,
+    pub lsu_mint: &'a AccountInfo<'info>,
+    pub lsu_exchange_rate_account: &'a AccountInfo<'info>,
+    pub pt_mint: &'a AccountInfo<'info>,
+    pub yt_mint: &'a AccountInfo<'info>,
+    pub lsu_vault: &'a AccountInfo<'info>,
+    pub pool: &'a AccountInfo<'info>,
+    pub pool_lsu_vault: &'a AccountInfo<'info>,
+    pub pool_pt_vault: &'a AccountInfo<'info>,
+    pub trader_lsu_ata: &'a AccountInfo<'info>,
+    pub trader_yt_ata: &'a AccountInfo<'info>,
+    pub trader_position_account: &'a AccountInfo<'info>,
+    pub token_program: &'a AccountInfo<'info>,
+    pub yield_token_program: &'a AccountInfo<'info>,
+    pub atoken_program: &'a AccountInfo<'info>,
+    pub system_program: &'a AccountInfo<'info>,
+    pub config_data: ProtocolConfig,
+    pub yield_tokenizer_data: YieldTokenizerState,
+    pub pool_data: PtPool,
+    pub pool_bump: u8,
+}
+
+impl<'a, 'info> TryFrom<(&'a Pubkey, &'a [AccountInfo<'info>])>
+    for SwapExactLsuForYtAccounts<'a, 'info>
+{
+    type Error = ProgramError;
+
+    fn try_from(
+        (program_id, accounts): (&'a Pubkey, &'a [AccountInfo<'info>]),
+    ) -> Result<Self, Self::Error> {
+        let [trader, config, yield_tokenizer, lsu_mint, lsu_exchange_rate_account, pt_mint, yt_mint, lsu_vault, pool, pool_lsu_vault, pool_pt_vault, trader_lsu_ata, trader_yt_ata, trader_position_account, token_program, yield_token_program, atoken_program, system_program, ..] =
+            accounts
+        else {
+            return Err(ProgramError::NotEnoughAccountKeys);
+        };
+
+        check_signer(trader)?;
+        let config_data = load_config(program_id, config)?;
+        let yield_tokenizer_data = load_yield_tokenizer(program_id, yield_tokenizer)?;
+        check_lsu(lsu_mint, lsu_exchange_rate_account, &yield_tokenizer_data)?;
+        check_address(
+            pt_mint,
+            &yield_tokenizer_data.pt,
+            YieldTokenizerError::InvalidPrincipalToken,
+        )?;
+        check_address(
+            yt_mint,
+            &yield_tokenizer_data.yt,
+            YieldTokenizerError::InvalidYieldToken,
+        )?;
+        check_vault(lsu_vault, &yield_tokenizer_data)?;
+        let (pool_data, pool_bump) = load_pool(
+            program_id,
+            pool,
+            yield_tokenizer,
+            pool_lsu_vault,
+            pool_pt_vault,
+        )?;
+        check_program(token_program, &spl_token::id())?;
+        check_yield_token_program(yield_token_program, pt_mint)?;
+        check_token_account(
+            trader_lsu_ata,
+            trader.key,
+            lsu_mint,
+            YieldTokenizerError::LSUTokenAccountMismatch,
+        )?;
+        check_token_account(
+            trader_yt_ata,
+            trader.key,
+            yt_mint,
+            YieldTokenizerError::InvalidYieldToken,
+        )?;
+        for account in [
+            trader,
+            pt_mint,
+            yt_mint,
+            trader_lsu_ata,
+            trader_yt_ata,
+            trader_position_account,
+        ] {
+            check_writable(account)?;
+        }
+        check_program(atoken_program, &spl_associated_token_account::id())?;
+        check_program(system_program, &system_program::id())?;
+
+        Ok(Self {
+            trader,
+            config,
+            yield_tokenizer,
+            lsu_mint,
+            lsu_exchange_rate_account,
+            pt_mint,
+            yt_mint,
+            lsu_vault,
+            pool,
+            pool_lsu_vault,
+            pool_pt_vault,
+            trader_lsu_ata,
+            trader_yt_ata,
+            trader_position_account,
+            token_program,
+            yield_token_program,
+            atoken_program,
+            system_program,
+            config_data,
+            yield_tokenizer_data,
+            pool_data,
+            pool_bump,
+        })
+    }
+}
+
+/// Accounts of `SwapExactYtForLsu`
+pub struct SwapExactYtForLsuAccounts<'a, 'info> {
+    pub trader: &'a AccountInfo<'info>,
+    pub config: &'a AccountInfo<'info>,
+    pub yield_tokenizer: &'a AccountInfo<'info>,
+    pub lsu_mint: &'a AccountInfo<'info>,
+    pub lsu_exchange_rate_account: &'a AccountInfo<'info>,
+    pub pt_mint: &'a AccountInfo<'info>,
+    pub yt_mint: &'a AccountInfo<'info>,
+    pub lsu_vault: &'a AccountInfo<'info>,
+    pub treasury: &'a AccountInfo<'info>,
+    pub pool: &'a AccountInfo<'info>,
+    pub pool_lsu_vault: &'a AccountInfo<'info>,
+    pub pool_pt_vault: &'a AccountInfo<'info>,
+    pub trader_lsu_ata: &'a AccountInfo<'info>,
+    pub trader_yt_ata: &'a AccountInfo<'info>,
+    pub trader_position_account: &'a AccountInfo<'info>,
+    pub token_program: &'a AccountInfo<'info>,
+    pub yield_token_program: &'a AccountInfo<'info>,
+    pub atoken_program: &'a AccountInfo<'info>,
+    pub system_program: &'a AccountInfo<'info>,
+    pub config_data: ProtocolConfig,
+    pub yield_tokenizer_data: YieldTokenizerState,
+    pub pool_data: PtPool,
+    pub pool_bump: u8,
+}
+
+impl<'a, 'info> TryFrom<(&'a Pubkey, &'a [AccountInfo<'info>])>
+    for SwapExactYtForLsuAccounts<'a, 'info>
+{
+    type Error = ProgramError;
+
+    fn try_from(
+        (program_id, accounts): (&'a Pubkey, &'a [AccountInfo<'info>]),
+    ) -> Result<Self, Self::Error> {
+        let [trader, config, yield_tokenizer, lsu_mint, lsu_exchange_rate_account, pt_mint, yt_mint, lsu_vault, treasury, pool, pool_lsu_vault, pool_pt_vault, trader_lsu_ata, trader_yt_ata, trader_position_account, token_program, yield_token_program, atoken_program, system_program, ..] =
+            accounts
+        else {
+            return Err(ProgramError::NotEnoughAccountKeys);
+        };
+
+        check_signer(trader)?;
+        let config_data = load_config(program_id, config)?;
+        let yield_tokenizer_data = load_yield_tokenizer(program_id, yield_tokenizer)?;
+        check_lsu(lsu_mint, lsu_exchange_rate_account, &yield_tokenizer_data)?;
+        check_address(
+            pt_mint,
+            &yield_tokenizer_data.pt,
+            YieldTokenizerError::InvalidPrincipalToken,
+        )?;
+        check_address(
+            yt_mint,
+            &yield_tokenizer_data.yt,
+            YieldTokenizerError::InvalidYieldToken,
+        )?;
+        check_vault(lsu_vault, &yield_tokenizer_data)?;
+        check_treasury(program_id, treasury, yield_tokenizer, &yield_tokenizer_data)?;
+        let (pool_data, pool_bump) = load_pool(
+            program_id,
+            pool,
+            yield_tokenizer,
+            pool_lsu_vault,
+            pool_pt_vault,
+        )?;
+        check_program(token_program, &spl_token::id())?;
+        check_yield_token_program(yield_token_program, pt_mint)?;
+        check_token_account(
+            trader_lsu_ata,
+            trader.key,
+            lsu_mint,
+            YieldTokenizerError::LSUTokenAccountMismatch,
+        )?;
+        check_token_account(
+            trader_yt_ata,
+            trader.key,
+            yt_mint,
+            YieldTokenizerError::InvalidYieldToken,
+        )?;
+        for account in [
+            trader,
+            pt_mint,
+            yt_mint,
+            trader_lsu_ata,
+            trader_yt_ata,
+            trader_position_account,
+        ] {
+            check_writable(account)?;
+        }
+        check_program(atoken_program, &spl_associated_token_account::id())?;
+        check_program(system_program, &system_program::id())?;
+
+        Ok(Self {
+            trader,
+            config,
+            yield_tokenizer,
+            lsu_mint,
+            lsu_exchange_rate_account,
+            pt_mint,
+            yt_mint,
+            lsu_vault,
+            treasury,
+            pool,
+            pool_lsu_vault,
+            pool_pt_vault,
+            trader_lsu_ata,
+            trader_yt_ata,
+            trader_position_account,
+            token_program,
+            yield_token_program,
+            atoken_program,
+            system_program,
+            config_data,
+            yield_tokenizer_data,
+            pool_data,
+            pool_bump,
+        })
+    }
+}
+
+/// Accounts of `Rollover`, the matured series first and the one it rolls into second
+pub struct RolloverAccounts<'a, 'info> {
+    pub owner: &'a AccountInfo<'info>,
+    pub config: &'a AccountInfo<'info>,
+    pub lsu_mint: &'a AccountInfo<'info>,
+    pub matured_yield_tokenizer: &'a AccountInfo<'info>,
+    pub matured_lsu_exchange_rate_account: &'a AccountInfo<'info>,
+    pub matured_pt_mint: &'a AccountInfo<'info>,
+    pub matured_yt_mint: &'a AccountInfo<'info>,
+    pub matured_lsu_vault: &'a AccountInfo<'info>,
+    pub matured_treasury: &'a AccountInfo<'info>,
+    pub owner_matured_pt_ata: &'a AccountInfo<'info>,
+    pub owner_matured_yt_ata: &'a AccountInfo<'info>,
+    pub owner_matured_position_account: &'a AccountInfo<'info>,
+    pub yield_tokenizer: &'a AccountInfo<'info>,
+    pub lsu_exchange_rate_account: &'a AccountInfo<'info>,
+    pub pt_mint: &'a AccountInfo<'info>,
+    pub yt_mint: &'a AccountInfo<'info>,
+    pub lsu_vault: &'a AccountInfo<'info>,
+    pub owner_pt_ata: &'a AccountInfo<'info>,
+    pub owner_yt_ata: &'a AccountInfo<'info>,
+    pub owner_position_account: &'a AccountInfo<'info>,
+    pub token_program: &'a AccountInfo<'info>,
+    pub matured_yield_token_program: &'a AccountInfo<'info>,
+    pub yield_token_program: &'a AccountInfo<'info>,
+    pub atoken_program: &'a AccountInfo<'info>,
+    pub system_program: &'a AccountInfo<'info>,
+    pub config_data: ProtocolConfig,
+    pub matured_data: YieldTokenizerState,
+    pub yield_tokenizer_data: YieldTokenizerState,
+}
+
+impl<'a, 'info> TryFrom<(&'a Pubkey, &'a [AccountInfo<'info>])> for RolloverAccounts<'a, 'info> {
+    type Error = ProgramError;
+
+    fn try_from(
+        (program_id, accounts): (&'a Pubkey, &'a [AccountInfo<'info>]),
+    ) -> Result<Self, Self::Error> {
+        let [owner, config, lsu_mint, matured_yield_tokenizer, matured_lsu_exchange_rate_account, matured_pt_mint, matured_yt_mint, matured_lsu_vault, matured_treasury, owner_matured_pt_ata, owner_matured_yt_ata, owner_matured_position_account, yield_tokenizer, lsu_exchange_rate_account, pt_mint, yt_mint, lsu_vault, owner_pt_ata, owner_yt_ata, owner_position_account, token_program, matured_yield_token_program, yield_token_program, atoken_program, system_program, ..] =
+            accounts
+        else {
+            return Err(ProgramError::NotEnoughAccountKeys);
+        };
+
+        check_signer(owner)?;
+        let config_data = load_config(program_id, config)?;
+        let matured_data = load_yield_tokenizer(program_id, matured_yield_tokenizer)?;
+        let yield_tokenizer_data = load_yield_tokenizer(program_id, yield_tokenizer)?;
+        check_program(token_program, &spl_token::id())?;
+
+        // Both series have to be for the LSU passed in, so LSU moves straight between the vaults
+        for (
+            yield_tokenizer_data,
+            lsu_exchange_rate_account,
+            pt_mint,
+            yt_mint,
+            lsu_vault,
+            owner_pt_ata,
+            owner_yt_ata,
+            yield_token_program,
+        ) in [
+            (
+                &matured_data,
+                matured_lsu_exchange_rate_account,
+                matured_pt_mint,
+                matured_yt_mint,
+                matured_lsu_vault,
+                owner_matured_pt_ata,
+                owner_matured_yt_ata,
+                matured_yield_token_program,
+            ),
+            (
+                &yield_tokenizer_data,
+                lsu_exchange_rate_account,
+                pt_mint,
+                yt_mint,
+                lsu_vault,
+                owner_pt_ata,
+                owner_yt_ata,
+                yield_token_program,
+            ),
+        ] {
+            check_lsu(lsu_mint, lsu_exchange_rate_account, yield_tokenizer_data)?;
+            check_address(
+                pt_mint,
+                &yield_tokenizer_data.pt,
+                YieldTokenizerError::InvalidPrincipalToken,
+            )?;
+            check_address(
+                yt_mint,
+                &yield_tokenizer_data.yt,
+                YieldTokenizerError::InvalidYieldToken,
+            )?;
+            check_vault(lsu_vault, yield_tokenizer_data)?;
+            check_yield_token_program(yield_token_program, pt_mint)?;
+            check_token_account(
+                owner_pt_ata,
+                owner.key,
+                pt_mint,
+                YieldTokenizerError::InvalidPrincipalToken,
+            )?;
+            check_token_account(
+                owner_yt_ata,
+                owner.key,
+                yt_mint,
+                YieldTokenizerError::InvalidYieldToken,
+            )?;
+        }
+        check_treasury(
+            program_id,
+            matured_treasury,
+            matured_yield_tokenizer,
+            &matured_data,
+        )?;
+        for account in [
+            owner,
+            matured_pt_mint,
+            matured_yt_mint,
+            owner_matured_pt_ata,
+            owner_matured_yt_ata,
+            owner_matured_position_account,
+            pt_mint,
+            yt_mint,
+            owner_pt_ata,
+            owner_yt_ata,
+            owner_position_account,
+        ] {
+            check_writable(account)?;
+        }
+        check_program(atoken_program, &spl_associated_token_account::id())?;
+        check_program(system_program, &system_program::id())?;
+
+        Ok(Self {
+            owner,
+            config,
+            lsu_mint,
+            matured_yield_tokenizer,
+            matured_lsu_exchange_rate_account,
+            matured_pt_mint,
+            matured_yt_mint,
+            matured_lsu_vault,
+            matured_treasury,
+            owner_matured_pt_ata,
+            owner_matured_yt_ata,
+            owner_matured_position_account,
+            yield_tokenizer,
+            lsu_exchange_rate_account,
+            pt_mint,
+            yt_mint,
+            lsu_vault,
+            owner_pt_ata,
+            owner_yt_ata,
+            owner_position_account,
+            token_program,
+            matured_yield_token_program,
+            yield_token_program,
+            atoken_program,
+            system_program,
+            config_data,
+            matured_data,
+            yield_tokenizer_data,
+        })
+    }
+}
+
+/// Accounts of `CloseYieldTokenizer`
+pub struct CloseYieldTokenizerAccounts<'a, 'info> {
+    pub authority: &'a AccountInfo<'info>,
+    pub config: &'a AccountInfo<'info>,
+    pub yield_tokenizer: &'a AccountInfo<'info>,
+    pub initializer: &'a AccountInfo<'info>,
+    pub pt_mint: &'a AccountInfo<'info>,
+    pub yt_mint: &'a AccountInfo<'info>,
+    pub lsu_vault: &'a AccountInfo<'info>,
+    pub treasury: &'a AccountInfo<'info>,
+    pub destination: &'a AccountInfo<'info>,
+    pub token_program: &'a AccountInfo<'info>,
+    pub yield_token_program: &'a AccountInfo<'info>,
+    pub config_data: ProtocolConfig,
+    pub yield_tokenizer_data: YieldTokenizerState,
+}
+
+impl<'a, 'info> TryFrom<(&'a Pubkey, &'a [AccountInfo<'info>])>
+    for CloseYieldTokenizerAccounts<'a, 'info>
+{
+    type Error = ProgramError;
+
+    fn try_from(
+        (program_id, accounts): (&'a Pubkey, &'a [AccountInfo<'info>]),
+    ) -> Result<Self, Self::Error> {
+        let [authority, config, yield_tokenizer, initializer, pt_mint, yt_mint, lsu_vault, treasury, destination, token_program, yield_token_program, ..] =
+            accounts
+        else {
+            return Err(ProgramError::NotEnoughAccountKeys);
+        };
+
+        let config_data = load_config(program_id, config)?;
+        check_authority(authority, &config_data)?;
+        let yield_tokenizer_data = load_yield_tokenizer(program_id, yield_tokenizer)?;
+        if initializer.key != &yield_tokenizer_data.initializer {
+            return Err(ProgramError::InvalidAccountData);
+        }
+        check_address(
+            pt_mint,
+            &yield_tokenizer_data.pt,
+            YieldTokenizerError::InvalidPrincipalToken,
+        )?;
+        check_address(
+            yt_mint,
+            &yield_tokenizer_data.yt,
+            YieldTokenizerError::InvalidYieldToken,
+        )?;
+        check_vault(lsu_vault, &yield_tokenizer_data)?;
+        check_treasury(program_id, treasury, yield_tokenizer, &yield_tokenizer_data)?;
+        check_writable(initializer)?;
+        check_writable(destination)?;
+        check_program(token_program, &spl_token::id())?;
+        check_yield_token_program(yield_token_program, pt_mint)?;
+        check_yield_token_program(yield_token_program, yt_mint)?;
+
+        Ok(Self {
+            authority,
+            config,
+            yield_tokenizer,
+            initializer,
+            pt_mint,
+            yt_mint,
+            lsu_vault,
+            treasury,
+            destination,
+            token_program,
+            yield_token_program,
+            config_data,
+            yield_tokenizer_data,
+        })
+    }
+}
+
+/// Accounts of `MigrateState`, the migrated account's type is told from its contents
+pub struct MigrateStateAccounts<'a, 'info> {
+    pub payer: &'a AccountInfo<'info>,
+    pub account: &'a AccountInfo<'info>,
+    pub system_program: &'a AccountInfo<'info>,
+}
+
+impl<'a, 'info> TryFrom<(&'a Pubkey, &'a [AccountInfo<'info>])>
+    for MigrateStateAccounts<'a, 'info>
+{
+    type Error = ProgramError;
+
+    fn try_from(
+        (program_id, accounts): (&'a Pubkey, &'a [AccountInfo<'info>]),
+    ) -> Result<Self, Self::Error> {
+        let [payer, account, system_program, ..] = accounts else {
+            return Err(ProgramError::NotEnoughAccountKeys);
+        };
+
+        check_signer(payer)?;
+        check_writable(payer)?;
+        check_owner(account, program_id)?;
+        check_writable(account)?;
+        check_program(system_program, &system_program::id())?;
+
+        Ok(Self {
+            payer,
+            account,
+            system_program,
+        })
+    }
+}
+
+/// Accounts `MigrateState` takes after the first three to upgrade a version 0 tokenizer, which
+/// predates the treasury and the exchange rate. Built from the tokenizer and those accounts.
+pub struct MigrateYieldTokenizerAccounts<'a, 'info> {
+    pub config: &'a AccountInfo<'info>,
+    pub lsu_mint: &'a AccountInfo<'info>,
+    pub lsu_exchange_rate_account: &'a AccountInfo<'info>,
+    pub treasury: &'a AccountInfo<'info>,
+    pub token_program: &'a AccountInfo<'info>,
+    pub legacy_data: LegacyYieldTokenizerState,
+    /// Config entry of the LSU, the series takes its adapter and exchange rate account as
+    /// `InitializeYieldTokenizer` would
+    pub supported_lsu: SupportedLsu,
+    pub bump: u8,
+    pub pt_bump: u8,
+    pub yt_bump: u8,
+    pub treasury_bump: u8,
+}
+
+impl<'a, 'info> TryFrom<(&'a Pubkey, &'a AccountInfo<'info>, &'a [AccountInfo<'info>])>
+    for MigrateYieldTokenizerAccounts<'a, 'info>
+{
+    type Error = ProgramError;
+
+    fn try_from(
+        (program_id, yield_tokenizer, accounts): (
+            &'a Pubkey,
+            &'a AccountInfo<'info>,
+            &'a [AccountInfo<'info>],
+        ),
+    ) -> Result<Self, Self::Error> {
+        let [config, lsu_mint, lsu_exchange_rate_account, treasury, token_program, ..] = accounts
+        else {
+            return Err(ProgramError::NotEnoughAccountKeys);
+        };
+
+        let legacy_data = {
+            let data = yield_tokenizer.data.borrow();
+            if data.len() != LEGACY_YIELD_TOKENIZER_LEN {
+                return Err(ProgramError::InvalidAccountData);
+            }
+            LegacyYieldTokenizerState::try_from_slice(&data)?
+        };
+        // Bumps are searched for once here and stored from then on
+        let (yield_tokenizer_addr, bump) = find_yield_tokenizer_address(
+            program_id,
+            &legacy_data.lsu_mint,
+            legacy_data.maturity_date,
+        );
+        check_address(
+            yield_tokenizer,
+            &yield_tokenizer_addr,
+            YieldTokenizerError::InvalidYieldTokenizerAddress,
+        )?;
+        let (pt_mint_addr, pt_bump) = find_principal_token_address(program_id, yield_tokenizer.key);
+        if legacy_data.pt != pt_mint_addr {
+            return Err(YieldTokenizerError::InvalidPrincipalToken.into());
+        }
+        let (yt_mint_addr, yt_bump) = find_yield_token_address(program_id, yield_tokenizer.key);
+        if legacy_data.yt != yt_mint_addr {
+            return Err(YieldTokenizerError::InvalidYieldToken.into());
+        }
+        if legacy_data.lsu_vault
+            != get_associated_token_address(yield_tokenizer.key, &legacy_data.lsu_mint)
+        {
+            return Err(YieldTokenizerError::InvalidVault.into());
+        }
+
+        let config_data = load_config(program_id, config)?;
+        let supported_lsu = config_data
+            .get_lsu(&legacy_data.lsu_mint)
+            .ok_or(YieldTokenizerError::InvalidLSU)?
+            .clone();
+        check_address(
+            lsu_mint,
+            &legacy_data.lsu_mint,
+            YieldTokenizerError::InvalidLSU,
+        )?;
+        check_address(
+            lsu_exchange_rate_account,
+            &supported_lsu.exchange_rate_account,
+            YieldTokenizerError::InvalidExchangeRateAccount,
+        )?;
+        let (treasury_addr, treasury_bump) = find_treasury_address(program_id, yield_tokenizer.key);
+        check_address(
+            treasury,
+            &treasury_addr,
+            YieldTokenizerError::InvalidTreasury,
+        )?;
+        check_writable(treasury)?;
+        check_program(token_program, &spl_token::id())?;
+
+        Ok(Self {
+            config,
+            lsu_mint,
+            lsu_exchange_rate_account,
+            treasury,
+            token_program,
+            legacy_data,
+            supported_lsu,
+            bump,
+            pt_bump,
+            yt_bump,
+            treasury_bump,
+        })
+    }
+}
+
+/// Loads the protocol config after checking it's the program's config PDA
+fn load_config(program_id: &Pubkey, config: &AccountInfo) -> Result<ProtocolConfig, ProgramError> {
+    if config.key != &get_config_address(program_id) || config.owner != program_id {
+        return Err(YieldTokenizerError::InvalidConfigAddress.into());
+    }
+    ProtocolConfig::unpack(&config.data.borrow())
+}
+
+fn check_authority(
+    authority: &AccountInfo,
+    config_data: &ProtocolConfig,
+) -> Result<(), ProgramError> {
+    if !authority.is_signer || authority.key != &config_data.authority {
+        return Err(YieldTokenizerError::InvalidAuthority.into());
+    }
+    Ok(())
+}
+
+/// Upgrade authority from the program's `UpgradeableLoaderState::ProgramData` account, a u32
+/// tag, the u64 deployment slot and an `Option<Pubkey>`
+fn get_upgrade_authority(
+    program_id: &Pubkey,
+    program_data: &AccountInfo,
+) -> Result<Option<Pubkey>, ProgramError> {
+    let (program_data_addr, _) =
+        Pubkey::find_program_address(&[program_id.as_ref()], &bpf_loader_upgradeable::id());
+    if program_data.key != &program_data_addr || program_data.owner != &bpf_loader_upgradeable::id()
+    {
+        return Err(ProgramError::InvalidAccountData);
+    }
+
+    let data = program_data.data.borrow();
+    if data.len() < 45 || data[..4] != 3u32.to_le_bytes() {
+        return Err(ProgramError::InvalidAccountData);
+    }
+    if data[12] == 0 {
+        return Ok(None);
+    }
+
+    let mut authority = [0u8; 32];
+    authority.copy_from_slice(&data[13..45]);
+    Ok(Some(Pubkey::new_from_array(authority)))
+}
+
+/// Loads a writable tokenizer account, see `read_yield_tokenizer`
+fn load_yield_tokenizer(
+    program_id: &Pubkey,
+    yield_tokenizer: &AccountInfo,
+) -> Result<YieldTokenizerState, ProgramError> {
+    check_writable(yield_tokenizer)?;
+    read_yield_tokenizer(program_id, yield_tokenizer)
+}
+
+/// Loads a tokenizer account after checking it's owned by the program and sits at the PDA of
+/// its own LSU mint, maturity and stored bump
+fn read_yield_tokenizer(
+    program_id: &Pubkey,
+    yield_tokenizer: &AccountInfo,
+) -> Result<YieldTokenizerState, ProgramError> {
+    check_owner(yield_tokenizer, program_id)?;
+    let yield_tokenizer_data = YieldTokenizerState::unpack(&yield_tokenizer.data.borrow())?;
+    check_address(
+        yield_tokenizer,
+        &create_yield_tokenizer_address(
+            program_id,
+            &yield_tokenizer_data.lsu_mint,
+            yield_tokenizer_data.maturity_date,
+            yield_tokenizer_data.bump,
+        )?,
+        YieldTokenizerError::InvalidYieldTokenizerAddress,
+    )?;
+    Ok(yield_tokenizer_data)
+}
+
+/// Loads a tokenizer's writable PT pool after checking it sits at the tokenizer's pool PDA and
+/// the vaults passed with it. Returns the pool's bump for signing.
+fn load_pool<'info>(
+    program_id: &Pubkey,
+    pool: &AccountInfo<'info>,
+    yield_tokenizer: &AccountInfo<'info>,
+    pool_lsu_vault: &AccountInfo<'info>,
+    pool_pt_vault: &AccountInfo<'info>,
+) -> Result<(PtPool, u8), ProgramError> {
+    check_owner(pool, program_id)?;
+    let pool_data = PtPool::unpack(&pool.data.borrow())?;
+    check_address(
+        pool,
+        &create_pool_address(program_id, yield_tokenizer.key, pool_data.bump)?,
+        YieldTokenizerError::InvalidPoolAddress,
+    )?;
+    check_address(
+        pool_lsu_vault,
+        &pool_data.lsu_vault,
+        YieldTokenizerError::InvalidVault,
+    )?;
+    check_address(
+        pool_pt_vault,
+        &pool_data.pt_vault,
+        YieldTokenizerError::InvalidVault,
+    )?;
+    for account in [pool, pool_lsu_vault, pool_pt_vault] {
+        check_writable(account)?;
+    }
+    let pool_bump = pool_data.bump;
+    Ok((pool_data, pool_bump))
+}
+
+/// Checks a liquidity provider's LSU, PT and LP token accounts, which are all written
+fn check_provider_atas<'info>(
+    provider: &AccountInfo<'info>,
+    lsu_mint: &AccountInfo<'info>,
+    pt_mint: &AccountInfo<'info>,
+    lp_mint: &AccountInfo<'info>,
+    provider_lsu_ata: &AccountInfo<'info>,
+    provider_pt_ata: &AccountInfo<'info>,
+    provider_lp_ata: &AccountInfo<'info>,
+) -> Result<(), ProgramError> {
+    check_token_account(
+        provider_lsu_ata,
+        provider.key,
+        lsu_mint,
+        YieldTokenizerError::LSUTokenAccountMismatch,
+    )?;
+    check_token_account(
+        provider_pt_ata,
+        provider.key,
+        pt_mint,
+        YieldTokenizerError::InvalidPrincipalToken,
+    )?;
+    check_token_account(
+        provider_lp_ata,
+        provider.key,
+        lp_mint,
+        YieldTokenizerError::InvalidLPToken,
+    )?;
+    for account in [provider, provider_lsu_ata, provider_pt_ata, provider_lp_ata] {
+        check_writable(account)?;
+    }
+    Ok(())
+}
+
+fn check_lsu(
+    lsu_mint: &AccountInfo,
+    lsu_exchange_rate_account: &AccountInfo,
+    yield_tokenizer_data: &YieldTokenizerState,
+) -> Result<(), ProgramError> {
+    check_address(
+        lsu_mint,
+        &yield_tokenizer_data.lsu_mint,
+        YieldTokenizerError::InvalidLSU,
+    )?;
+    check_address(
+        lsu_exchange_rate_account,
+        &yield_tokenizer_data.lsu_exchange_rate_account,
+        YieldTokenizerError::InvalidExchangeRateAccount,
+    )
+}
+
+/// Checks the tokenizer's vault against the address stored at init, which was checked to be the
+/// tokenizer's LSU ATA then
+fn check_vault(
+    lsu_vault: &AccountInfo,
+    yield_tokenizer_data: &YieldTokenizerState,
+) -> Result<(), ProgramError> {
+    check_address(
+        lsu_vault,
+        &yield_tokenizer_data.lsu_vault,
+        YieldTokenizerError::InvalidVault,
+    )?;
+    check_writable(lsu_vault)
+}
+
+fn check_treasury(
+    program_id: &Pubkey,
+    treasury: &AccountInfo,
+    yield_tokenizer: &AccountInfo,
+    yield_tokenizer_data: &YieldTokenizerState,
+) -> Result<(), ProgramError> {
+    check_address(
+        treasury,
+        &create_treasury_address(
+            program_id,
+            yield_tokenizer.key,
+            yield_tokenizer_data.treasury_bump,
+        )?,
+        YieldTokenizerError::InvalidTreasury,
+    )?;
+    check_writable(treasury)
+}
+
+/// Checks a user's token account holds `mint` for `owner`, from the mint and owner its token
+/// program stored in it rather than by deriving the ATA. An account that doesn't exist yet passes,
+/// the ATA program checks its address when the instruction creates it and the token program
+/// refuses it otherwise.
+fn check_token_account(
+    token_account: &AccountInfo,
+    owner: &Pubkey,
+    mint: &AccountInfo,
+    error: YieldTokenizerError,
+) -> Result<(), ProgramError> {
+    if token_account.owner == &system_program::id() && token_account.data_is_empty() {
+        return Ok(());
+    }
+    if token_account.owner != mint.owner {
+        return Err(error.into());
+    }
+    let data = token_account.data.borrow();
+    let token_account = StateWithExtensions::<Account>::unpack(&data).map_err(|_| error.clone())?;
+    if &token_account.base.mint != mint.key || &token_account.base.owner != owner {
+        return Err(error.into());
+    }
+    Ok(())
+}
+
+/// Checks `yield_token_program` is the token program that owns the PT/YT `mint`
+pub(crate) fn check_yield_token_program(
+    yield_token_program: &AccountInfo,
+    mint: &AccountInfo,
+) -> Result<(), ProgramError> {
+    if yield_token_program.key != mint.owner
+        || (yield_token_program.key != &spl_token::id()
+            && yield_token_program.key != &spl_token_2022::id())
+    {
+        return Err(YieldTokenizerError::InvalidProgram.into());
+    }
+    Ok(())
+}
+
+fn check_signer(account: &AccountInfo) -> Result<(), ProgramError> {
+    if !account.is_signer {
+        return Err(YieldTokenizerError::MissingSigner.into());
+    }
+    Ok(())
+}
+
+fn check_writable(account: &AccountInfo) -> Result<(), ProgramError> {
+    if !account.is_writable {
+        return Err(YieldTokenizerError::AccountNotWritable.into());
+    }
+    Ok(())
+}
+
+fn check_owner(account: &AccountInfo, owner: &Pubkey) -> Result<(), ProgramError> {
+    if account.owner != owner {
+        return Err(YieldTokenizerError::InvalidAccountOwner.into());
+    }
+    Ok(())
+}
+
+fn check_program(account: &AccountInfo, program_id: &Pubkey) -> Result<(), ProgramError> {
+    if account.key != program_id {
+        return Err(YieldTokenizerError::InvalidProgram.into());
+    }
+    Ok(())
+}
+
+fn check_address(
+    account: &AccountInfo,
+    expected: &Pubkey,
+    error: YieldTokenizerError,
+) -> Result<(), ProgramError> {
+    if account.key != expected {
+        return Err(error.into());
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use {
+        super::*,
+        crate::{
+            adapter::LsuAdapter,
+            instruction,
+            resolver::{self, TokenizerAccounts},
+            state::{
+                PauseState, EXCHANGE_RATE_PRECISION, YIELD_TOKENIZER_DISCRIMINATOR,
+                YIELD_TOKENIZER_VERSION,
+            },
+        },
+        solana_program::{instruction::Instruction, program_pack::Pack},
+        spl_token::state::AccountState,
+        std::collections::HashMap,
+    };
+
+    const MATURITY_DATE: i64 = 1_798_675_200; // 2026-12-31
+
+    type Check = fn(&Pubkey, &[AccountInfo]) -> Result<(), ProgramError>;
+
+    /// Owned backing of an `AccountInfo`
+    struct TestAccount {
+        key: Pubkey,
+        is_signer: bool,
+        is_writable: bool,
+        lamports: u64,
+        data: Vec<u8>,
+        owner: Pubkey,
+    }
+
+    impl TestAccount {
+        fn info(&mut self) -> AccountInfo<'_> {
+            AccountInfo::new(
+                &self.key,
+                self.is_signer,
+                self.is_writable,
+                &mut self.lamports,
+                &mut self.data,
+                &self.owner,
+                false,
+                0,
+            )
+        }
+    }
+
+    /// A series under `spl_token` and a user holding its LSU, PT and YT, of a tokenizer deployed
+    /// somewhere other than `crate::id()`. Accounts that aren't
+    /// listed, the programs, rate account and positions, are empty system accounts.
+    struct Fixture {
+        accounts: TokenizerAccounts,
+        states: HashMap<Pubkey, (Pubkey, Vec<u8>)>,
+    }
+
+    impl Fixture {
+        fn new() -> Self {
+            let accounts = TokenizerAccounts::new(
+                &Pubkey::new_unique(),
+                &Pubkey::new_unique(),
+                MATURITY_DATE,
+                &Pubkey::new_unique(),
+                &Pubkey::new_unique(),
+                &spl_token::id(),
+            );
+            let yield_tokenizer = YieldTokenizerState {
+                discriminator: YIELD_TOKENIZER_DISCRIMINATOR,
+                version: YIELD_TOKENIZER_VERSION,
+                pt: accounts.pt_mint,
+                yt: accounts.yt_mint,
+                maturity_date: MATURITY_DATE,
+                lsu_mint: accounts.lsu_mint,
+                lsu_vault: accounts.lsu_vault,
+                adapter: LsuAdapter::Marinade,
+                lsu_exchange_rate_account: accounts.lsu_exchange_rate_account,
+                initial_exchange_rate: EXCHANGE_RATE_PRECISION,
+                last_exchange_rate: EXCHANGE_RATE_PRECISION,
+                maturity_exchange_rate: 0,
+                yield_index: 0,
+                pause_state: PauseState::Active,
+                initializer: accounts.user,
+                bump: find_yield_tokenizer_address(
+                    &accounts.program_id,
+                    &accounts.lsu_mint,
+                    MATURITY_DATE,
+                )
+                .1,
+                pt_bump: find_principal_token_address(
+                    &accounts.program_id,
+                    &accounts.yield_tokenizer,
+                )
+                .1,
+                yt_bump: find_yield_token_address(&accounts.program_id, &accounts.yield_tokenizer)
+                    .1,
+                treasury_bump: find_treasury_address(
+                    &accounts.program_id,
+                    &accounts.yield_tokenizer,
+                )
+                .1,
+            };
+
+            let mut states = HashMap::new();
+            states.insert(
+                accounts.config,
+                (
+                    accounts.program_id,
+                    borsh::to_vec(&ProtocolConfig::new(accounts.user)).unwrap(),
+                ),
+            );
+            states.insert(
+                accounts.yield_tokenizer,
+                (
+                    accounts.program_id,
+                    borsh::to_vec(&yield_tokenizer).unwrap(),
+                ),
+            );
+            for mint in [accounts.lsu_mint, accounts.pt_mint, accounts.yt_mint] {
+                let mut data = vec![0; spl_token::state::Mint::LEN];
+                spl_token::state::Mint {
+                    decimals: 9,
+                    is_initialized: true,
+                    ..Default::default()
+                }
+                .pack_into_slice(&mut data);
+                states.insert(mint, (spl_token::id(), data));
+            }
+            for (token_account, mint, owner) in [
+                (
+                    accounts.lsu_vault,
+                    accounts.lsu_mint,
+                    accounts.yield_tokenizer,
+                ),
+                (
+                    accounts.treasury,
+                    accounts.lsu_mint,
+                    accounts.yield_tokenizer,
+                ),
+                (accounts.user_lsu_ata, accounts.lsu_mint, accounts.user),
+                (accounts.user_pt_ata, accounts.pt_mint, accounts.user),
+                (accounts.user_yt_ata, accounts.yt_mint, accounts.user),
+            ] {
+                states.insert(
+                    token_account,
+                    (spl_token::id(), token_account_data(mint, owner)),
+                );
+            }
+
+            Self { accounts, states }
+        }
+
+        /// The instruction's accounts in order, with its signer and writable flags
+        fn test_accounts(&self, instruction: &Instruction) -> Vec<TestAccount> {
+            instruction
+                .accounts
+                .iter()
+                .map(|meta| {
+                    let (owner, data) = self
+                        .states
+                        .get(&meta.pubkey)
+                        .cloned()
+                        .unwrap_or((system_program::id(), vec![]));
+                    TestAccount {
+                        key: meta.pubkey,
+                        is_signer: meta.is_signer,
+                        is_writable: meta.is_writable,
+                        lamports: 1,
+                        data,
+                        owner,
+                    }
+                })
+                .collect()
+        }
+
+        /// The user-facing instructions with their accounts checks
+        fn user_instructions(&self) -> Vec<(Instruction, Check)> {
+            let accounts = &self.accounts;
+            vec![
+                (
+                    resolver::tokenize_yield_for(accounts, 1).unwrap(),
+                    |program_id, accounts| {
+                        TokenizeYieldAccounts::try_from((program_id, accounts)).map(|_| ())
+                    },
+                ),
+                (
+                    resolver::redeem_for(accounts, 1).unwrap(),
+                    |program_id, accounts| {
+                        RedeemAccounts::try_from((program_id, accounts)).map(|_| ())
+                    },
+                ),
+                (
+                    resolver::redeem_from_pt_for(accounts, 1).unwrap(),
+                    |program_id, accounts| {
+                        RedeemFromPtAccounts::try_from((program_id, accounts)).map(|_| ())
+                    },
+                ),
+                (
+                    resolver::claim_yield_for(accounts).unwrap(),
+                    |program_id, accounts| {
+                        ClaimYieldAccounts::try_from((program_id, accounts)).map(|_| ())
+                    },
+                ),
+                (
+                    resolver::transfer_yt_for(accounts, &Pubkey::new_unique(), 1).unwrap(),
+                    |program_id, accounts| {
+                        TransferYtAccounts::try_from((program_id, accounts)).map(|_| ())
+                    },
+                ),
+            ]
+        }
+    }
+
+    fn token_account_data(mint: Pubkey, owner: Pubkey) -> Vec<u8> {
+        let mut data = vec![0; spl_token::state::Account::LEN];
+        spl_token::state::Account {
+            mint,
+            owner,
+            state: AccountState::Initialized,
+            ..Default::default()
+        }
+        .pack_into_slice(&mut data);
+        data
+    }
+
+    /// Runs `check` on the instruction's accounts after `modify` has changed them
+    fn check_with(
+        fixture: &Fixture,
+        instruction: &Instruction,
+        check: Check,
+        modify: impl FnOnce(&mut [TestAccount]),
+    ) -> Result<(), ProgramError> {
+        let mut test_accounts = fixture.test_accounts(instruction);
+        modify(&mut test_accounts);
+        let infos: Vec<_> = test_accounts.iter_mut().map(TestAccount::info).collect();
+        check(&fixture.accounts.program_id, &infos)
+    }
+
+    fn index_of(instruction: &Instruction, key: &Pubkey) -> Option<usize> {
+        instruction
+            .accounts
+            .iter()
+            .position(|meta| &meta.pubkey == key)
+    }
+
+    #[test]
+    fn test_user_accounts_accept_the_series() {
+        let fixture = Fixture::new();
+        for (instruction, check) in fixture.user_instructions() {
+            assert_eq!(check_with(&fixture, &instruction, check, |_| ()), Ok(()));
+        }
+    }
+
+    #[test]
+    fn test_user_accounts_reject_another_program() {
+        let fixture = Fixture::new();
+        for (instruction, check) in fixture.user_instructions() {
+            // The same accounts checked for the deployment at `crate::id()`
+            let mut test_accounts = fixture.test_accounts(&instruction);
+            let infos: Vec<_> = test_accounts.iter_mut().map(TestAccount::info).collect();
+            assert_eq!(
+                check(&crate::id(), &infos),
+                Err(YieldTokenizerError::InvalidConfigAddress.into())
+            );
+        }
+    }
+
+    #[test]
+    fn test_user_accounts_reject_missing_signer() {
+        let fixture = Fixture::new();
+        for (instruction, check) in fixture.user_instructions() {
+            let user = index_of(&instruction, &fixture.accounts.user).unwrap();
+            assert_eq!(
+                check_with(&fixture, &instruction, check, |accounts| {
+                    accounts[user].is_signer = false
+                }),
+                Err(YieldTokenizerError::MissingSigner.into())
+            );
+        }
+    }
+
+    #[test]
+    fn test_user_accounts_reject_read_only() {
+        let fixture = Fixture::new();
+        let accounts = fixture.accounts;
+        for (instruction, check) in fixture.user_instructions() {
+            for (index, meta) in instruction.accounts.iter().enumerate() {
+                // Every account of the series and the user that the instruction writes
+                if !meta.is_writable
+                    || ![
+                        accounts.user,
+                        accounts.yield_tokenizer,
+                        accounts.pt_mint,
+                        accounts.yt_mint,
+                        accounts.lsu_vault,
+                        accounts.treasury,
+                        accounts.user_lsu_ata,
+                        accounts.user_pt_ata,
+                        accounts.user_yt_ata,
+                        accounts.user_position,
+                    ]
+                    .contains(&meta.pubkey)
+                {
+                    continue;
+                }
+                assert_eq!(
+                    check_with(&fixture, &instruction, check, |accounts| {
+                        accounts[index].is_writable = false
+                    }),
+                    Err(YieldTokenizerError::AccountNotWritable.into()),
+                    "account {} of {:?}",
+                    index,
+                    instruction.data
+                );
+            }
+        }
+    }
+
+    #[test]
+    fn test_user_accounts_reject_wrong_owner() {
+        let fixture = Fixture::new();
+        let accounts = fixture.accounts;
+        for (instruction, check) in fixture.user_instructions() {
+            for (key, error) in [
+                (accounts.config, YieldTokenizerError::InvalidConfigAddress),
+                (
+                    accounts.yield_tokenizer,
+                    YieldTokenizerError::InvalidAccountOwner,
+                ),
+                (
+                    accounts.user_lsu_ata,
+                    YieldTokenizerError::LSUTokenAccountMismatch,
+                ),
+                (accounts.user_yt_ata, YieldTokenizerError::InvalidYieldToken),
+            ] {
+                let Some(index) = index_of(&instruction, &key) else {
+                    continue;
+                };
+                assert_eq!(
+                    check_with(&fixture, &instruction, check, |accounts| {
+                        accounts[index].owner = Pubkey::new_unique()
+                    }),
+                    Err(error.into())
+                );
+            }
+
+            // Token accounts of the right mint that belong to someone else
+            for (key, mint, error) in [
+                (
+                    accounts.user_pt_ata,
+                    accounts.pt_mint,
+                    YieldTokenizerError::InvalidPrincipalToken,
+                ),
+                (
+                    accounts.user_yt_ata,
+                    accounts.yt_mint,
+                    YieldTokenizerError::InvalidYieldToken,
+                ),
+            ] {
+                let Some(index) = index_of(&instruction, &key) else {
+                    continue;
+                };
+                assert_eq!(
+                    check_with(&fixture, &instruction, check, |accounts| {
+                        accounts[index].data = token_account_data(mint, Pubkey::new_unique())
+                    }),
+                    Err(error.into())
+                );
+            }
+        }
+    }
+
+    #[test]
+    fn test_user_accounts_reject_wrong_pda() {
+        let fixture = Fixture::new();
+        let accounts = fixture.accounts;
+        for (instruction, check) in fixture.user_instructions() {
+            for (key, error) in [
+                (accounts.config, YieldTokenizerError::InvalidConfigAddress),
+                (
+                    accounts.yield_tokenizer,
+                    YieldTokenizerError::InvalidYieldTokenizerAddress,
+                ),
+                (accounts.lsu_vault, YieldTokenizerError::InvalidVault),
+                (accounts.treasury, YieldTokenizerError::InvalidTreasury),
+            ] {
+                let Some(index) = index_of(&instruction, &key) else {
+                    continue;
+                };
+                // Same contents at another address
+                assert_eq!(
+                    check_with(&fixture, &instruction, check, |accounts| {
+                        accounts[index].key = Pubkey::new_unique()
+                    }),
+                    Err(error.into())
+                );
+            }
+        }
+    }
+
+    #[test]
+    fn test_close_checks_both_mints_against_the_token_program() {
+        let fixture = Fixture::new();
+        let accounts = fixture.accounts;
+        let instruction = instruction::close_yield_tokenizer(
+            &accounts.program_id,
+            &accounts.user,
+            &accounts.config,
+            &accounts.yield_tokenizer,
+            &accounts.user,
+            &accounts.pt_mint,
+            &accounts.yt_mint,
+            &accounts.lsu_vault,
+            &accounts.treasury,
+            &accounts.user_lsu_ata,
+            &spl_token::id(),
+        )
+        .unwrap();
+        let check: Check = |program_id, accounts| {
+            CloseYieldTokenizerAccounts::try_from((program_id, accounts)).map(|_| ())
+        };
+        assert_eq!(check_with(&fixture, &instruction, check, |_| ()), Ok(()));
+
+        let yt_mint = index_of(&instruction, &accounts.yt_mint).unwrap();
+        assert_eq!(
+            check_with(&fixture, &instruction, check, |accounts| {
+                accounts[yt_mint].owner = spl_token_2022::id()
+            }),
+            Err(YieldTokenizerError::InvalidProgram.into())
+        );
+    }
+}
@@ -0,0 +1,476 @@
+use {
+    crate::{error::YieldTokenizerError, state::EXCHANGE_RATE_PRECISION},
+    borsh::{BorshDeserialize, BorshSchema, BorshSerialize},
+    solana_program::{
+        account_info::AccountInfo, clock::Epoch, program_error::ProgramError, pubkey,
+        pubkey::Pubkey,
+    },
+};
+
+pub const MARINADE_PROGRAM_ID: Pubkey = pubkey!("MarBmsSgKXdrN1egZf5sqe1TMai9K1rChYNDJgjq7aD");
+pub const SPL_STAKE_POOL_PROGRAM_ID: Pubkey =
+    pubkey!("SPoo1Ku8WFXoNDMHPsrGSTSG1Y47rzgn41SLUNakuHy");
+
+pub const MARINADE_STATE: Pubkey = pubkey!("8szGkuLTAux9XMgZ2vtY39jVSowEcpBfFfD8hXSEqdGC");
+pub const JITO_STAKE_POOL: Pubkey = pubkey!("Jito4APyf642JPZPx3hGc6WWJ8zPKtRbRs4P815Awbb");
+pub const BLAZE_STAKE_POOL: Pubkey = pubkey!("stk9ApL5HeVAwPLr3TLhDXdZS8ptVu7zp6ov8HFDuMi");
+pub const JPOOL_STAKE_POOL: Pubkey = pubkey!("CtMyWsrUtAwXWiGr9WjHT5fC3p3fgV8cyGpLTo2LJzG1");
+
+// Marinade `State` anchor account
+const MARINADE_STATE_DISCRIMINATOR: [u8; 8] = [216, 146, 107, 94, 104, 75, 182, 177];
+const MARINADE_MSOL_MINT_OFFSET: usize = 8;
+const MARINADE_MSOL_PRICE_OFFSET: usize = 512;
+const MARINADE_PRICE_DENOMINATOR: u128 = 0x1_0000_0000;
+
+// SPL stake pool `StakePool` account
+const STAKE_POOL_ACCOUNT_TYPE: u8 = 1;
+const STAKE_POOL_MINT_OFFSET: usize = 162;
+const STAKE_POOL_LAMPORTS_OFFSET: usize = 258;
+const STAKE_POOL_SUPPLY_OFFSET: usize = 266;
+const STAKE_POOL_LAST_UPDATE_EPOCH_OFFSET: usize = 274;
+
+/// Source of the SOL per LSU exchange rate for a liquid staking unit
+#[derive(Clone, Copy, Debug, PartialEq, Eq, BorshDeserialize, BorshSerialize, BorshSchema)]
+pub enum LsuAdapter {
+    /// Marinade `State` account, `msol_price`
+    Marinade,
+    /// SPL stake pool `StakePool` account, `total_lamports / pool_token_supply`
+    SplStakePool,
+}
+
+impl LsuAdapter {
+    pub fn program_id(&self) -> Pubkey {
+        match self {
+            LsuAdapter::Marinade => MARINADE_PROGRAM_ID,
+            LsuAdapter::SplStakePool => SPL_STAKE_POOL_PROGRAM_ID,
+        }
+    }
+
+    /// Reads the SOL value of one LSU, scaled by `EXCHANGE_RATE_PRECISION`, after checking the
+    /// account is owned by the adapter's program and backs `lsu_mint`.
+    ///
+    /// Stake pools are only updated some time into each epoch. Exits also accept a pool last
+    /// updated in the previous epoch so they don't fail across every epoch boundary.
+    pub fn get_exchange_rate(
+        &self,
+        lsu_mint: &Pubkey,
+        exchange_rate_account: &AccountInfo,
+        epoch: Epoch,
+        is_exit: bool,
+    ) -> Result<u64, ProgramError> {
+        if exchange_rate_account.owner != &self.program_id() {
+            return Err(YieldTokenizerError::InvalidExchangeRateAccount.into());
+        }
+
+        let data = exchange_rate_account.data.borrow();
+        let exchange_rate = match self {
+            LsuAdapter::Marinade => {
+                if data.len() < MARINADE_MSOL_PRICE_OFFSET + 8
+                    || data[..8] != MARINADE_STATE_DISCRIMINATOR
+                {
+                    return Err(YieldTokenizerError::InvalidExchangeRateAccount.into());
+                }
+                if &read_pubkey(&data, MARINADE_MSOL_MINT_OFFSET) != lsu_mint {
+                    return Err(YieldTokenizerError::InvalidExchangeRateAccount.into());
+                }
+
+                read_u64(&data, MARINADE_MSOL_PRICE_OFFSET) as u128
+                    * EXCHANGE_RATE_PRECISION as u128
+                    / MARINADE_PRICE_DENOMINATOR
+            }
+            LsuAdapter::SplStakePool => {
+                if data.len() < STAKE_POOL_LAST_UPDATE_EPOCH_OFFSET + 8
+                    || data[0] != STAKE_POOL_ACCOUNT_TYPE
+                {
+                    return Err(YieldTokenizerError::InvalidExchangeRateAccount.into());
+                }
+                if &read_pubkey(&data, STAKE_POOL_MINT_OFFSET) != lsu_mint {
+                    return Err(YieldTokenizerError::InvalidExchangeRateAccount.into());
+                }
+                // Balances are only meaningful once the pool has been updated for this epoch
+                let last_update_epoch = read_u64(&data, STAKE_POOL_LAST_UPDATE_EPOCH_OFFSET);
+                let is_previous_epoch = last_update_epoch.checked_add(1) == Some(epoch);
+                if last_update_epoch != epoch && !(is_exit && is_previous_epoch) {
+                    return Err(YieldTokenizerError::StaleExchangeRate.into());
+                }
+
+                let total_lamports = read_u64(&data, STAKE_POOL_LAMPORTS_OFFSET);
+                let pool_token_supply = read_u64(&data, STAKE_POOL_SUPPLY_OFFSET);
+                if pool_token_supply == 0 {
+                    return Err(YieldTokenizerError::InvalidExchangeRateAccount.into());
+                }
+
+                total_lamports as u128 * EXCHANGE_RATE_PRECISION as u128 / pool_token_supply as u128
+            }
+        };
+
+        if exchange_rate == 0 {
+            return Err(YieldTokenizerError::InvalidExchangeRateAccount.into());
+        }
+        u64::try_from(exchange_rate).map_err(|_| ProgramError::ArithmeticOverflow)
+    }
+}
+
+/// Adapter and exchange rate account for the LSUs the protocol launched with, for seeding the
+/// protocol config's allowlist
+pub fn get_lsu_adapter(lsu_mint: &Pubkey) -> Option<(LsuAdapter, Pubkey)> {
+    match *lsu_mint {
+        crate::MSOL => Some((LsuAdapter::Marinade, MARINADE_STATE)),
+        crate::JITOSOL => Some((LsuAdapter::SplStakePool, JITO_STAKE_POOL)),
+        crate::BSOL => Some((LsuAdapter::SplStakePool, BLAZE_STAKE_POOL)),
+        crate::JSOL => Some((LsuAdapter::SplStakePool, JPOOL_STAKE_POOL)),
+        _ => None,
+    }
+}
+
+fn read_pubkey(data: &[u8], offset: usize) -> Pubkey {
+    let mut bytes = [0u8; 32];
+    bytes.copy_from_slice(&data[offset..offset + 32]);
+    Pubkey::new_from_array(bytes)
+}
+
+fn read_u64(data: &[u8], offset: usize) -> u64 {
+    let mut bytes = [0u8; 8];
+    bytes.copy_from_slice(&data[offset..offset + 8]);
+    u64::from_le_bytes(bytes)
+}
+
+#[cfg(test)]
+mod tests {
+    use {super::*, borsh::to_vec};
+
+    const EPOCH: Epoch = 600;
+
+    /// Upstream `spl_stake_pool::state::StakePool` up to `last_update_epoch`
+    #[derive(BorshSerialize)]
+    struct StakePoolFixture {
+        account_type: u8,
+        manager: Pubkey,
+        staker: Pubkey,
+        stake_deposit_authority: Pubkey,
+        stake_withdraw_bump_seed: u8,
+        validator_list: Pubkey,
+        reserve_stake: Pubkey,
+        pool_mint: Pubkey,
+        manager_fee_account: Pubkey,
+        token_program_id: Pubkey,
+        total_lamports: u64,
+        pool_token_supply: u64,
+        last_update_epoch: u64,
+    }
+
+    impl StakePoolFixture {
+        fn new(pool_mint: Pubkey, total_lamports: u64, pool_token_supply: u64) -> Self {
+            Self {
+                account_type: STAKE_POOL_ACCOUNT_TYPE,
+                manager: Pubkey::new_unique(),
+                staker: Pubkey::new_unique(),
+                stake_deposit_authority: Pubkey::new_unique(),
+                stake_withdraw_bump_seed: 255,
+                validator_list: Pubkey::new_unique(),
+                reserve_stake: Pubkey::new_unique(),
+                pool_mint,
+                manager_fee_account: Pubkey::new_unique(),
+                token_program_id: spl_token::id(),
+                total_lamports,
+                pool_token_supply,
+                last_update_epoch: EPOCH,
+            }
+        }
+    }
+
+    /// Upstream Marinade `List`
+    #[derive(BorshSerialize, Default)]
+    struct MarinadeList {
+        account: Pubkey,
+        item_size: u32,
+        count: u32,
+        reserved1: Pubkey,
+        reserved2: u32,
+    }
+
+    /// Upstream Marinade `State` up to `msol_price`, after the anchor discriminator
+    #[derive(BorshSerialize, Default)]
+    struct MarinadeStateFixture {
+        msol_mint: Pubkey,
+        admin_authority: Pubkey,
+        operational_sol_account: Pubkey,
+        treasury_msol_account: Pubkey,
+        reserve_bump_seed: u8,
+        msol_mint_authority_bump_seed: u8,
+        rent_exempt_for_token_acc: u64,
+        reward_fee_bps: u32,
+        // `StakeSystem`
+        stake_list: MarinadeList,
+        delayed_unstake_cooling_down: u64,
+        stake_deposit_bump_seed: u8,
+        stake_withdraw_bump_seed: u8,
+        slots_for_stake_delta: u64,
+        last_stake_delta_epoch: u64,
+        min_stake: u64,
+        extra_stake_delta_runs: u32,
+        // `ValidatorSystem`
+        validator_list: MarinadeList,
+        manager_authority: Pubkey,
+        total_validator_score: u32,
+        total_active_balance: u64,
+        auto_add_validator_enabled: u8,
+        // `LiqPool`
+        lp_mint: Pubkey,
+        lp_mint_authority_bump_seed: u8,
+        sol_leg_bump_seed: u8,
+        msol_leg_authority_bump_seed: u8,
+        msol_leg: Pubkey,
+        lp_liquidity_target: u64,
+        lp_max_fee_bps: u32,
+        lp_min_fee_bps: u32,
+        treasury_cut_bps: u32,
+        lp_supply: u64,
+        lent_from_sol_leg: u64,
+        liquidity_sol_cap: u64,
+        available_reserve_balance: u64,
+        msol_supply: u64,
+        msol_price: u64,
+    }
+
+    fn marinade_data(msol_mint: Pubkey, msol_price: u64) -> Vec<u8> {
+        let mut data = MARINADE_STATE_DISCRIMINATOR.to_vec();
+        data.extend(
+            to_vec(&MarinadeStateFixture {
+                msol_mint,
+                msol_price,
+                ..MarinadeStateFixture::default()
+            })
+            .unwrap(),
+        );
+        // Fields after `msol_price`
+        data.resize(data.len() + 256, 0);
+        data
+    }
+
+    fn stake_pool_data(fixture: &StakePoolFixture) -> Vec<u8> {
+        let mut data = to_vec(fixture).unwrap();
+        // Lockups, fees and the rest of the pool
+        data.resize(data.len() + 256, 0);
+        data
+    }
+
+    fn exchange_rate(
+        adapter: LsuAdapter,
+        lsu_mint: &Pubkey,
+        owner: &Pubkey,
+        data: Vec<u8>,
+        epoch: Epoch,
+    ) -> Result<u64, ProgramError> {
+        read_exchange_rate(adapter, lsu_mint, owner, data, epoch, false)
+    }
+
+    fn read_exchange_rate(
+        adapter: LsuAdapter,
+        lsu_mint: &Pubkey,
+        owner: &Pubkey,
+        mut data: Vec<u8>,
+        epoch: Epoch,
+        is_exit: bool,
+    ) -> Result<u64, ProgramError> {
+        let key = Pubkey::new_unique();
+        let mut lamports = 0;
+        let account = AccountInfo::new(
+            &key,
+            false,
+            false,
+            &mut lamports,
+            &mut data,
+            owner,
+            false,
+            0,
+        );
+        adapter.get_exchange_rate(lsu_mint, &account, epoch, is_exit)
+    }
+
+    #[test]
+    fn test_marinade_layout() {
+        let data = marinade_data(crate::MSOL, 0);
+        assert_eq!(
+            &data[MARINADE_MSOL_MINT_OFFSET..][..32],
+            crate::MSOL.as_ref()
+        );
+        assert_eq!(data.len() - 256, MARINADE_MSOL_PRICE_OFFSET + 8);
+    }
+
+    #[test]
+    fn test_marinade_exchange_rate() {
+        // 1.25 SOL per mSOL in Marinade's 32-bit fixed point
+        let data = marinade_data(crate::MSOL, 5 * (MARINADE_PRICE_DENOMINATOR as u64) / 4);
+        assert_eq!(
+            exchange_rate(
+                LsuAdapter::Marinade,
+                &crate::MSOL,
+                &MARINADE_PROGRAM_ID,
+                data,
+                EPOCH
+            )
+            .unwrap(),
+            EXCHANGE_RATE_PRECISION * 5 / 4
+        );
+    }
+
+    #[test]
+    fn test_marinade_rejects_invalid_accounts() {
+        let price = MARINADE_PRICE_DENOMINATOR as u64;
+        let invalid = |lsu_mint: &Pubkey, owner: &Pubkey, data: Vec<u8>| {
+            assert_eq!(
+                exchange_rate(LsuAdapter::Marinade, lsu_mint, owner, data, EPOCH),
+                Err(YieldTokenizerError::InvalidExchangeRateAccount.into())
+            );
+        };
+
+        invalid(
+            &crate::MSOL,
+            &SPL_STAKE_POOL_PROGRAM_ID,
+            marinade_data(crate::MSOL, price),
+        );
+        invalid(
+            &crate::JITOSOL,
+            &MARINADE_PROGRAM_ID,
+            marinade_data(crate::MSOL, price),
+        );
+
+        let mut data = marinade_data(crate::MSOL, price);
+        data[0] ^= 1;
+        invalid(&crate::MSOL, &MARINADE_PROGRAM_ID, data);
+
+        let mut data = marinade_data(crate::MSOL, price);
+        data.truncate(MARINADE_MSOL_PRICE_OFFSET + 7);
+        invalid(&crate::MSOL, &MARINADE_PROGRAM_ID, data);
+
+        invalid(
+            &crate::MSOL,
+            &MARINADE_PROGRAM_ID,
+            marinade_data(crate::MSOL, 0),
+        );
+    }
+
+    #[test]
+    fn test_stake_pool_layout() {
+        let data = stake_pool_data(&StakePoolFixture::new(crate::JITOSOL, 11, 7));
+        assert_eq!(
+            &data[STAKE_POOL_MINT_OFFSET..][..32],
+            crate::JITOSOL.as_ref()
+        );
+        assert_eq!(read_u64(&data, STAKE_POOL_LAMPORTS_OFFSET), 11);
+        assert_eq!(read_u64(&data, STAKE_POOL_SUPPLY_OFFSET), 7);
+        assert_eq!(read_u64(&data, STAKE_POOL_LAST_UPDATE_EPOCH_OFFSET), EPOCH);
+    }
+
+    #[test]
+    fn test_stake_pool_exchange_rate() {
+        let data = stake_pool_data(&StakePoolFixture::new(
+            crate::JITOSOL,
+            1_150_000_000_000,
+            1_000_000_000_000,
+        ));
+        assert_eq!(
+            exchange_rate(
+                LsuAdapter::SplStakePool,
+                &crate::JITOSOL,
+                &SPL_STAKE_POOL_PROGRAM_ID,
+                data,
+                EPOCH
+            )
+            .unwrap(),
+            EXCHANGE_RATE_PRECISION * 115 / 100
+        );
+    }
+
+    #[test]
+    fn test_stake_pool_rejects_invalid_accounts() {
+        let rate = |fixture: &StakePoolFixture, lsu_mint: &Pubkey, owner: &Pubkey, epoch| {
+            exchange_rate(
+                LsuAdapter::SplStakePool,
+                lsu_mint,
+                owner,
+                stake_pool_data(fixture),
+                epoch,
+            )
+        };
+        let invalid = Err(YieldTokenizerError::InvalidExchangeRateAccount.into());
+        let fixture = StakePoolFixture::new(crate::JITOSOL, 2, 1);
+
+        assert_eq!(
+            rate(&fixture, &crate::JITOSOL, &MARINADE_PROGRAM_ID, EPOCH),
+            invalid
+        );
+        assert_eq!(
+            rate(&fixture, &crate::BSOL, &SPL_STAKE_POOL_PROGRAM_ID, EPOCH),
+            invalid
+        );
+        assert_eq!(
+            rate(
+                &fixture,
+                &crate::JITOSOL,
+                &SPL_STAKE_POOL_PROGRAM_ID,
+                EPOCH + 1
+            ),
+            Err(YieldTokenizerError::StaleExchangeRate.into())
+        );
+
+        // Validator list accounts share the program but not the account type
+        let validator_list = StakePoolFixture {
+            account_type: 2,
+            ..StakePoolFixture::new(crate::JITOSOL, 2, 1)
+        };
+        assert_eq!(
+            rate(
+                &validator_list,
+                &crate::JITOSOL,
+                &SPL_STAKE_POOL_PROGRAM_ID,
+                EPOCH
+            ),
+            invalid
+        );
+
+        let empty = StakePoolFixture::new(crate::JITOSOL, 0, 0);
+        assert_eq!(
+            rate(&empty, &crate::JITOSOL, &SPL_STAKE_POOL_PROGRAM_ID, EPOCH),
+            invalid
+        );
+
+        let mut data = stake_pool_data(&fixture);
+        data.truncate(STAKE_POOL_LAST_UPDATE_EPOCH_OFFSET + 7);
+        assert_eq!(
+            exchange_rate(
+                LsuAdapter::SplStakePool,
+                &crate::JITOSOL,
+                &SPL_STAKE_POOL_PROGRAM_ID,
+                data,
+                EPOCH
+            ),
+            invalid
+        );
+    }
+
+    #[test]
+    fn test_stake_pool_exits_accept_the_previous_epoch() {
+        let rate = |epoch, is_exit| {
+            read_exchange_rate(
+                LsuAdapter::SplStakePool,
+                &crate::JITOSOL,
+                &SPL_STAKE_POOL_PROGRAM_ID,
+                stake_pool_data(&StakePoolFixture::new(crate::JITOSOL, 2, 1)),
+                epoch,
+                is_exit,
+            )
+        };
+        let stale = Err(YieldTokenizerError::StaleExchangeRate.into());
+
+        // The pool hasn't been updated since the epoch boundary
+        assert_eq!(rate(EPOCH + 1, false), stale);
+        assert_eq!(rate(EPOCH + 1, true), Ok(2 * EXCHANGE_RATE_PRECISION));
+        assert_eq!(rate(EPOCH, true), Ok(2 * EXCHANGE_RATE_PRECISION));
+
+        // Nor the one before
+        assert_eq!(rate(EPOCH + 2, true), stale);
+        assert_eq!(rate(EPOCH - 1, true), stale);
+    }
+}
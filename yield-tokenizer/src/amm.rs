@@ -0,0 +1,897 @@
+use {
+    crate::{
+        error::YieldTokenizerError,
+        math::{div, exp, ln, mul, mul_div, years_to_maturity, Rounding, ONE},
+        state::{PtPool, YieldTokenizerState},
+    },
+    solana_program::program_error::ProgramError,
+};
+
+/// LP locked by the first deposit so the pool's reserves can never be fully withdrawn
+pub const MINIMUM_LIQUIDITY: u64 = 1_000;
+
+/// Largest share of the pool PT can make up after a trade, past this the curve is too steep to
+/// price safely
+pub const MAX_PT_PROPORTION: i128 = 960_000_000_000_000_000;
+
+/// Upper bound on `PtPool::scalar_root`
+pub const MAX_SCALAR_ROOT: i128 = 1_000 * ONE;
+
+/// Upper bound on `PtPool::ln_fee_rate_root`, ln(1.05)
+pub const MAX_LN_FEE_RATE_ROOT: i128 = 48_790_164_169_432_003;
+
+/// Exact-in PT purchases stop searching once the bracket is within 1 / `SEARCH_PRECISION` of the
+/// amount found
+const SEARCH_PRECISION: i128 = 1_000_000_000;
+
+/// Logit curve of a PT pool at a point in time. The pool trades at an exchange rate, PT per unit
+/// of asset, of `ln(p / (1 - p)) / rate_scalar + rate_anchor` where `p` is PT's share of the
+/// reserves after the trade.
+///
+/// Asset is the LSU reserve valued in principal, the unit PT redeems for at maturity, so the rate
+/// is always at least one. `rate_scalar` grows as maturity approaches which flattens the curve
+/// around the anchor, and the fee shrinks with it, so PT converges on its redemption value.
+pub struct Curve {
+    pub rate_scalar: i128,
+    pub rate_anchor: i128,
+    /// `e^(ln_fee_rate_root * years)`, the fee as a multiplier on the exchange rate
+    pub fee_rate: i128,
+    pub years: i128,
+}
+
+impl Curve {
+    /// Curve for `pool` with its anchor re-derived from the current reserves, so time passing
+    /// doesn't move the implied rate, only trades do
+    pub fn new(pool: &PtPool, total_asset: i128, years: i128) -> Result<Self, ProgramError> {
+        let mut curve = Self::with_anchor(pool, 0, years)?;
+
+        let exchange_rate = exp(
+            mul(pool.last_ln_implied_rate, years, Rounding::Down)?,
+            Rounding::Down,
+        )?;
+        let ln_odds = ln_odds(pool.pt_reserve as i128, total_asset, 0, Rounding::Down)?;
+        curve.rate_anchor =
+            exchange_rate - mul_div(ln_odds, ONE, curve.rate_scalar, Rounding::Down)?;
+
+        Ok(curve)
+    }
+
+    /// Curve for `pool` anchored at `rate_anchor`, the first deposit uses the pool's
+    /// `initial_anchor`
+    pub fn with_anchor(
+        pool: &PtPool,
+        rate_anchor: i128,
+        years: i128,
+    ) -> Result<Self, ProgramError> {
+        if years <= 0 {
+            return Err(YieldTokenizerError::Expired.into());
+        }
+
+        Ok(Self {
+            rate_scalar: div(pool.scalar_root, years, Rounding::Down)?,
+            rate_anchor,
+            fee_rate: exp(
+                mul(pool.ln_fee_rate_root, years, Rounding::Up)?,
+                Rounding::Up,
+            )?,
+            years,
+        })
+    }
+
+    /// Pre-fee exchange rate of a trade moving `net_pt_to_account` PT out of the pool, negative
+    /// when PT is sold into it
+    pub fn exchange_rate(
+        &self,
+        total_pt: i128,
+        total_asset: i128,
+        net_pt_to_account: i128,
+        rounding: Rounding,
+    ) -> Result<i128, ProgramError> {
+        let proportion = mul_div(
+            total_pt - net_pt_to_account,
+            ONE,
+            total_pt + total_asset,
+            Rounding::Down,
+        )?;
+        if proportion > MAX_PT_PROPORTION {
+            return Err(YieldTokenizerError::InsufficientLiquidity.into());
+        }
+
+        let ln_odds = ln_odds(total_pt, total_asset, net_pt_to_account, rounding)?;
+        let exchange_rate = mul_div(ln_odds, ONE, self.rate_scalar, rounding)? + self.rate_anchor;
+        if exchange_rate < ONE {
+            return Err(YieldTokenizerError::InsufficientLiquidity.into());
+        }
+        Ok(exchange_rate)
+    }
+
+    /// Asset paid to the account for `net_pt_to_account` PT, negative when the account pays.
+    /// Both sides are rounded in the pool's favor.
+    pub fn swap(
+        &self,
+        total_pt: i128,
+        total_asset: i128,
+        net_pt_to_account: i128,
+    ) -> Result<i128, ProgramError> {
+        let is_buying_pt = net_pt_to_account > 0;
+        // A lower rate costs buyers more asset and a higher one pays sellers less
+        let rounding = if is_buying_pt {
+            Rounding::Down
+        } else {
+            Rounding::Up
+        };
+        let pre_fee_exchange_rate =
+            self.exchange_rate(total_pt, total_asset, net_pt_to_account, rounding)?;
+
+        if is_buying_pt {
+            let post_fee_exchange_rate = div(pre_fee_exchange_rate, self.fee_rate, Rounding::Down)?;
+            if post_fee_exchange_rate < ONE {
+                return Err(YieldTokenizerError::InsufficientLiquidity.into());
+            }
+            let asset_in = mul_div(net_pt_to_account, ONE, post_fee_exchange_rate, Rounding::Up)?;
+            Ok(-asset_in)
+        } else {
+            let pre_fee_asset_out = mul_div(
+                -net_pt_to_account,
+                ONE,
+                pre_fee_exchange_rate,
+                Rounding::Down,
+            )?;
+            Ok(mul_div(
+                pre_fee_asset_out,
+                ONE,
+                self.fee_rate,
+                Rounding::Down,
+            )?)
+        }
+    }
+
+    /// Most PT `asset_in` buys, found by bisection as the curve has no closed-form inverse
+    pub fn pt_out_for_exact_asset_in(
+        &self,
+        total_pt: i128,
+        total_asset: i128,
+        asset_in: i128,
+    ) -> Result<i128, ProgramError> {
+        // Buying PT only pushes the rate down, so the pre-trade rate bounds the whole trade
+        let marginal_exchange_rate = self.exchange_rate(total_pt, total_asset, 0, Rounding::Up)?;
+        let hi = mul(asset_in, marginal_exchange_rate, Rounding::Up)?.min(total_pt);
+
+        // Post-fee rates are at least one, so `asset_in` PT is affordable if it's tradeable at all
+        search_max(asset_in, hi, |pt_out| {
+            let net_asset_to_account = self.swap(total_pt, total_asset, pt_out)?;
+            Ok(-net_asset_to_account <= asset_in)
+        })
+    }
+
+    /// `ln(1 + implied_rate)` the pool's reserves are priced at
+    pub fn ln_implied_rate(&self, total_pt: i128, total_asset: i128) -> Result<i128, ProgramError> {
+        let exchange_rate = self.exchange_rate(total_pt, total_asset, 0, Rounding::Down)?;
+        mul_div(
+            ln(exchange_rate, Rounding::Down)?,
+            ONE,
+            self.years,
+            Rounding::Down,
+        )
+    }
+}
+
+/// Largest amount in `[0, hi]` that `is_affordable`, which must be monotonic, starting the
+/// bisection from `guess` when it's affordable. Amounts the curve can't trade count as
+/// unaffordable.
+fn search_max<F>(guess: i128, mut hi: i128, is_affordable: F) -> Result<i128, ProgramError>
+where
+    F: Fn(i128) -> Result<bool, ProgramError>,
+{
+    let is_affordable = |amount: i128| match is_affordable(amount) {
+        Err(err) if err == YieldTokenizerError::InsufficientLiquidity.into() => Ok(false),
+        result => result,
+    };
+
+    let mut lo = 0;
+    if guess <= hi && is_affordable(guess)? {
+        lo = guess;
+    }
+
+    while hi > lo && hi - lo > lo / SEARCH_PRECISION {
+        let mid = lo + (hi - lo + 1) / 2;
+        if is_affordable(mid)? {
+            lo = mid;
+        } else {
+            hi = mid - 1;
+        }
+    }
+    Ok(lo)
+}
+
+/// `ln(p / (1 - p))` of PT's share of the reserves after `net_pt_to_account` leaves the pool
+fn ln_odds(
+    total_pt: i128,
+    total_asset: i128,
+    net_pt_to_account: i128,
+    rounding: Rounding,
+) -> Result<i128, ProgramError> {
+    let pt = total_pt - net_pt_to_account;
+    let asset = total_asset + net_pt_to_account;
+    if pt <= 0 || asset <= 0 {
+        return Err(YieldTokenizerError::InsufficientLiquidity.into());
+    }
+    ln(mul_div(pt, ONE, asset, rounding)?, rounding)
+}
+
+/// LP for the first deposit into a pool, including the locked `MINIMUM_LIQUIDITY`
+pub fn initial_liquidity(pt_amount: u64, lsu_amount: u64) -> u64 {
+    let product = pt_amount as u128 * lsu_amount as u128;
+
+    // Newton's method, starting above the root so the iterates decrease towards it
+    let mut root = product;
+    let mut next = product.div_ceil(2);
+    while next < root {
+        root = next;
+        next = (root + product / root) / 2;
+    }
+    root as u64
+}
+
+/// LP minted for a deposit into a funded pool and the PT and LSU it takes, at most `pt_amount`
+/// and `lsu_amount`. The pool keeps its ratio, so the side in excess is only partly used.
+pub fn proportional_deposit(
+    pool: &PtPool,
+    pt_amount: u64,
+    lsu_amount: u64,
+) -> Result<(u64, u64, u64), ProgramError> {
+    let lp_for_pt = (pt_amount as u128 * pool.lp_supply as u128)
+        .checked_div(pool.pt_reserve as u128)
+        .ok_or(YieldTokenizerError::InsufficientLiquidity)?;
+    let lp_for_lsu = (lsu_amount as u128 * pool.lp_supply as u128)
+        .checked_div(pool.lsu_reserve as u128)
+        .ok_or(YieldTokenizerError::InsufficientLiquidity)?;
+    let lp_out = lp_for_pt.min(lp_for_lsu);
+
+    // Rounded up so the LP is always fully backed
+    let pt_used = (pool.pt_reserve as u128 * lp_out).div_ceil(pool.lp_supply as u128);
+    let lsu_used = (pool.lsu_reserve as u128 * lp_out).div_ceil(pool.lp_supply as u128);
+
+    Ok((lp_out as u64, pt_used as u64, lsu_used as u64))
+}
+
+/// PT and LSU paid out for burning `lp_amount`, rounded down
+pub fn proportional_withdrawal(pool: &PtPool, lp_amount: u64) -> Result<(u64, u64), ProgramError> {
+    if lp_amount > pool.lp_supply {
+        return Err(YieldTokenizerError::InsufficientLiquidity.into());
+    }
+    let pt_out = pool.pt_reserve as u128 * lp_amount as u128 / pool.lp_supply as u128;
+    let lsu_out = pool.lsu_reserve as u128 * lp_amount as u128 / pool.lp_supply as u128;
+    Ok((pt_out as u64, lsu_out as u64))
+}
+
+impl PtPool {
+    /// Opens the pool with its first deposit, whose ratio sets the implied rate it trades at.
+    /// Returns the LP owed to the depositor.
+    pub fn open(
+        &mut self,
+        yield_tokenizer: &YieldTokenizerState,
+        now: i64,
+        pt_amount: u64,
+        lsu_amount: u64,
+    ) -> Result<u64, ProgramError> {
+        let lp_supply = initial_liquidity(pt_amount, lsu_amount);
+        if lp_supply <= MINIMUM_LIQUIDITY {
+            return Err(YieldTokenizerError::InsufficientLiquidity.into());
+        }
+
+        let years = years_to_maturity(now, yield_tokenizer.maturity_date, Rounding::Down)?;
+        let curve = Curve::with_anchor(self, self.initial_anchor, years)?;
+        self.last_ln_implied_rate = curve.ln_implied_rate(
+            pt_amount as i128,
+            yield_tokenizer.lsu_to_principal(lsu_amount)? as i128,
+        )?;
+
+        self.pt_reserve = pt_amount;
+        self.lsu_reserve = lsu_amount;
+        self.lp_supply = lp_supply;
+        self.oracle.initialize(now);
+        Ok(lp_supply - MINIMUM_LIQUIDITY)
+    }
+
+    /// Sells `pt_in` PT to the pool, returning the LSU paid out
+    pub fn swap_exact_pt_for_lsu(
+        &mut self,
+        yield_tokenizer: &YieldTokenizerState,
+        now: i64,
+        pt_in: u64,
+    ) -> Result<u64, ProgramError> {
+        let (curve, total_asset) = self.curve(yield_tokenizer, now)?;
+        let asset_out = curve.swap(self.pt_reserve as i128, total_asset, -(pt_in as i128))?;
+        let lsu_out = yield_tokenizer.principal_to_lsu(asset_out as u64)?;
+
+        self.pt_reserve = self
+            .pt_reserve
+            .checked_add(pt_in)
+            .ok_or(ProgramError::ArithmeticOverflow)?;
+        self.lsu_reserve = self
+            .lsu_reserve
+            .checked_sub(lsu_out)
+            .ok_or(YieldTokenizerError::InsufficientLiquidity)?;
+        self.update_implied_rate(&curve, yield_tokenizer, now)?;
+        Ok(lsu_out)
+    }
+
+    /// Buys PT from the pool with exactly `lsu_in` LSU, returning the PT paid out
+    pub fn swap_exact_lsu_for_pt(
+        &mut self,
+        yield_tokenizer: &YieldTokenizerState,
+        now: i64,
+        lsu_in: u64,
+    ) -> Result<u64, ProgramError> {
+        let (curve, total_asset) = self.curve(yield_tokenizer, now)?;
+        let asset_in = yield_tokenizer.lsu_to_principal(lsu_in)? as i128;
+        let pt_out =
+            curve.pt_out_for_exact_asset_in(self.pt_reserve as i128, total_asset, asset_in)? as u64;
+
+        self.pt_reserve = self
+            .pt_reserve
+            .checked_sub(pt_out)
+            .ok_or(YieldTokenizerError::InsufficientLiquidity)?;
+        self.lsu_reserve = self
+            .lsu_reserve
+            .checked_add(lsu_in)
+            .ok_or(ProgramError::ArithmeticOverflow)?;
+        self.update_implied_rate(&curve, yield_tokenizer, now)?;
+        Ok(pt_out)
+    }
+
+    /// Buys exactly `pt_out` PT from the pool, returning the LSU owed for it, rounded up
+    pub fn swap_lsu_for_exact_pt(
+        &mut self,
+        yield_tokenizer: &YieldTokenizerState,
+        now: i64,
+        pt_out: u64,
+    ) -> Result<u64, ProgramError> {
+        let (curve, total_asset) = self.curve(yield_tokenizer, now)?;
+        let asset_in = -curve.swap(self.pt_reserve as i128, total_asset, pt_out as i128)?;
+        let lsu_in = yield_tokenizer.principal_to_lsu_up(asset_in as u64)?;
+
+        self.pt_reserve = self
+            .pt_reserve
+            .checked_sub(pt_out)
+            .ok_or(YieldTokenizerError::InsufficientLiquidity)?;
+        self.lsu_reserve = self
+            .lsu_reserve
+            .checked_add(lsu_in)
+            .ok_or(ProgramError::ArithmeticOverflow)?;
+        self.update_implied_rate(&curve, yield_tokenizer, now)?;
+        Ok(lsu_in)
+    }
+
+    /// Most YT `lsu_in` buys through flash tokenization, where `lsu_in` plus the LSU from selling
+    /// the PT leg to the pool is tokenized. Doesn't change the pool, the PT sale is applied with
+    /// `swap_exact_pt_for_lsu`. Fails if the pool can't take the PT leg `lsu_in` pays for.
+    pub fn yt_out_for_exact_lsu_in(
+        &self,
+        yield_tokenizer: &YieldTokenizerState,
+        now: i64,
+        lsu_in: u64,
+    ) -> Result<u64, ProgramError> {
+        let (curve, total_asset) = self.curve(yield_tokenizer, now)?;
+        let total_pt = self.pt_reserve as i128;
+        let asset_in = yield_tokenizer.lsu_to_principal(lsu_in)? as i128;
+
+        // Selling PT only lowers its price, so YT costs at least its pre-trade price of
+        // `1 - 1 / exchange_rate` and the PT the pool can absorb caps the rest
+        let marginal_exchange_rate =
+            curve.exchange_rate(total_pt, total_asset, 0, Rounding::Down)?;
+        let max_pt_in = mul(total_pt + total_asset, MAX_PT_PROPORTION, Rounding::Down)? - total_pt;
+        let hi = match marginal_exchange_rate - ONE {
+            0 => max_pt_in,
+            yt_price => {
+                mul_div(asset_in, marginal_exchange_rate, yt_price, Rounding::Up)?.min(max_pt_in)
+            }
+        };
+
+        // Tokenizing `lsu_in` and selling all of its PT already buys `asset_in` YT
+        let yt_out = search_max(asset_in, hi, |yt_out| {
+            let asset_out = curve.swap(total_pt, total_asset, -yt_out)?;
+            let lsu_out = yield_tokenizer.principal_to_lsu(asset_out as u64)?;
+            let lsu_to_tokenize = yield_tokenizer.principal_to_lsu_up(yt_out as u64)?;
+            Ok(lsu_to_tokenize as u128 <= lsu_in as u128 + lsu_out as u128)
+        })?;
+
+        // Fails when the search was stopped by the PT the pool can absorb rather than by
+        // `lsu_in`, the rest of `lsu_in` would be tokenized for nothing
+        curve.swap(
+            total_pt,
+            total_asset,
+            -(yt_out + yt_out / SEARCH_PRECISION + 1),
+        )?;
+        Ok(yt_out as u64)
+    }
+
+    /// Curve at `now` and the LSU reserve valued in principal
+    fn curve(
+        &self,
+        yield_tokenizer: &YieldTokenizerState,
+        now: i64,
+    ) -> Result<(Curve, i128), ProgramError> {
+        if self.lp_supply == 0 {
+            return Err(YieldTokenizerError::InsufficientLiquidity.into());
+        }
+        let years = years_to_maturity(now, yield_tokenizer.maturity_date, Rounding::Down)?;
+        let total_asset = yield_tokenizer.lsu_to_principal(self.lsu_reserve)? as i128;
+        Ok((Curve::new(self, total_asset, years)?, total_asset))
+    }
+
+    /// Records the rate the pool traded at up to `now` in the oracle before moving it to the
+    /// rate of the new reserves
+    fn update_implied_rate(
+        &mut self,
+        curve: &Curve,
+        yield_tokenizer: &YieldTokenizerState,
+        now: i64,
+    ) -> Result<(), ProgramError> {
+        self.oracle.update(now, self.last_ln_implied_rate)?;
+        self.last_ln_implied_rate = curve.ln_implied_rate(
+            self.pt_reserve as i128,
+            yield_tokenizer.lsu_to_principal(self.lsu_reserve)? as i128,
+        )?;
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use {
+        super::*,
+        crate::{
+            adapter::LsuAdapter,
+            state::{
+                PauseState, EXCHANGE_RATE_PRECISION, YIELD_INDEX_PRECISION,
+                YIELD_TOKENIZER_DISCRIMINATOR, YIELD_TOKENIZER_VERSION,
+            },
+        },
+        solana_program::pubkey::Pubkey,
+    };
+
+    const MATURITY: i64 = 1_806_451_200; // 2027-03-31
+    const DAY: i64 = 86_400;
+    const YEAR: i64 = 365 * DAY;
+    const RESERVE: u64 = 1_000_000_000_000;
+
+    /// Tokenizer whose LSU has earned `yield_bps` since initialization
+    fn tokenizer(yield_bps: u64) -> YieldTokenizerState {
+        let yield_index = YIELD_INDEX_PRECISION * yield_bps / 10_000;
+        YieldTokenizerState {
+            discriminator: YIELD_TOKENIZER_DISCRIMINATOR,
+            version: YIELD_TOKENIZER_VERSION,
+            pt: Pubkey::new_unique(),
+            yt: Pubkey::new_unique(),
+            maturity_date: MATURITY,
+            lsu_mint: Pubkey::new_unique(),
+            lsu_vault: Pubkey::new_unique(),
+            adapter: LsuAdapter::Marinade,
+            lsu_exchange_rate_account: Pubkey::new_unique(),
+            initial_exchange_rate: EXCHANGE_RATE_PRECISION,
+            last_exchange_rate: (EXCHANGE_RATE_PRECISION as u128 * YIELD_INDEX_PRECISION as u128
+                / (YIELD_INDEX_PRECISION - yield_index) as u128)
+                as u64,
+            maturity_exchange_rate: 0,
+            yield_index,
+            pause_state: PauseState::Active,
+            initializer: Pubkey::new_unique(),
+            bump: 0,
+            pt_bump: 0,
+            yt_bump: 0,
+            treasury_bump: 0,
+        }
+    }
+
+    fn pool(pt_reserve: u64, lsu_reserve: u64, lp_supply: u64) -> PtPool {
+        PtPool {
+            pt_reserve,
+            lsu_reserve,
+            lp_supply,
+            ..PtPool::new(
+                Pubkey::new_unique(),
+                Pubkey::new_unique(),
+                Pubkey::new_unique(),
+                Pubkey::new_unique(),
+                50 * ONE,
+                ONE + ONE / 20,
+                ONE * 3 / 1_000,
+                255,
+            )
+        }
+    }
+
+    /// Pool opened at `now` with `RESERVE` PT and LSU
+    fn open_pool(yield_tokenizer: &YieldTokenizerState, now: i64) -> PtPool {
+        let mut pool = pool(0, 0, 0);
+        pool.open(yield_tokenizer, now, RESERVE, RESERVE).unwrap();
+        pool
+    }
+
+    #[test]
+    fn test_lsu_pt_round_trip_loses_value() {
+        let yield_tokenizer = tokenizer(500);
+        let now = MATURITY - YEAR / 2;
+
+        for lsu_in in [1_000, 1_000_000_000, 50_000_000_000, 200_000_000_000] {
+            let mut pool = open_pool(&yield_tokenizer, now);
+            let pt_out = pool
+                .swap_exact_lsu_for_pt(&yield_tokenizer, now, lsu_in)
+                .unwrap();
+            assert!(pt_out > 0);
+            let lsu_back = pool
+                .swap_exact_pt_for_lsu(&yield_tokenizer, now, pt_out)
+                .unwrap();
+            assert!(lsu_back <= lsu_in, "{} bought back {}", lsu_in, lsu_back);
+            assert_eq!(pool.pt_reserve, RESERVE);
+            assert!(pool.lsu_reserve >= RESERVE);
+        }
+    }
+
+    #[test]
+    fn test_pt_lsu_round_trip_loses_value() {
+        let yield_tokenizer = tokenizer(500);
+        let now = MATURITY - YEAR / 2;
+
+        for pt_in in [1_000, 1_000_000_000, 50_000_000_000, 200_000_000_000] {
+            let mut pool = open_pool(&yield_tokenizer, now);
+            let lsu_out = pool
+                .swap_exact_pt_for_lsu(&yield_tokenizer, now, pt_in)
+                .unwrap();
+            let pt_back = pool
+                .swap_exact_lsu_for_pt(&yield_tokenizer, now, lsu_out)
+                .unwrap();
+            assert!(pt_back <= pt_in, "{} bought back {}", pt_in, pt_back);
+
+            // Buying an exact amount back costs more than the sale paid
+            let mut pool = open_pool(&yield_tokenizer, now);
+            let lsu_out = pool
+                .swap_exact_pt_for_lsu(&yield_tokenizer, now, pt_in)
+                .unwrap();
+            let lsu_in = pool
+                .swap_lsu_for_exact_pt(&yield_tokenizer, now, pt_in)
+                .unwrap();
+            assert!(lsu_in >= lsu_out, "{} bought back for {}", lsu_out, lsu_in);
+            assert_eq!(pool.pt_reserve, RESERVE);
+            assert!(pool.lsu_reserve >= RESERVE);
+        }
+    }
+
+    #[test]
+    fn test_exact_in_and_exact_out_agree() {
+        let yield_tokenizer = tokenizer(0);
+        let now = MATURITY - YEAR;
+
+        let mut pool = open_pool(&yield_tokenizer, now);
+        let pt_out = pool
+            .swap_exact_lsu_for_pt(&yield_tokenizer, now, 10_000_000_000)
+            .unwrap();
+        let mut pool = open_pool(&yield_tokenizer, now);
+        let lsu_in = pool
+            .swap_lsu_for_exact_pt(&yield_tokenizer, now, pt_out)
+            .unwrap();
+        assert!(lsu_in <= 10_000_000_000);
+        assert!(10_000_000_000 - lsu_in <= 10);
+    }
+
+    #[test]
+    fn test_search_max_finds_boundary() {
+        let up_to = |boundary: i128| move |amount: i128| Ok(amount <= boundary);
+
+        // Below `SEARCH_PRECISION` the search is exact whatever the guess
+        for boundary in [0, 1, 7, 1_000, 999_999_999] {
+            for guess in [0, 1, boundary, boundary + 1, 2_000_000_000] {
+                assert_eq!(
+                    search_max(guess, 2_000_000_000, up_to(boundary)).unwrap(),
+                    boundary
+                );
+            }
+        }
+
+        // Above it the search stops within one part in `SEARCH_PRECISION`, never past it
+        for boundary in [1_000_000_007, 123_456_789_012_345_678] {
+            for guess in [0, boundary / 2, boundary + 1] {
+                let found = search_max(guess, 2 * boundary, up_to(boundary)).unwrap();
+                assert!(found <= boundary);
+                assert!(boundary - found <= found / SEARCH_PRECISION);
+            }
+        }
+
+        assert_eq!(search_max(0, 500, up_to(1_000)).unwrap(), 500);
+    }
+
+    #[test]
+    fn test_search_max_errors() {
+        // Untradeable amounts are unaffordable
+        let found = search_max(0, 1_000, |amount| {
+            if amount > 600 {
+                Err(YieldTokenizerError::InsufficientLiquidity.into())
+            } else {
+                Ok(true)
+            }
+        })
+        .unwrap();
+        assert_eq!(found, 600);
+
+        // Anything else fails the search
+        assert_eq!(
+            search_max(0, 1_000, |_| Err(ProgramError::ArithmeticOverflow)),
+            Err(ProgramError::ArithmeticOverflow)
+        );
+    }
+
+    #[test]
+    fn test_pt_out_for_exact_asset_in_is_max() {
+        let yield_tokenizer = tokenizer(500);
+        let now = MATURITY - YEAR / 3;
+        let pool = open_pool(&yield_tokenizer, now);
+        let (curve, total_asset) = pool.curve(&yield_tokenizer, now).unwrap();
+        let total_pt = pool.pt_reserve as i128;
+
+        for asset_in in [1, 999, 1_000_000_000, 300_000_000_000] {
+            let pt_out = curve
+                .pt_out_for_exact_asset_in(total_pt, total_asset, asset_in)
+                .unwrap();
+            assert!(-curve.swap(total_pt, total_asset, pt_out).unwrap() <= asset_in);
+
+            let more = pt_out + pt_out / SEARCH_PRECISION + 1;
+            match curve.swap(total_pt, total_asset, more) {
+                Ok(net_asset_to_account) => assert!(-net_asset_to_account > asset_in),
+                Err(err) => assert_eq!(err, YieldTokenizerError::InsufficientLiquidity.into()),
+            }
+        }
+    }
+
+    #[test]
+    fn test_curve_tightens_toward_maturity() {
+        let yield_tokenizer = tokenizer(300);
+        let pool = open_pool(&yield_tokenizer, MATURITY - YEAR);
+        let total_pt = pool.pt_reserve as i128;
+
+        let mut last: Option<(Curve, i128, i128)> = None;
+        for time_left in [YEAR, YEAR / 4, 30 * DAY, DAY, 60] {
+            let now = MATURITY - time_left;
+            let (curve, total_asset) = pool.curve(&yield_tokenizer, now).unwrap();
+
+            // Re-anchoring keeps the implied rate where the last trade left it
+            let ln_implied_rate = curve.ln_implied_rate(total_pt, total_asset).unwrap();
+            assert!((ln_implied_rate - pool.last_ln_implied_rate).abs() <= ONE / 1_000_000);
+
+            let exchange_rate = curve
+                .exchange_rate(total_pt, total_asset, 0, Rounding::Down)
+                .unwrap();
+            let impact = exchange_rate
+                - curve
+                    .exchange_rate(total_pt, total_asset, total_pt / 10, Rounding::Down)
+                    .unwrap();
+            assert!(curve.fee_rate >= ONE);
+
+            if let Some((last, last_exchange_rate, last_impact)) = last {
+                assert!(curve.rate_scalar > last.rate_scalar);
+                assert!(curve.rate_anchor < last.rate_anchor);
+                assert!(curve.fee_rate < last.fee_rate);
+                assert!(exchange_rate < last_exchange_rate);
+                assert!(impact < last_impact);
+            }
+            last = Some((curve, exchange_rate, impact));
+        }
+
+        // A minute out PT trades within a millionth of its redemption value, fee included
+        let (curve, exchange_rate, _) = last.unwrap();
+        assert!(exchange_rate - ONE < ONE / 1_000_000);
+        assert!(curve.fee_rate - ONE < ONE / 1_000_000);
+
+        assert_eq!(
+            pool.curve(&yield_tokenizer, MATURITY).err(),
+            Some(YieldTokenizerError::Expired.into())
+        );
+    }
+
+    #[test]
+    fn test_proportional_deposit_rounds_toward_pool() {
+        let pool = pool(1_000_003, 999_983, 999_989);
+
+        for (pt_amount, lsu_amount) in [
+            (1, 1),
+            (7, 13),
+            (1_000, 999),
+            (123_457, 1_000_000),
+            (5_000_000, 4_999_999),
+        ] {
+            let (lp_out, pt_used, lsu_used) =
+                proportional_deposit(&pool, pt_amount, lsu_amount).unwrap();
+            assert!(pt_used <= pt_amount && lsu_used <= lsu_amount);
+            // The LP minted is never worth more than what was taken for it
+            assert!(
+                pt_used as u128 * pool.lp_supply as u128
+                    >= lp_out as u128 * pool.pt_reserve as u128
+            );
+            assert!(
+                lsu_used as u128 * pool.lp_supply as u128
+                    >= lp_out as u128 * pool.lsu_reserve as u128
+            );
+
+            let mut deposited = self::pool(
+                pool.pt_reserve + pt_used,
+                pool.lsu_reserve + lsu_used,
+                pool.lp_supply + lp_out,
+            );
+            let (pt_out, lsu_out) = proportional_withdrawal(&deposited, lp_out).unwrap();
+            assert!(pt_out <= pt_used && lsu_out <= lsu_used);
+            deposited.pt_reserve -= pt_out;
+            deposited.lsu_reserve -= lsu_out;
+            assert!(deposited.pt_reserve >= pool.pt_reserve);
+            assert!(deposited.lsu_reserve >= pool.lsu_reserve);
+        }
+
+        assert_eq!(
+            proportional_deposit(&self::pool(0, 0, 0), 1_000, 1_000).err(),
+            Some(YieldTokenizerError::InsufficientLiquidity.into())
+        );
+    }
+
+    #[test]
+    fn test_proportional_withdrawal_rounds_toward_pool() {
+        let pool = pool(1_000_003, 999_983, 999_989);
+
+        for lp_amount in [0, 1, 2, 999, 500_000, 999_989] {
+            let (pt_out, lsu_out) = proportional_withdrawal(&pool, lp_amount).unwrap();
+            assert!(
+                pt_out as u128 * pool.lp_supply as u128
+                    <= lp_amount as u128 * pool.pt_reserve as u128
+            );
+            assert!(
+                lsu_out as u128 * pool.lp_supply as u128
+                    <= lp_amount as u128 * pool.lsu_reserve as u128
+            );
+        }
+        assert_eq!(proportional_withdrawal(&pool, 1), Ok((1, 0)));
+        assert_eq!(
+            proportional_withdrawal(&pool, 999_989),
+            Ok((1_000_003, 999_983))
+        );
+        assert_eq!(
+            proportional_withdrawal(&pool, 999_990),
+            Err(YieldTokenizerError::InsufficientLiquidity.into())
+        );
+    }
+
+    /// Vault's LSU left after `SwapExactLsuForYt` takes `lsu_in` and the pool's payment for the
+    /// PT leg and mints `yt_out` PT and YT, negative when it's under-collateralized
+    fn lsu_for_yt_surplus(
+        yield_tokenizer: &YieldTokenizerState,
+        lsu_in: u64,
+        lsu_from_pool: u64,
+        yt_out: u64,
+    ) -> i128 {
+        lsu_in as i128 + lsu_from_pool as i128
+            - yield_tokenizer.principal_to_lsu_up(yt_out).unwrap() as i128
+    }
+
+    #[test]
+    fn test_lsu_for_yt_keeps_vault_collateralized() {
+        for yield_bps in [0, 500, 3_000] {
+            let yield_tokenizer = tokenizer(yield_bps);
+            for time_left in [YEAR, 30 * DAY, DAY] {
+                let now = MATURITY - time_left;
+                for lsu_in in [1, 3, 1_000, 1_000_000_007, 20_000_000_000] {
+                    let mut pool = open_pool(&yield_tokenizer, now);
+                    let yt_out = pool
+                        .yt_out_for_exact_lsu_in(&yield_tokenizer, now, lsu_in)
+                        .unwrap();
+                    // Never worse than tokenizing `lsu_in` directly
+                    assert!(yt_out >= yield_tokenizer.lsu_to_principal(lsu_in).unwrap());
+
+                    let lsu_from_pool = pool
+                        .swap_exact_pt_for_lsu(&yield_tokenizer, now, yt_out)
+                        .unwrap();
+                    assert!(
+                        lsu_for_yt_surplus(&yield_tokenizer, lsu_in, lsu_from_pool, yt_out) >= 0,
+                        "{} LSU for {} YT at {} bps",
+                        lsu_in,
+                        yt_out,
+                        yield_bps
+                    );
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn test_yt_out_for_exact_lsu_in_is_max() {
+        let yield_tokenizer = tokenizer(500);
+        let now = MATURITY - YEAR / 2;
+
+        for lsu_in in [1_000, 1_000_000_007, 20_000_000_000] {
+            let pool = open_pool(&yield_tokenizer, now);
+            let yt_out = pool
+                .yt_out_for_exact_lsu_in(&yield_tokenizer, now, lsu_in)
+                .unwrap();
+
+            // Just past the search precision the vault would be short. YT is cheap next to PT, so
+            // a lamport of rounding in the pool's payment moves the boundary by a few dozen YT.
+            let more = yt_out + yt_out / SEARCH_PRECISION as u64 + 100;
+            let mut pool = open_pool(&yield_tokenizer, now);
+            match pool.swap_exact_pt_for_lsu(&yield_tokenizer, now, more) {
+                Ok(lsu_from_pool) => {
+                    assert!(lsu_for_yt_surplus(&yield_tokenizer, lsu_in, lsu_from_pool, more) < 0)
+                }
+                Err(err) => assert_eq!(err, YieldTokenizerError::InsufficientLiquidity.into()),
+            }
+        }
+    }
+
+    #[test]
+    fn test_lsu_for_yt_capped_by_pool_depth() {
+        let yield_tokenizer = tokenizer(500);
+        let now = MATURITY - YEAR / 2;
+        let pool = open_pool(&yield_tokenizer, now);
+
+        // More LSU than the pool can take the PT leg for is refused rather than partly wasted
+        assert_eq!(
+            pool.yt_out_for_exact_lsu_in(&yield_tokenizer, now, 100 * RESERVE),
+            Err(YieldTokenizerError::InsufficientLiquidity.into())
+        );
+
+        // Half the reserve would tokenize for far more YT than the pool can take the PT of
+        assert_eq!(
+            pool.yt_out_for_exact_lsu_in(&yield_tokenizer, now, RESERVE / 2),
+            Err(YieldTokenizerError::InsufficientLiquidity.into())
+        );
+        assert!(pool
+            .yt_out_for_exact_lsu_in(&yield_tokenizer, now, RESERVE / 100)
+            .is_ok());
+    }
+
+    #[test]
+    fn test_yt_for_lsu_round_trip() {
+        for yield_bps in [0, 500, 3_000] {
+            let yield_tokenizer = tokenizer(yield_bps);
+            for time_left in [YEAR, 30 * DAY, DAY] {
+                let now = MATURITY - time_left;
+                for lsu_in in [1_000, 1_000_000_007, 20_000_000_000] {
+                    let mut pool = open_pool(&yield_tokenizer, now);
+                    let yt_out = pool
+                        .yt_out_for_exact_lsu_in(&yield_tokenizer, now, lsu_in)
+                        .unwrap();
+                    let lsu_from_pool = pool
+                        .swap_exact_pt_for_lsu(&yield_tokenizer, now, yt_out)
+                        .unwrap();
+                    let vault = lsu_for_yt_surplus(&yield_tokenizer, lsu_in, lsu_from_pool, yt_out)
+                        + yield_tokenizer.principal_to_lsu_up(yt_out).unwrap() as i128;
+
+                    // `SwapExactYtForLsu`: buy the PT leg back, burn the pair and pay the pool
+                    // out of the redeemed LSU
+                    let lsu_to_pool = pool
+                        .swap_lsu_for_exact_pt(&yield_tokenizer, now, yt_out)
+                        .unwrap();
+                    let redeemed = yield_tokenizer.principal_to_lsu(yt_out).unwrap();
+                    assert!(lsu_to_pool <= redeemed);
+                    let lsu_out = redeemed - lsu_to_pool;
+
+                    assert!(lsu_out <= lsu_in, "{} LSU came back as {}", lsu_in, lsu_out);
+                    assert!(vault >= redeemed as i128);
+                    assert_eq!(pool.pt_reserve, RESERVE);
+                    assert!(pool.lsu_reserve >= RESERVE);
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn test_yt_for_lsu_needs_pool_liquidity() {
+        let yield_tokenizer = tokenizer(500);
+        let now = MATURITY - YEAR / 2;
+        let mut pool = open_pool(&yield_tokenizer, now);
+
+        // The pool can't sell back more PT than it holds
+        assert_eq!(
+            pool.swap_lsu_for_exact_pt(&yield_tokenizer, now, RESERVE),
+            Err(YieldTokenizerError::InsufficientLiquidity.into())
+        );
+        assert_eq!(pool.pt_reserve, RESERVE);
+        assert_eq!(pool.lsu_reserve, RESERVE);
+    }
+}
@@ -0,0 +1,494 @@
+//! Invokes the tokenizer from other programs. Build with the `no-entrypoint` feature to link the
+//! crate into a program. Accounts are the same, in the same order, as the `instruction` builders,
+//! with `program` the tokenizer program account. The series' PDAs are derived from `program`'s
+//! key, so any deployment of the tokenizer can be invoked. `signer_seeds` signs for any PDA among
+//! them, typically the owner of the LSU, PT and YT token accounts.
+
+use {
+    crate::instruction,
+    solana_program::{
+        account_info::AccountInfo, entrypoint::ProgramResult, program::invoke_signed,
+    },
+};
+
+/// Tokenize `amount` LSU into PT and YT, see `instruction::tokenize_yield`
+#[allow(clippy::too_many_arguments)]
+pub fn tokenize_yield<'info>(
+    program: &AccountInfo<'info>,
+    buyer: &AccountInfo<'info>,
+    config: &AccountInfo<'info>,
+    yield_tokenizer: &AccountInfo<'info>,
+    lsu_mint: &AccountInfo<'info>,
+    lsu_exchange_rate_account: &AccountInfo<'info>,
+    pt_mint: &AccountInfo<'info>,
+    yt_mint: &AccountInfo<'info>,
+    lsu_vault: &AccountInfo<'info>,
+    buyer_lsu_ata: &AccountInfo<'info>,
+    buyer_pt_ata: &AccountInfo<'info>,
+    buyer_yt_ata: &AccountInfo<'info>,
+    buyer_position: &AccountInfo<'info>,
+    token_program: &AccountInfo<'info>,
+    yield_token_program: &AccountInfo<'info>,
+    atoken_program: &AccountInfo<'info>,
+    system_program: &AccountInfo<'info>,
+    amount: u64,
+    signer_seeds: &[&[&[u8]]],
+) -> ProgramResult {
+    invoke_signed(
+        &instruction::tokenize_yield(
+            program.key,
+            buyer.key,
+            config.key,
+            yield_tokenizer.key,
+            lsu_mint.key,
+            lsu_exchange_rate_account.key,
+            pt_mint.key,
+            yt_mint.key,
+            lsu_vault.key,
+            buyer_lsu_ata.key,
+            buyer_pt_ata.key,
+            buyer_yt_ata.key,
+            buyer_position.key,
+            yield_token_program.key,
+            amount,
+        )?,
+        &[
+            buyer.clone(),
+            config.clone(),
+            yield_tokenizer.clone(),
+            lsu_mint.clone(),
+            lsu_exchange_rate_account.clone(),
+            pt_mint.clone(),
+            yt_mint.clone(),
+            lsu_vault.clone(),
+            buyer_lsu_ata.clone(),
+            buyer_pt_ata.clone(),
+            buyer_yt_ata.clone(),
+            buyer_position.clone(),
+            token_program.clone(),
+            yield_token_program.clone(),
+            atoken_program.clone(),
+            system_program.clone(),
+            program.clone(),
+        ],
+        signer_seeds,
+    )
+}
+
+/// Redeem `amount` PT and YT for LSU, see `instruction::redeem`
+#[allow(clippy::too_many_arguments)]
+pub fn redeem<'info>(
+    program: &AccountInfo<'info>,
+    redeemer: &AccountInfo<'info>,
+    config: &AccountInfo<'info>,
+    yield_tokenizer: &AccountInfo<'info>,
+    lsu_mint: &AccountInfo<'info>,
+    lsu_exchange_rate_account: &AccountInfo<'info>,
+    pt_mint: &AccountInfo<'info>,
+    yt_mint: &AccountInfo<'info>,
+    lsu_vault: &AccountInfo<'info>,
+    treasury: &AccountInfo<'info>,
+    redeemer_lsu_ata: &AccountInfo<'info>,
+    redeemer_pt_ata: &AccountInfo<'info>,
+    redeemer_yt_ata: &AccountInfo<'info>,
+    redeemer_position: &AccountInfo<'info>,
+    token_program: &AccountInfo<'info>,
+    yield_token_program: &AccountInfo<'info>,
+    atoken_program: &AccountInfo<'info>,
+    system_program: &AccountInfo<'info>,
+    amount: u64,
+    signer_seeds: &[&[&[u8]]],
+) -> ProgramResult {
+    invoke_signed(
+        &instruction::redeem(
+            program.key,
+            redeemer.key,
+            config.key,
+            yield_tokenizer.key,
+            lsu_mint.key,
+            lsu_exchange_rate_account.key,
+            pt_mint.key,
+            yt_mint.key,
+            lsu_vault.key,
+            treasury.key,
+            redeemer_lsu_ata.key,
+            redeemer_pt_ata.key,
+            redeemer_yt_ata.key,
+            redeemer_position.key,
+            yield_token_program.key,
+            amount,
+        )?,
+        &[
+            redeemer.clone(),
+            config.clone(),
+            yield_tokenizer.clone(),
+            lsu_mint.clone(),
+            lsu_exchange_rate_account.clone(),
+            pt_mint.clone(),
+            yt_mint.clone(),
+            lsu_vault.clone(),
+            treasury.clone(),
+            redeemer_lsu_ata.clone(),
+            redeemer_pt_ata.clone(),
+            redeemer_yt_ata.clone(),
+            redeemer_position.clone(),
+            token_program.clone(),
+            yield_token_program.clone(),
+            atoken_program.clone(),
+            system_program.clone(),
+            program.clone(),
+        ],
+        signer_seeds,
+    )
+}
+
+/// Collect the yield owed to `claimer`'s YT, see `instruction::claim_yield`
+#[allow(clippy::too_many_arguments)]
+pub fn claim_yield<'info>(
+    program: &AccountInfo<'info>,
+    claimer: &AccountInfo<'info>,
+    config: &AccountInfo<'info>,
+    yield_tokenizer: &AccountInfo<'info>,
+    lsu_mint: &AccountInfo<'info>,
+    lsu_exchange_rate_account: &AccountInfo<'info>,
+    yt_mint: &AccountInfo<'info>,
+    lsu_vault: &AccountInfo<'info>,
+    treasury: &AccountInfo<'info>,
+    claimer_lsu_ata: &AccountInfo<'info>,
+    claimer_yt_ata: &AccountInfo<'info>,
+    claimer_position: &AccountInfo<'info>,
+    token_program: &AccountInfo<'info>,
+    atoken_program: &AccountInfo<'info>,
+    system_program: &AccountInfo<'info>,
+    signer_seeds: &[&[&[u8]]],
+) -> ProgramResult {
+    invoke_signed(
+        &instruction::claim_yield(
+            program.key,
+            claimer.key,
+            config.key,
+            yield_tokenizer.key,
+            lsu_mint.key,
+            lsu_exchange_rate_account.key,
+            yt_mint.key,
+            lsu_vault.key,
+            treasury.key,
+            claimer_lsu_ata.key,
+            claimer_yt_ata.key,
+            claimer_position.key,
+        )?,
+        &[
+            claimer.clone(),
+            config.clone(),
+            yield_tokenizer.clone(),
+            lsu_mint.clone(),
+            lsu_exchange_rate_account.clone(),
+            yt_mint.clone(),
+            lsu_vault.clone(),
+            treasury.clone(),
+            claimer_lsu_ata.clone(),
+            claimer_yt_ata.clone(),
+            claimer_position.clone(),
+            token_program.clone(),
+            atoken_program.clone(),
+            system_program.clone(),
+            program.clone(),
+        ],
+        signer_seeds,
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use {
+        super::*,
+        solana_program::{
+            instruction::Instruction,
+            program_stubs::{self, SyscallStubs},
+            pubkey::Pubkey,
+            system_program,
+        },
+        std::cell::RefCell,
+    };
+
+    /// Seeds of a PDA owning the caller's token accounts
+    const SIGNER_SEEDS: &[&[&[u8]]] = &[&[b"owner", &[255]]];
+
+    /// What an invoke passed on, the accounts by key
+    struct Invoked {
+        instruction: Instruction,
+        keys: Vec<Pubkey>,
+        signers_seeds: Vec<Vec<Vec<u8>>>,
+    }
+
+    thread_local! {
+        /// The last invoke on this thread
+        static INVOKED: RefCell<Option<Invoked>> = const { RefCell::new(None) };
+    }
+
+    /// Records the invoke instead of the default stub's no-op, everything else is left as is
+    struct RecordInvoke;
+
+    impl SyscallStubs for RecordInvoke {
+        fn sol_invoke_signed(
+            &self,
+            instruction: &Instruction,
+            account_infos: &[AccountInfo],
+            signers_seeds: &[&[&[u8]]],
+        ) -> ProgramResult {
+            let recorded = Invoked {
+                instruction: instruction.clone(),
+                keys: account_infos.iter().map(|info| *info.key).collect(),
+                signers_seeds: signers_seeds
+                    .iter()
+                    .map(|seeds| seeds.iter().map(|seed| seed.to_vec()).collect())
+                    .collect(),
+            };
+            INVOKED.with(|invoked| invoked.replace(Some(recorded)));
+            Ok(())
+        }
+    }
+
+    /// Owned backing of an `AccountInfo`
+    struct TestAccount {
+        key: Pubkey,
+        lamports: u64,
+        data: Vec<u8>,
+        owner: Pubkey,
+    }
+
+    impl TestAccount {
+        fn info(&mut self) -> AccountInfo<'_> {
+            AccountInfo::new(
+                &self.key,
+                false,
+                true,
+                &mut self.lamports,
+                &mut self.data,
+                &self.owner,
+                false,
+                0,
+            )
+        }
+    }
+
+    /// The tokenizer program, deployed somewhere other than `crate::id()`, then `len` accounts
+    /// with unique keys and `programs`, the programs the instruction names by their ids
+    fn test_accounts(len: usize, programs: &[Pubkey]) -> Vec<TestAccount> {
+        std::iter::repeat_with(Pubkey::new_unique)
+            .take(1 + len)
+            .chain(programs.iter().copied())
+            .map(|key| TestAccount {
+                key,
+                lamports: 0,
+                data: vec![],
+                owner: system_program::id(),
+            })
+            .collect()
+    }
+
+    /// Checks `invoke` passed on `expected` with the accounts in its order followed by the
+    /// program, and the signer seeds
+    fn assert_invokes(invoke: impl FnOnce() -> ProgramResult, expected: Instruction) {
+        program_stubs::set_syscall_stubs(Box::new(RecordInvoke));
+        invoke().unwrap();
+        let invoked = INVOKED.with(|invoked| invoked.take()).unwrap();
+
+        let expected_keys: Vec<_> = expected
+            .accounts
+            .iter()
+            .map(|meta| meta.pubkey)
+            .chain([expected.program_id])
+            .collect();
+        assert_eq!(invoked.instruction, expected);
+        assert_eq!(invoked.keys, expected_keys);
+        assert_eq!(
+            invoked.signers_seeds,
+            vec![vec![b"owner".to_vec(), vec![255]]]
+        );
+    }
+
+    #[test]
+    fn test_tokenize_yield() {
+        let mut accounts = test_accounts(
+            12,
+            &[
+                spl_token::id(),
+                spl_token_2022::id(),
+                spl_associated_token_account::id(),
+                system_program::id(),
+            ],
+        );
+        let infos: Vec<_> = accounts.iter_mut().map(TestAccount::info).collect();
+        let [program, buyer, config, yield_tokenizer, lsu_mint, lsu_exchange_rate_account, pt_mint, yt_mint, lsu_vault, buyer_lsu_ata, buyer_pt_ata, buyer_yt_ata, buyer_position, token_program, yield_token_program, atoken_program, system_program] =
+            &infos[..]
+        else {
+            unreachable!();
+        };
+
+        assert_invokes(
+            || {
+                tokenize_yield(
+                    program,
+                    buyer,
+                    config,
+                    yield_tokenizer,
+                    lsu_mint,
+                    lsu_exchange_rate_account,
+                    pt_mint,
+                    yt_mint,
+                    lsu_vault,
+                    buyer_lsu_ata,
+                    buyer_pt_ata,
+                    buyer_yt_ata,
+                    buyer_position,
+                    token_program,
+                    yield_token_program,
+                    atoken_program,
+                    system_program,
+                    7,
+                    SIGNER_SEEDS,
+                )
+            },
+            instruction::tokenize_yield(
+                program.key,
+                buyer.key,
+                config.key,
+                yield_tokenizer.key,
+                lsu_mint.key,
+                lsu_exchange_rate_account.key,
+                pt_mint.key,
+                yt_mint.key,
+                lsu_vault.key,
+                buyer_lsu_ata.key,
+                buyer_pt_ata.key,
+                buyer_yt_ata.key,
+                buyer_position.key,
+                yield_token_program.key,
+                7,
+            )
+            .unwrap(),
+        );
+    }
+
+    #[test]
+    fn test_redeem() {
+        let mut accounts = test_accounts(
+            13,
+            &[
+                spl_token::id(),
+                spl_token_2022::id(),
+                spl_associated_token_account::id(),
+                system_program::id(),
+            ],
+        );
+        let infos: Vec<_> = accounts.iter_mut().map(TestAccount::info).collect();
+        let [program, redeemer, config, yield_tokenizer, lsu_mint, lsu_exchange_rate_account, pt_mint, yt_mint, lsu_vault, treasury, redeemer_lsu_ata, redeemer_pt_ata, redeemer_yt_ata, redeemer_position, token_program, yield_token_program, atoken_program, system_program] =
+            &infos[..]
+        else {
+            unreachable!();
+        };
+
+        assert_invokes(
+            || {
+                redeem(
+                    program,
+                    redeemer,
+                    config,
+                    yield_tokenizer,
+                    lsu_mint,
+                    lsu_exchange_rate_account,
+                    pt_mint,
+                    yt_mint,
+                    lsu_vault,
+                    treasury,
+                    redeemer_lsu_ata,
+                    redeemer_pt_ata,
+                    redeemer_yt_ata,
+                    redeemer_position,
+                    token_program,
+                    yield_token_program,
+                    atoken_program,
+                    system_program,
+                    7,
+                    SIGNER_SEEDS,
+                )
+            },
+            instruction::redeem(
+                program.key,
+                redeemer.key,
+                config.key,
+                yield_tokenizer.key,
+                lsu_mint.key,
+                lsu_exchange_rate_account.key,
+                pt_mint.key,
+                yt_mint.key,
+                lsu_vault.key,
+                treasury.key,
+                redeemer_lsu_ata.key,
+                redeemer_pt_ata.key,
+                redeemer_yt_ata.key,
+                redeemer_position.key,
+                yield_token_program.key,
+                7,
+            )
+            .unwrap(),
+        );
+    }
+
+    #[test]
+    fn test_claim_yield() {
+        let mut accounts = test_accounts(
+            11,
+            &[
+                spl_token::id(),
+                spl_associated_token_account::id(),
+                system_program::id(),
+            ],
+        );
+        let infos: Vec<_> = accounts.iter_mut().map(TestAccount::info).collect();
+        let [program, claimer, config, yield_tokenizer, lsu_mint, lsu_exchange_rate_account, yt_mint, lsu_vault, treasury, claimer_lsu_ata, claimer_yt_ata, claimer_position, token_program, atoken_program, system_program] =
+            &infos[..]
+        else {
+            unreachable!();
+        };
+
+        assert_invokes(
+            || {
+                claim_yield(
+                    program,
+                    claimer,
+                    config,
+                    yield_tokenizer,
+                    lsu_mint,
+                    lsu_exchange_rate_account,
+                    yt_mint,
+                    lsu_vault,
+                    treasury,
+                    claimer_lsu_ata,
+                    claimer_yt_ata,
+                    claimer_position,
+                    token_program,
+                    atoken_program,
+                    system_program,
+                    SIGNER_SEEDS,
+                )
+            },
+            instruction::claim_yield(
+                program.key,
+                claimer.key,
+                config.key,
+                yield_tokenizer.key,
+                lsu_mint.key,
+                lsu_exchange_rate_account.key,
+                yt_mint.key,
+                lsu_vault.key,
+                treasury.key,
+                claimer_lsu_ata.key,
+                claimer_yt_ata.key,
+                claimer_position.key,
+            )
+            .unwrap(),
+        );
+    }
+}
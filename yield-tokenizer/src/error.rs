@@ -29,6 +29,56 @@ pub enum YieldTokenizerError {
     Expired,
     #[error("Has not reached maturity")]
     Immature,
+    #[error("Invalid yield position address")]
+    InvalidYieldPosition,
+    #[error("Invalid exchange rate account")]
+    InvalidExchangeRateAccount,
+    #[error("Stale exchange rate")]
+    StaleExchangeRate,
+    #[error("Invalid maturity date")]
+    InvalidMaturityDate,
+    #[error("Invalid config address")]
+    InvalidConfigAddress,
+    #[error("Invalid authority")]
+    InvalidAuthority,
+    #[error("LSU is already supported")]
+    LSUAlreadySupported,
+    #[error("Supported LSU list is full")]
+    SupportedLSUListFull,
+    #[error("Invalid treasury address")]
+    InvalidTreasury,
+    #[error("Fee exceeds the maximum")]
+    InvalidFee,
+    #[error("Paused")]
+    Paused,
+    #[error("Invalid LSU symbol")]
+    InvalidLSUSymbol,
+    #[error("Invalid pool address")]
+    InvalidPoolAddress,
+    #[error("Invalid LP token address")]
+    InvalidLPToken,
+    #[error("Invalid pool parameters")]
+    InvalidPoolParameters,
+    #[error("Insufficient liquidity")]
+    InsufficientLiquidity,
+    #[error("Slippage exceeded")]
+    SlippageExceeded,
+    #[error("Insufficient oracle history")]
+    InsufficientOracleHistory,
+    #[error("Tokenizer not empty")]
+    TokenizerNotEmpty,
+    #[error("Outdated account version")]
+    OutdatedAccountVersion,
+    #[error("Missing signer")]
+    MissingSigner,
+    #[error("Account not writable")]
+    AccountNotWritable,
+    #[error("Invalid account owner")]
+    InvalidAccountOwner,
+    #[error("Invalid program")]
+    InvalidProgram,
+    #[error("Already initialized")]
+    AlreadyInitialized,
 }
 
 impl From<YieldTokenizerError> for ProgramError {
@@ -62,6 +112,61 @@ impl PrintProgramError for YieldTokenizerError {
                 msg!("The provided LSU vault address is incorrect")
             }
             YieldTokenizerError::Immature => msg!("Has not reached maturity"),
+            YieldTokenizerError::InvalidYieldPosition => msg!("Invalid yield position address"),
+            YieldTokenizerError::InvalidExchangeRateAccount => {
+                msg!("The provided exchange rate account does not belong to the LSU")
+            }
+            YieldTokenizerError::StaleExchangeRate => {
+                msg!("The LSU exchange rate has not been updated this epoch")
+            }
+            YieldTokenizerError::InvalidMaturityDate => {
+                msg!("Maturity must be 00:00 UTC on a month-end within the allowed horizon")
+            }
+            YieldTokenizerError::InvalidConfigAddress => msg!("Invalid protocol config address"),
+            YieldTokenizerError::InvalidAuthority => msg!("Signer is not the protocol authority"),
+            YieldTokenizerError::LSUAlreadySupported => msg!("LSU is already supported"),
+            YieldTokenizerError::SupportedLSUListFull => {
+                msg!("The protocol config can't hold any more LSUs")
+            }
+            YieldTokenizerError::InvalidTreasury => msg!("Invalid treasury address"),
+            YieldTokenizerError::InvalidFee => msg!("Fee exceeds the maximum fee"),
+            YieldTokenizerError::Paused => {
+                msg!("The protocol or tokenizer is paused or in emergency mode")
+            }
+            YieldTokenizerError::InvalidLSUSymbol => {
+                msg!("LSU symbol must be between 1 and 10 bytes")
+            }
+            YieldTokenizerError::InvalidPoolAddress => msg!("Invalid PT pool address"),
+            YieldTokenizerError::InvalidLPToken => msg!("Invalid LP token address"),
+            YieldTokenizerError::InvalidPoolParameters => {
+                msg!("Pool scalar, anchor or fee is out of range")
+            }
+            YieldTokenizerError::InsufficientLiquidity => {
+                msg!("The pool can't absorb a trade of this size")
+            }
+            YieldTokenizerError::SlippageExceeded => {
+                msg!("Output is below the minimum amount out")
+            }
+            YieldTokenizerError::InsufficientOracleHistory => {
+                msg!("The pool's oracle doesn't go back far enough for this window")
+            }
+            YieldTokenizerError::TokenizerNotEmpty => {
+                msg!("PT or YT is still outstanding or the vault holds more than dust")
+            }
+            YieldTokenizerError::OutdatedAccountVersion => {
+                msg!("The account has an outdated layout and has to be migrated first")
+            }
+            YieldTokenizerError::MissingSigner => msg!("A required signature is missing"),
+            YieldTokenizerError::AccountNotWritable => {
+                msg!("An account the instruction writes to is not writable")
+            }
+            YieldTokenizerError::InvalidAccountOwner => {
+                msg!("An account is not owned by the expected program")
+            }
+            YieldTokenizerError::InvalidProgram => msg!("An invoked program has the wrong id"),
+            YieldTokenizerError::AlreadyInitialized => {
+                msg!("The tokenizer has already been initialized")
+            }
         }
     }
 }
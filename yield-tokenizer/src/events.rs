@@ -0,0 +1,500 @@
+use {
+    borsh::{BorshDeserialize, BorshSchema, BorshSerialize},
+    solana_program::{log::sol_log_data, program_error::ProgramError, pubkey::Pubkey},
+};
+
+/// Emitted by the program through `sol_log_data`, one per instruction, and read back from a
+/// transaction's log messages with `parse_events`. Exchange rates are SOL per LSU scaled by
+/// `state::EXCHANGE_RATE_PRECISION`, timestamps are the cluster's unix time.
+#[derive(Clone, Debug, PartialEq, Eq, BorshDeserialize, BorshSerialize, BorshSchema)]
+pub enum YieldTokenizerEvent {
+    InitializeYieldTokenizer {
+        yield_tokenizer: Pubkey,
+        initializer: Pubkey,
+        lsu_mint: Pubkey,
+        maturity_date: i64,
+        exchange_rate: u64,
+        timestamp: i64,
+    },
+    /// `lsu_amount` LSU deposited for `principal` PT and YT each
+    TokenizeYield {
+        yield_tokenizer: Pubkey,
+        user: Pubkey,
+        lsu_amount: u64,
+        principal: u64,
+        exchange_rate: u64,
+        timestamp: i64,
+    },
+    /// `principal` PT and YT burned for `lsu_amount` LSU, accrued yield included and `fee`
+    /// excluded
+    Redeem {
+        yield_tokenizer: Pubkey,
+        user: Pubkey,
+        principal: u64,
+        lsu_amount: u64,
+        fee: u64,
+        exchange_rate: u64,
+        timestamp: i64,
+    },
+    RedeemFromPt {
+        yield_tokenizer: Pubkey,
+        user: Pubkey,
+        principal: u64,
+        lsu_amount: u64,
+        fee: u64,
+        exchange_rate: u64,
+        timestamp: i64,
+    },
+    ClaimYield {
+        yield_tokenizer: Pubkey,
+        user: Pubkey,
+        lsu_amount: u64,
+        fee: u64,
+        exchange_rate: u64,
+        timestamp: i64,
+    },
+    TransferYt {
+        yield_tokenizer: Pubkey,
+        user: Pubkey,
+        recipient: Pubkey,
+        amount: u64,
+        exchange_rate: u64,
+        timestamp: i64,
+    },
+    AddLiquidity {
+        yield_tokenizer: Pubkey,
+        user: Pubkey,
+        pt_amount: u64,
+        lsu_amount: u64,
+        lp_amount: u64,
+        exchange_rate: u64,
+        timestamp: i64,
+    },
+    RemoveLiquidity {
+        yield_tokenizer: Pubkey,
+        user: Pubkey,
+        lp_amount: u64,
+        pt_amount: u64,
+        lsu_amount: u64,
+        exchange_rate: u64,
+        timestamp: i64,
+    },
+    /// A trade against the PT pool, `ln_implied_rate` is the pool's rate after it
+    SwapExactLsuForPt {
+        yield_tokenizer: Pubkey,
+        user: Pubkey,
+        lsu_in: u64,
+        pt_out: u64,
+        ln_implied_rate: i128,
+        exchange_rate: u64,
+        timestamp: i64,
+    },
+    SwapExactPtForLsu {
+        yield_tokenizer: Pubkey,
+        user: Pubkey,
+        pt_in: u64,
+        lsu_out: u64,
+        ln_implied_rate: i128,
+        exchange_rate: u64,
+        timestamp: i64,
+    },
+    SwapExactLsuForYt {
+        yield_tokenizer: Pubkey,
+        user: Pubkey,
+        lsu_in: u64,
+        yt_out: u64,
+        ln_implied_rate: i128,
+        exchange_rate: u64,
+        timestamp: i64,
+    },
+    SwapExactYtForLsu {
+        yield_tokenizer: Pubkey,
+        user: Pubkey,
+        yt_in: u64,
+        lsu_out: u64,
+        ln_implied_rate: i128,
+        exchange_rate: u64,
+        timestamp: i64,
+    },
+    /// `principal_in` matured PT redeemed, with its YT's final yield, into `lsu_amount` LSU that
+    /// was tokenized into `principal_out` PT and YT of `yield_tokenizer`
+    Rollover {
+        matured_yield_tokenizer: Pubkey,
+        yield_tokenizer: Pubkey,
+        user: Pubkey,
+        principal_in: u64,
+        lsu_amount: u64,
+        principal_out: u64,
+        fee: u64,
+        exchange_rate: u64,
+        timestamp: i64,
+    },
+    /// `swept_fees` is the vault dust and uncollected fees sent to the destination
+    CloseYieldTokenizer {
+        yield_tokenizer: Pubkey,
+        initializer: Pubkey,
+        swept_fees: u64,
+        timestamp: i64,
+    },
+}
+
+impl YieldTokenizerEvent {
+    /// Logs the event as `Program data: <base64>`
+    pub fn emit(&self) -> Result<(), ProgramError> {
+        sol_log_data(&[&borsh::to_vec(self)?]);
+        Ok(())
+    }
+}
+
+const PROGRAM_DATA_PREFIX: &str = "Program data: ";
+
+/// Decodes the events the tokenizer at `program_id` emitted in a transaction's log messages, in
+/// order. Data logged by any other program, including the token programs it invokes, is skipped.
+pub fn parse_events(
+    program_id: &Pubkey,
+    log_messages: &[String],
+) -> Result<Vec<YieldTokenizerEvent>, ProgramError> {
+    let program_id = program_id.to_string();
+    let mut invocations: Vec<&str> = Vec::new();
+    let mut events = Vec::new();
+
+    for message in log_messages {
+        if let Some(data) = message.strip_prefix(PROGRAM_DATA_PREFIX) {
+            if invocations.last() == Some(&program_id.as_str()) {
+                let bytes = decode_base64(data).ok_or(ProgramError::InvalidArgument)?;
+                events.push(YieldTokenizerEvent::try_from_slice(&bytes)?);
+            }
+            continue;
+        }
+
+        // `Program <id> invoke [<depth>]` opens an invocation, `success` or `failed: <error>`
+        // closes the innermost one
+        let mut words = message.split(' ');
+        if let (Some("Program"), Some(id), Some(status)) =
+            (words.next(), words.next(), words.next())
+        {
+            match status {
+                "invoke" => invocations.push(id),
+                "success" | "failed:" => {
+                    invocations.pop();
+                }
+                _ => {}
+            }
+        }
+    }
+    Ok(events)
+}
+
+/// Standard padded base64, the encoding the runtime logs `sol_log_data` with
+fn decode_base64(encoded: &str) -> Option<Vec<u8>> {
+    let encoded = encoded.trim_end_matches('=').as_bytes();
+    let mut bytes = Vec::with_capacity(encoded.len() * 3 / 4);
+    let mut buffer = 0u32;
+    let mut bits = 0;
+    for &c in encoded {
+        let value = match c {
+            b'A'..=b'Z' => c - b'A',
+            b'a'..=b'z' => c - b'a' + 26,
+            b'0'..=b'9' => c - b'0' + 52,
+            b'+' => 62,
+            b'/' => 63,
+            _ => return None,
+        };
+        buffer = buffer << 6 | value as u32;
+        bits += 6;
+        if bits >= 8 {
+            bits -= 8;
+            bytes.push((buffer >> bits) as u8);
+        }
+    }
+    Some(bytes)
+}
+
+#[cfg(test)]
+mod tests {
+    use {
+        super::*,
+        base64::{engine::general_purpose::STANDARD, Engine},
+    };
+
+    /// One of each event
+    fn events() -> Vec<YieldTokenizerEvent> {
+        let yield_tokenizer = Pubkey::new_unique();
+        let user = Pubkey::new_unique();
+        vec![
+            YieldTokenizerEvent::InitializeYieldTokenizer {
+                yield_tokenizer,
+                initializer: user,
+                lsu_mint: Pubkey::new_unique(),
+                maturity_date: 1_806_451_200,
+                exchange_rate: 1_250_000_000_000,
+                timestamp: 1_792_281_600,
+            },
+            YieldTokenizerEvent::TokenizeYield {
+                yield_tokenizer,
+                user,
+                lsu_amount: 1_000,
+                principal: 1_250,
+                exchange_rate: 1_250_000_000_000,
+                timestamp: 1_792_281_601,
+            },
+            YieldTokenizerEvent::Redeem {
+                yield_tokenizer,
+                user,
+                principal: 1_250,
+                lsu_amount: 1_001,
+                fee: 1,
+                exchange_rate: 1_260_000_000_000,
+                timestamp: 1_792_281_602,
+            },
+            YieldTokenizerEvent::RedeemFromPt {
+                yield_tokenizer,
+                user,
+                principal: 1_250,
+                lsu_amount: 990,
+                fee: 2,
+                exchange_rate: 1_260_000_000_000,
+                timestamp: 1_806_451_200,
+            },
+            YieldTokenizerEvent::ClaimYield {
+                yield_tokenizer,
+                user,
+                lsu_amount: 10,
+                fee: 1,
+                exchange_rate: 1_260_000_000_000,
+                timestamp: 1_792_281_603,
+            },
+            YieldTokenizerEvent::TransferYt {
+                yield_tokenizer,
+                user,
+                recipient: Pubkey::new_unique(),
+                amount: u64::MAX,
+                exchange_rate: 1_260_000_000_000,
+                timestamp: 1_792_281_604,
+            },
+            YieldTokenizerEvent::AddLiquidity {
+                yield_tokenizer,
+                user,
+                pt_amount: 5_000,
+                lsu_amount: 4_000,
+                lp_amount: 4_472,
+                exchange_rate: 1_260_000_000_000,
+                timestamp: 1_792_281_605,
+            },
+            YieldTokenizerEvent::RemoveLiquidity {
+                yield_tokenizer,
+                user,
+                lp_amount: 4_472,
+                pt_amount: 5_000,
+                lsu_amount: 3_999,
+                exchange_rate: 1_260_000_000_000,
+                timestamp: 1_792_281_606,
+            },
+            YieldTokenizerEvent::SwapExactLsuForPt {
+                yield_tokenizer,
+                user,
+                lsu_in: 100,
+                pt_out: 130,
+                ln_implied_rate: 48_790_164_169_432_003,
+                exchange_rate: 1_260_000_000_000,
+                timestamp: 1_792_281_607,
+            },
+            YieldTokenizerEvent::SwapExactPtForLsu {
+                yield_tokenizer,
+                user,
+                pt_in: 130,
+                lsu_out: 99,
+                ln_implied_rate: -1,
+                exchange_rate: 1_260_000_000_000,
+                timestamp: 1_792_281_608,
+            },
+            YieldTokenizerEvent::SwapExactLsuForYt {
+                yield_tokenizer,
+                user,
+                lsu_in: 100,
+                yt_out: 2_500,
+                ln_implied_rate: i128::MAX,
+                exchange_rate: 1_260_000_000_000,
+                timestamp: 1_792_281_609,
+            },
+            YieldTokenizerEvent::SwapExactYtForLsu {
+                yield_tokenizer,
+                user,
+                yt_in: 2_500,
+                lsu_out: 90,
+                ln_implied_rate: i128::MIN,
+                exchange_rate: 1_260_000_000_000,
+                timestamp: 1_792_281_610,
+            },
+            YieldTokenizerEvent::Rollover {
+                matured_yield_tokenizer: Pubkey::new_unique(),
+                yield_tokenizer,
+                user,
+                principal_in: 1_250,
+                lsu_amount: 990,
+                principal_out: 1_240,
+                fee: 2,
+                exchange_rate: 1_270_000_000_000,
+                timestamp: 1_806_451_300,
+            },
+            YieldTokenizerEvent::CloseYieldTokenizer {
+                yield_tokenizer,
+                initializer: user,
+                swept_fees: 12,
+                timestamp: 1_806_451_400,
+            },
+        ]
+    }
+
+    /// The line `sol_log_data` writes for `event`
+    fn data_line(event: &YieldTokenizerEvent) -> String {
+        format!(
+            "{}{}",
+            PROGRAM_DATA_PREFIX,
+            STANDARD.encode(borsh::to_vec(event).unwrap())
+        )
+    }
+
+    fn invoke(program_id: &Pubkey, depth: usize) -> String {
+        format!("Program {} invoke [{}]", program_id, depth)
+    }
+
+    fn success(program_id: &Pubkey) -> String {
+        format!("Program {} success", program_id)
+    }
+
+    fn consumed(program_id: &Pubkey) -> String {
+        format!(
+            "Program {} consumed 41231 of 200000 compute units",
+            program_id
+        )
+    }
+
+    #[test]
+    fn test_round_trip_every_event() {
+        let events = events();
+        let mut logs = vec![invoke(&crate::id(), 1)];
+        logs.extend(events.iter().map(data_line));
+        logs.extend([consumed(&crate::id()), success(&crate::id())]);
+
+        assert_eq!(parse_events(&crate::id(), &logs).unwrap(), events);
+    }
+
+    #[test]
+    fn test_skips_data_from_other_programs() {
+        let events = events();
+        let token_program = spl_token_2022::id();
+        let caller = Pubkey::new_unique();
+        let other = Pubkey::new_unique();
+
+        let logs = vec![
+            // An unrelated instruction logging data before ours
+            invoke(&other, 1),
+            data_line(&events[0]),
+            "Program data: not base64 at all!".to_string(),
+            success(&other),
+            // A vault program calling the tokenizer, which calls the token program
+            invoke(&caller, 1),
+            "Program log: Instruction: Deposit".to_string(),
+            format!("{}AQID", PROGRAM_DATA_PREFIX),
+            invoke(&crate::id(), 2),
+            "Program log: Instruction: TokenizeYield".to_string(),
+            invoke(&token_program, 3),
+            "Program log: Instruction: MintTo".to_string(),
+            data_line(&events[5]),
+            consumed(&token_program),
+            success(&token_program),
+            data_line(&events[1]),
+            consumed(&crate::id()),
+            success(&crate::id()),
+            data_line(&events[2]),
+            format!("Program return: {} AQID", caller),
+            success(&caller),
+            // Invoked directly afterwards
+            invoke(&crate::id(), 1),
+            data_line(&events[3]),
+            success(&crate::id()),
+        ];
+
+        assert_eq!(
+            parse_events(&crate::id(), &logs).unwrap(),
+            vec![events[1].clone(), events[3].clone()]
+        );
+    }
+
+    #[test]
+    fn test_failed_invocations_close() {
+        let events = events();
+        let token_program = spl_token::id();
+
+        let logs = vec![
+            invoke(&crate::id(), 1),
+            invoke(&token_program, 2),
+            "Program log: Error: insufficient funds".to_string(),
+            format!(
+                "Program {} failed: custom program error: 0x1",
+                token_program
+            ),
+            data_line(&events[0]),
+            success(&crate::id()),
+            invoke(&token_program, 1),
+            data_line(&events[1]),
+            success(&token_program),
+        ];
+
+        assert_eq!(
+            parse_events(&crate::id(), &logs).unwrap(),
+            vec![events[0].clone()]
+        );
+    }
+
+    #[test]
+    fn test_rejects_invalid_data_from_the_program() {
+        let mut line = data_line(&events()[0]);
+        line.push('*');
+        let logs = vec![invoke(&crate::id(), 1), line, success(&crate::id())];
+        assert_eq!(
+            parse_events(&crate::id(), &logs),
+            Err(ProgramError::InvalidArgument)
+        );
+
+        // Valid base64 that isn't an event
+        let logs = vec![
+            invoke(&crate::id(), 1),
+            format!("{}{}", PROGRAM_DATA_PREFIX, STANDARD.encode([200u8, 1, 2])),
+            success(&crate::id()),
+        ];
+        assert!(parse_events(&crate::id(), &logs).is_err());
+
+        // Trailing bytes after an event
+        let mut data = borsh::to_vec(&events()[0]).unwrap();
+        data.push(0);
+        let logs = vec![
+            invoke(&crate::id(), 1),
+            format!("{}{}", PROGRAM_DATA_PREFIX, STANDARD.encode(data)),
+            success(&crate::id()),
+        ];
+        assert!(parse_events(&crate::id(), &logs).is_err());
+    }
+
+    #[test]
+    fn test_decode_base64() {
+        // Every padding length, against the encoding the runtime uses
+        for len in 0..=64 {
+            let bytes: Vec<u8> = (0..len).map(|i| (i * 37 + 11) as u8).collect();
+            let encoded = STANDARD.encode(&bytes);
+            assert_eq!(decode_base64(&encoded), Some(bytes.clone()), "{}", encoded);
+            assert_eq!(decode_base64(encoded.trim_end_matches('=')), Some(bytes));
+        }
+
+        assert_eq!(decode_base64(""), Some(vec![]));
+        assert_eq!(decode_base64("TQ=="), Some(b"M".to_vec()));
+        assert_eq!(decode_base64("TWE="), Some(b"Ma".to_vec()));
+        assert_eq!(decode_base64("TWFu"), Some(b"Man".to_vec()));
+        assert_eq!(decode_base64("+/+/"), Some(vec![0xfb, 0xff, 0xbf]));
+
+        for invalid in ["TQ=a", "TW=u", "TWFu TWFu", "TWF-", "TWF_", "TWFu\n", "*"] {
+            assert_eq!(decode_base64(invalid), None, "{}", invalid);
+        }
+    }
+}
@@ -1,234 +1,1288 @@
 use {
+    crate::{adapter::LsuAdapter, state::PauseState},
     borsh::{BorshDeserialize, BorshSchema, BorshSerialize},
     solana_program::{
+        bpf_loader_upgradeable,
         instruction::{AccountMeta, Instruction},
-        msg,
         program_error::ProgramError,
         pubkey::Pubkey,
         system_program,
     },
-    spl_associated_token_account::get_associated_token_address,
 };
 
-#[derive(Clone, Debug, PartialEq, Eq, BorshDeserialize, BorshSerialize, BorshSchema)]
-pub enum Expiry {
-    TwelveMonths,
-    EighteenMonths,
-    TwentyFourMonths,
-}
-
 #[derive(Clone, Debug, PartialEq, Eq, BorshDeserialize, BorshSerialize, BorshSchema)]
 pub enum YieldTokenizerInstruction {
     /// Authority
+    /// Config Account
     /// Yield Tokenizer Account
     /// LSU Token Mint
+    /// LSU Exchange Rate Account
     /// Principal Token Mint
     /// Yield Mint
     /// LSU  Vault
+    /// Treasury
     /// Token Program
+    /// PT/YT Token Program
     /// Assoc Token Program
     /// System Program
-    InitializeYieldTokenizer { expiry: Expiry },
+    ///
+    /// `maturity_date` must be 00:00 UTC on a month-end, see `maturity::is_valid_maturity_date`.
+    /// The LSU must be on the config allowlist. Passing Token-2022 as the PT/YT token program
+    /// creates the mints with on-mint metadata naming the series.
+    InitializeYieldTokenizer { maturity_date: i64 },
 
     /// Buyer
+    /// Config Account
     /// Yield Tokenizer Account
     /// LSU Token Mint
+    /// LSU Exchange Rate Account
     /// PT Mint
     /// YT Mint
     /// LSU Token Vault
     /// Buyer LSU ATA
     /// Buyer PT ATA
     /// Buyer YT ATA
+    /// Buyer Yield Position
     /// Token Program
+    /// PT/YT Token Program
     /// Assoc Token Program
+    /// System Program
     TokenizeYield { amount: u64 },
 
     /// Redeemer
+    /// Config Account
     /// Yield Tokenizer Account
     /// LSU Token Mint
+    /// LSU Exchange Rate Account
     /// PT Mint
     /// YT Mint
     /// LSU Token Vault
-    /// Buyer LSU ATA
-    /// Buyer PT ATA
-    /// Buyer YT ATA
+    /// Treasury
+    /// Redeemer LSU ATA
+    /// Redeemer PT ATA
+    /// Redeemer YT ATA
+    /// Redeemer Yield Position
     /// Token Program
+    /// PT/YT Token Program
     /// Assoc Token Program
+    /// System Program
     Redeem { amount: u64 },
 
     /// Redeemer
+    /// Config Account
     /// YieldTokenizerAccount
     /// LSU Token Mint
+    /// LSU Exchange Rate Account
     /// PT Mint
-    /// YT Mint
     /// LSU Token Vault
+    /// Treasury
     /// Redeemer LSU ATA
     /// Redeemer PT ATA
-    /// Redeemer YT ATA
     /// Token Program
+    /// PT/YT Token Program
     /// Assoc Token Program
+    /// System Program
     RedeemFromPt { amount: u64 },
 
     /// Claimer
+    /// Config Account
     /// Yield Tokenizer Account
     /// LSU Token Mint
+    /// LSU Exchange Rate Account
     /// YT Mint
+    /// LSU Token Vault
+    /// Treasury
     /// Claimer LSU ATA
     /// Claimer YT ATA
+    /// Claimer Yield Position
     /// Token Program
     /// Assoc Token Program
+    /// System Program
     ClaimYield,
+
+    /// YT accounts are frozen by the tokenizer, so YT can only be moved through this instruction,
+    /// which settles the yield of both parties before transferring
+    ///
+    /// Sender
+    /// Config Account
+    /// Yield Tokenizer Account
+    /// LSU Token Mint
+    /// LSU Exchange Rate Account
+    /// YT Mint
+    /// Sender YT ATA
+    /// Sender Yield Position
+    /// Recipient
+    /// Recipient YT ATA
+    /// Recipient Yield Position
+    /// YT Token Program
+    /// Assoc Token Program
+    /// System Program
+    TransferYt { amount: u64 },
+
+    /// Creates the protocol config, can only be called by the program's upgrade authority
+    ///
+    /// Authority
+    /// Config Account
+    /// Program Data Account
+    /// System Program
+    InitializeConfig,
+
+    /// Adds an LSU to the allowlist, reading its exchange rate once to validate the account
+    ///
+    /// Authority
+    /// Config Account
+    /// LSU Token Mint
+    /// LSU Exchange Rate Account
+    AddLsu { adapter: LsuAdapter, symbol: String },
+
+    /// Removes an LSU from the allowlist, existing tokenizers are unaffected
+    ///
+    /// Authority
+    /// Config Account
+    /// LSU Token Mint
+    RemoveLsu,
+
+    /// Authority
+    /// Config Account
+    SetAuthority { new_authority: Pubkey },
+
+    /// Sets the protocol fees, each capped at `state::MAX_FEE_BPS`
+    ///
+    /// Authority
+    /// Config Account
+    SetFees {
+        yield_fee_bps: u16,
+        redemption_fee_bps: u16,
+    },
+
+    /// Moves collected fees out of a tokenizer's treasury
+    ///
+    /// Authority
+    /// Config Account
+    /// Yield Tokenizer Account
+    /// Treasury
+    /// Destination LSU Token Account
+    /// Token Program
+    WithdrawFees { amount: u64 },
+
+    /// Sets the protocol-wide pause state, which applies on top of each tokenizer's own
+    ///
+    /// Authority
+    /// Config Account
+    SetPause { pause_state: PauseState },
+
+    /// Authority
+    /// Config Account
+    /// Yield Tokenizer Account
+    SetTokenizerPause { pause_state: PauseState },
+
+    /// Creates the PT/LSU pool for a tokenizer, see `amm::Curve` for the parameters
+    ///
+    /// Authority
+    /// Config Account
+    /// Yield Tokenizer Account
+    /// LSU Token Mint
+    /// PT Mint
+    /// Pool Account
+    /// LP Mint
+    /// Pool LSU Vault
+    /// Pool PT Vault
+    /// Token Program
+    /// PT/YT Token Program
+    /// Assoc Token Program
+    /// System Program
+    InitializePool {
+        scalar_root: i128,
+        initial_anchor: i128,
+        ln_fee_rate_root: i128,
+    },
+
+    /// Deposits PT and LSU at the pool's current ratio, `pt_amount` and `lsu_amount` are the most
+    /// that will be taken. The first deposit sets the ratio and so the initial implied rate.
+    ///
+    /// Provider
+    /// Config Account
+    /// Yield Tokenizer Account
+    /// LSU Token Mint
+    /// LSU Exchange Rate Account
+    /// PT Mint
+    /// Pool Account
+    /// LP Mint
+    /// Pool LSU Vault
+    /// Pool PT Vault
+    /// Provider LSU ATA
+    /// Provider PT ATA
+    /// Provider LP ATA
+    /// Token Program
+    /// PT/YT Token Program
+    /// Assoc Token Program
+    /// System Program
+    AddLiquidity {
+        pt_amount: u64,
+        lsu_amount: u64,
+        min_lp_out: u64,
+    },
+
+    /// Burns LP for its share of the reserves, also allowed after maturity and in emergency mode
+    ///
+    /// Provider
+    /// Config Account
+    /// Yield Tokenizer Account
+    /// LSU Token Mint
+    /// PT Mint
+    /// Pool Account
+    /// LP Mint
+    /// Pool LSU Vault
+    /// Pool PT Vault
+    /// Provider LSU ATA
+    /// Provider PT ATA
+    /// Provider LP ATA
+    /// Token Program
+    /// PT/YT Token Program
+    RemoveLiquidity {
+        lp_amount: u64,
+        min_pt_out: u64,
+        min_lsu_out: u64,
+    },
+
+    /// The PT out is found by bisection over the curve, transactions should request a higher
+    /// compute unit limit
+    ///
+    /// Trader
+    /// Config Account
+    /// Yield Tokenizer Account
+    /// LSU Token Mint
+    /// LSU Exchange Rate Account
+    /// PT Mint
+    /// Pool Account
+    /// Pool LSU Vault
+    /// Pool PT Vault
+    /// Trader LSU ATA
+    /// Trader PT ATA
+    /// Token Program
+    /// PT/YT Token Program
+    /// Assoc Token Program
+    /// System Program
+    SwapExactLsuForPt { lsu_in: u64, min_pt_out: u64 },
+
+    /// Same accounts as `SwapExactLsuForPt`
+    SwapExactPtForLsu { pt_in: u64, min_lsu_out: u64 },
+
+    /// Buys YT by tokenizing `lsu_in` together with the LSU the pool pays for the PT leg, which
+    /// is sold to it in the same step. Like `SwapExactLsuForPt` the amount is found by bisection.
+    ///
+    /// Trader
+    /// Config Account
+    /// Yield Tokenizer Account
+    /// LSU Token Mint
+    /// LSU Exchange Rate Account
+    /// PT Mint
+    /// YT Mint
+    /// LSU Token Vault
+    /// Pool Account
+    /// Pool LSU Vault
+    /// Pool PT Vault
+    /// Trader LSU ATA
+    /// Trader YT ATA
+    /// Trader Yield Position
+    /// Token Program
+    /// PT/YT Token Program
+    /// Assoc Token Program
+    /// System Program
+    SwapExactLsuForYt { lsu_in: u64, min_yt_out: u64 },
+
+    /// Sells YT by buying the PT leg back from the pool and redeeming the pair, the pool is paid
+    /// out of the redeemed LSU. Yield accrued by the trader is paid out alongside.
+    ///
+    /// Trader
+    /// Config Account
+    /// Yield Tokenizer Account
+    /// LSU Token Mint
+    /// LSU Exchange Rate Account
+    /// PT Mint
+    /// YT Mint
+    /// LSU Token Vault
+    /// Treasury
+    /// Pool Account
+    /// Pool LSU Vault
+    /// Pool PT Vault
+    /// Trader LSU ATA
+    /// Trader YT ATA
+    /// Trader Yield Position
+    /// Token Program
+    /// PT/YT Token Program
+    /// Assoc Token Program
+    /// System Program
+    SwapExactYtForLsu { yt_in: u64, min_lsu_out: u64 },
+
+    /// Redeems `amount` PT of a matured series, together with the final yield on the owner's YT,
+    /// and tokenizes the LSU into a live series of the same LSU. The LSU moves between the
+    /// vaults directly and the usual redemption and yield fees go to the matured series' treasury.
+    /// The owner's matured YT has nothing left to earn and is burned.
+    ///
+    /// Owner
+    /// Config Account
+    /// LSU Token Mint
+    /// Matured Yield Tokenizer Account
+    /// Matured LSU Exchange Rate Account
+    /// Matured PT Mint
+    /// Matured YT Mint
+    /// Matured LSU Token Vault
+    /// Matured Treasury
+    /// Owner Matured PT ATA
+    /// Owner Matured YT ATA
+    /// Owner Matured Yield Position
+    /// Yield Tokenizer Account
+    /// LSU Exchange Rate Account
+    /// PT Mint
+    /// YT Mint
+    /// LSU Token Vault
+    /// Owner PT ATA
+    /// Owner YT ATA
+    /// Owner Yield Position
+    /// Token Program
+    /// Matured PT/YT Token Program
+    /// PT/YT Token Program
+    /// Assoc Token Program
+    /// System Program
+    Rollover { amount: u64 },
+
+    /// Closes a matured tokenizer once all of its PT has been burned and its YT has claimed its
+    /// final yield, returning the rent of the tokenizer, its vault and treasury to the account
+    /// that initialized it. Vault dust is swept through the treasury into the destination along
+    /// with any uncollected fees.
+    ///
+    /// Authority
+    /// Config Account
+    /// Yield Tokenizer Account
+    /// Initializer
+    /// PT Mint
+    /// YT Mint
+    /// LSU Token Vault
+    /// Treasury
+    /// Destination LSU Token Account
+    /// Token Program
+    /// PT/YT Token Program
+    CloseYieldTokenizer,
+
+    /// Upgrades a tokenizer, pool, position or the config written with an older layout, see the
+    /// `state::*_VERSION` constants, to the current one, reallocating it in place. Anyone can
+    /// migrate an account, the payer funds the extra rent. Current accounts are left untouched.
+    ///
+    /// A version 0 tokenizer predates its treasury and exchange rate, so it also takes the
+    /// accounts after the system program. The treasury is created and the series restarts its
+    /// accounting at the current rate, with the payer as its initializer.
+    ///
+    /// Payer
+    /// Account to migrate
+    /// System Program
+    /// Config Account, version 0 tokenizers only
+    /// LSU Mint, version 0 tokenizers only
+    /// LSU Exchange Rate Account, version 0 tokenizers only
+    /// Treasury, version 0 tokenizers only
+    /// Token Program, version 0 tokenizers only
+    MigrateState,
 }
 
 /// Initialize a Yield Tokenizer for a specific maturity and liquid staking unit
+#[allow(clippy::too_many_arguments)]
 pub fn init_yield_tokenizer(
     program_id: &Pubkey,
     authority: &Pubkey,
+    config: &Pubkey,
     yield_tokenizer: &Pubkey,
     lsu_mint: &Pubkey,
+    lsu_exchange_rate_account: &Pubkey,
     pt_mint: &Pubkey,
     yt_mint: &Pubkey,
     lsu_vault: &Pubkey,
-    expiry: Expiry,
+    treasury: &Pubkey,
+    yield_token_program: &Pubkey,
+    maturity_date: i64,
 ) -> Result<Instruction, ProgramError> {
     Ok(Instruction::new_with_borsh(
-        crate::id(),
-        &YieldTokenizerInstruction::InitializeYieldTokenizer { expiry },
+        *program_id,
+        &YieldTokenizerInstruction::InitializeYieldTokenizer { maturity_date },
         vec![
             AccountMeta::new(*authority, true),
+            AccountMeta::new_readonly(*config, false),
             AccountMeta::new(*yield_tokenizer, false),
-            AccountMeta::new(*lsu_mint, false),
+            AccountMeta::new_readonly(*lsu_mint, false),
+            AccountMeta::new_readonly(*lsu_exchange_rate_account, false),
             AccountMeta::new(*pt_mint, false),
             AccountMeta::new(*yt_mint, false),
             AccountMeta::new(*lsu_vault, false),
-            AccountMeta::new(spl_token::id(), false),
-            AccountMeta::new(spl_associated_token_account::id(), false),
-            AccountMeta::new(system_program::id(), false),
+            AccountMeta::new(*treasury, false),
+            AccountMeta::new_readonly(spl_token::id(), false),
+            AccountMeta::new_readonly(*yield_token_program, false),
+            AccountMeta::new_readonly(spl_associated_token_account::id(), false),
+            AccountMeta::new_readonly(system_program::id(), false),
         ],
     ))
 }
 
 /// Tokenize a liquid staking unit into a principal token and a yield token
+#[allow(clippy::too_many_arguments)]
 pub fn tokenize_yield(
+    program_id: &Pubkey,
     buyer: &Pubkey,
+    config: &Pubkey,
     yield_tokenizer: &Pubkey,
     lsu_mint: &Pubkey,
+    lsu_exchange_rate_account: &Pubkey,
     pt_mint: &Pubkey,
     yt_mint: &Pubkey,
     lsu_vault: &Pubkey,
     buyer_lsu_ata: &Pubkey,
     buyer_pt_ata: &Pubkey,
     buyer_yt_ata: &Pubkey,
+    buyer_position: &Pubkey,
+    yield_token_program: &Pubkey,
     amount: u64,
 ) -> Result<Instruction, ProgramError> {
     Ok(Instruction::new_with_borsh(
-        crate::id(),
+        *program_id,
         &YieldTokenizerInstruction::TokenizeYield { amount },
         vec![
             AccountMeta::new(*buyer, true),
+            AccountMeta::new_readonly(*config, false),
             AccountMeta::new(*yield_tokenizer, false),
-            AccountMeta::new(*lsu_mint, false),
+            AccountMeta::new_readonly(*lsu_mint, false),
+            AccountMeta::new_readonly(*lsu_exchange_rate_account, false),
             AccountMeta::new(*pt_mint, false),
             AccountMeta::new(*yt_mint, false),
             AccountMeta::new(*lsu_vault, false),
             AccountMeta::new(*buyer_lsu_ata, false),
             AccountMeta::new(*buyer_pt_ata, false),
             AccountMeta::new(*buyer_yt_ata, false),
-            AccountMeta::new(spl_token::id(), false),
-            AccountMeta::new(spl_associated_token_account::id(), false),
+            AccountMeta::new(*buyer_position, false),
+            AccountMeta::new_readonly(spl_token::id(), false),
+            AccountMeta::new_readonly(*yield_token_program, false),
+            AccountMeta::new_readonly(spl_associated_token_account::id(), false),
+            AccountMeta::new_readonly(system_program::id(), false),
         ],
     ))
 }
 
 /// Redeem a liquid staking unit from a principal token + yield token, the PT and YT must be in
-/// 1:1 ratio
+/// 1:1 ratio. Yield accrued by the redeemer is paid out alongside.
+#[allow(clippy::too_many_arguments)]
 pub fn redeem(
+    program_id: &Pubkey,
     redeemer: &Pubkey,
+    config: &Pubkey,
     yield_tokenizer: &Pubkey,
     lsu_mint: &Pubkey,
+    lsu_exchange_rate_account: &Pubkey,
     pt_mint: &Pubkey,
     yt_mint: &Pubkey,
     lsu_vault: &Pubkey,
-    pt_vault: &Pubkey,
-    yt_vault: &Pubkey,
+    treasury: &Pubkey,
     redeemer_lsu_ata: &Pubkey,
     redeemer_pt_ata: &Pubkey,
     redeemer_yt_ata: &Pubkey,
+    redeemer_position: &Pubkey,
+    yield_token_program: &Pubkey,
     amount: u64,
 ) -> Result<Instruction, ProgramError> {
     Ok(Instruction::new_with_borsh(
-        crate::id(),
+        *program_id,
         &YieldTokenizerInstruction::Redeem { amount },
         vec![
             AccountMeta::new(*redeemer, true),
+            AccountMeta::new_readonly(*config, false),
             AccountMeta::new(*yield_tokenizer, false),
-            AccountMeta::new(*lsu_mint, false),
+            AccountMeta::new_readonly(*lsu_mint, false),
+            AccountMeta::new_readonly(*lsu_exchange_rate_account, false),
             AccountMeta::new(*pt_mint, false),
             AccountMeta::new(*yt_mint, false),
             AccountMeta::new(*lsu_vault, false),
-            AccountMeta::new(*pt_vault, false),
-            AccountMeta::new(*yt_vault, false),
+            AccountMeta::new(*treasury, false),
             AccountMeta::new(*redeemer_lsu_ata, false),
             AccountMeta::new(*redeemer_pt_ata, false),
             AccountMeta::new(*redeemer_yt_ata, false),
-            AccountMeta::new(spl_token::id(), false),
-            AccountMeta::new(spl_associated_token_account::id(), false),
+            AccountMeta::new(*redeemer_position, false),
+            AccountMeta::new_readonly(spl_token::id(), false),
+            AccountMeta::new_readonly(*yield_token_program, false),
+            AccountMeta::new_readonly(spl_associated_token_account::id(), false),
+            AccountMeta::new_readonly(system_program::id(), false),
         ],
     ))
 }
 
-/// Redeem a liquid staking unit from a principal token only - can only be called after expiry
+/// Redeem a liquid staking unit from a principal token only - can only be called after expiry.
+/// PT pays out the LSU worth its SOL principal at the maturity exchange rate.
+#[allow(clippy::too_many_arguments)]
 pub fn redeem_from_pt(
+    program_id: &Pubkey,
     redeemer: &Pubkey,
+    config: &Pubkey,
     yield_tokenizer: &Pubkey,
     lsu_mint: &Pubkey,
+    lsu_exchange_rate_account: &Pubkey,
     pt_mint: &Pubkey,
     lsu_vault: &Pubkey,
-    pt_vault: &Pubkey,
+    treasury: &Pubkey,
     redeemer_lsu_ata: &Pubkey,
     redeemer_pt_ata: &Pubkey,
+    yield_token_program: &Pubkey,
     amount: u64,
 ) -> Result<Instruction, ProgramError> {
     Ok(Instruction::new_with_borsh(
-        crate::id(),
+        *program_id,
         &YieldTokenizerInstruction::RedeemFromPt { amount },
         vec![
             AccountMeta::new(*redeemer, true),
+            AccountMeta::new_readonly(*config, false),
             AccountMeta::new(*yield_tokenizer, false),
-            AccountMeta::new(*lsu_mint, false),
+            AccountMeta::new_readonly(*lsu_mint, false),
+            AccountMeta::new_readonly(*lsu_exchange_rate_account, false),
             AccountMeta::new(*pt_mint, false),
             AccountMeta::new(*lsu_vault, false),
+            AccountMeta::new(*treasury, false),
             AccountMeta::new(*redeemer_lsu_ata, false),
             AccountMeta::new(*redeemer_pt_ata, false),
-            AccountMeta::new(spl_token::id(), false),
-            AccountMeta::new(spl_associated_token_account::id(), false),
+            AccountMeta::new_readonly(spl_token::id(), false),
+            AccountMeta::new_readonly(*yield_token_program, false),
+            AccountMeta::new_readonly(spl_associated_token_account::id(), false),
+            AccountMeta::new_readonly(system_program::id(), false),
         ],
     ))
 }
 
-/// Collect yield owed from holding a yield token
+/// Collect yield owed from holding a yield token, the YT is kept by the claimer. The protocol's
+/// yield fee is sent to the tokenizer's treasury.
+#[allow(clippy::too_many_arguments)]
 pub fn claim_yield(
+    program_id: &Pubkey,
     claimer: &Pubkey,
+    config: &Pubkey,
     yield_tokenizer: &Pubkey,
     lsu_mint: &Pubkey,
+    lsu_exchange_rate_account: &Pubkey,
     yt_mint: &Pubkey,
     lsu_vault: &Pubkey,
-    yt_vault: &Pubkey,
-    redeemer_lsu_ata: &Pubkey,
-    redeemer_yt_ata: &Pubkey,
+    treasury: &Pubkey,
+    claimer_lsu_ata: &Pubkey,
+    claimer_yt_ata: &Pubkey,
+    claimer_position: &Pubkey,
 ) -> Result<Instruction, ProgramError> {
     Ok(Instruction::new_with_borsh(
-        crate::id(),
+        *program_id,
         &YieldTokenizerInstruction::ClaimYield,
         vec![
             AccountMeta::new(*claimer, true),
+            AccountMeta::new_readonly(*config, false),
+            AccountMeta::new(*yield_tokenizer, false),
+            AccountMeta::new_readonly(*lsu_mint, false),
+            AccountMeta::new_readonly(*lsu_exchange_rate_account, false),
+            AccountMeta::new_readonly(*yt_mint, false),
+            AccountMeta::new(*lsu_vault, false),
+            AccountMeta::new(*treasury, false),
+            AccountMeta::new(*claimer_lsu_ata, false),
+            AccountMeta::new_readonly(*claimer_yt_ata, false),
+            AccountMeta::new(*claimer_position, false),
+            AccountMeta::new_readonly(spl_token::id(), false),
+            AccountMeta::new_readonly(spl_associated_token_account::id(), false),
+            AccountMeta::new_readonly(system_program::id(), false),
+        ],
+    ))
+}
+
+/// Transfer yield tokens, settling the yield owed to the sender and recipient first
+#[allow(clippy::too_many_arguments)]
+pub fn transfer_yt(
+    program_id: &Pubkey,
+    sender: &Pubkey,
+    config: &Pubkey,
+    yield_tokenizer: &Pubkey,
+    lsu_mint: &Pubkey,
+    lsu_exchange_rate_account: &Pubkey,
+    yt_mint: &Pubkey,
+    sender_yt_ata: &Pubkey,
+    sender_position: &Pubkey,
+    recipient: &Pubkey,
+    recipient_yt_ata: &Pubkey,
+    recipient_position: &Pubkey,
+    yield_token_program: &Pubkey,
+    amount: u64,
+) -> Result<Instruction, ProgramError> {
+    Ok(Instruction::new_with_borsh(
+        *program_id,
+        &YieldTokenizerInstruction::TransferYt { amount },
+        vec![
+            AccountMeta::new(*sender, true),
+            AccountMeta::new_readonly(*config, false),
             AccountMeta::new(*yield_tokenizer, false),
-            AccountMeta::new(*lsu_mint, false),
+            AccountMeta::new_readonly(*lsu_mint, false),
+            AccountMeta::new_readonly(*lsu_exchange_rate_account, false),
+            AccountMeta::new_readonly(*yt_mint, false),
+            AccountMeta::new(*sender_yt_ata, false),
+            AccountMeta::new(*sender_position, false),
+            AccountMeta::new(*recipient, false),
+            AccountMeta::new(*recipient_yt_ata, false),
+            AccountMeta::new(*recipient_position, false),
+            AccountMeta::new_readonly(*yield_token_program, false),
+            AccountMeta::new_readonly(spl_associated_token_account::id(), false),
+            AccountMeta::new_readonly(system_program::id(), false),
+        ],
+    ))
+}
+
+/// Create the protocol config with the program's upgrade authority as admin
+pub fn init_config(
+    program_id: &Pubkey,
+    authority: &Pubkey,
+    config: &Pubkey,
+) -> Result<Instruction, ProgramError> {
+    let (program_data, _) =
+        Pubkey::find_program_address(&[program_id.as_ref()], &bpf_loader_upgradeable::id());
+
+    Ok(Instruction::new_with_borsh(
+        *program_id,
+        &YieldTokenizerInstruction::InitializeConfig,
+        vec![
+            AccountMeta::new(*authority, true),
+            AccountMeta::new(*config, false),
+            AccountMeta::new_readonly(program_data, false),
+            AccountMeta::new_readonly(system_program::id(), false),
+        ],
+    ))
+}
+
+/// Allow tokenizers to be initialized for `lsu_mint`, `symbol` names its PT/YT, e.g. `mSOL`
+pub fn add_lsu(
+    program_id: &Pubkey,
+    authority: &Pubkey,
+    config: &Pubkey,
+    lsu_mint: &Pubkey,
+    lsu_exchange_rate_account: &Pubkey,
+    adapter: LsuAdapter,
+    symbol: &str,
+) -> Result<Instruction, ProgramError> {
+    Ok(Instruction::new_with_borsh(
+        *program_id,
+        &YieldTokenizerInstruction::AddLsu {
+            adapter,
+            symbol: symbol.to_string(),
+        },
+        vec![
+            AccountMeta::new_readonly(*authority, true),
+            AccountMeta::new(*config, false),
+            AccountMeta::new_readonly(*lsu_mint, false),
+            AccountMeta::new_readonly(*lsu_exchange_rate_account, false),
+        ],
+    ))
+}
+
+/// Stop new tokenizers from being initialized for `lsu_mint`
+pub fn remove_lsu(
+    program_id: &Pubkey,
+    authority: &Pubkey,
+    config: &Pubkey,
+    lsu_mint: &Pubkey,
+) -> Result<Instruction, ProgramError> {
+    Ok(Instruction::new_with_borsh(
+        *program_id,
+        &YieldTokenizerInstruction::RemoveLsu,
+        vec![
+            AccountMeta::new_readonly(*authority, true),
+            AccountMeta::new(*config, false),
+            AccountMeta::new_readonly(*lsu_mint, false),
+        ],
+    ))
+}
+
+/// Hand the protocol admin role over to `new_authority`
+pub fn set_authority(
+    program_id: &Pubkey,
+    authority: &Pubkey,
+    config: &Pubkey,
+    new_authority: &Pubkey,
+) -> Result<Instruction, ProgramError> {
+    Ok(Instruction::new_with_borsh(
+        *program_id,
+        &YieldTokenizerInstruction::SetAuthority {
+            new_authority: *new_authority,
+        },
+        vec![
+            AccountMeta::new_readonly(*authority, true),
+            AccountMeta::new(*config, false),
+        ],
+    ))
+}
+
+/// Set the protocol fees in basis points, the yield fee applies to yield paid to YT holders and
+/// the redemption fee to LSU paid out for PT after maturity
+pub fn set_fees(
+    program_id: &Pubkey,
+    authority: &Pubkey,
+    config: &Pubkey,
+    yield_fee_bps: u16,
+    redemption_fee_bps: u16,
+) -> Result<Instruction, ProgramError> {
+    Ok(Instruction::new_with_borsh(
+        *program_id,
+        &YieldTokenizerInstruction::SetFees {
+            yield_fee_bps,
+            redemption_fee_bps,
+        },
+        vec![
+            AccountMeta::new_readonly(*authority, true),
+            AccountMeta::new(*config, false),
+        ],
+    ))
+}
+
+/// Withdraw fees collected by a tokenizer into `destination`, an LSU token account
+pub fn withdraw_fees(
+    program_id: &Pubkey,
+    authority: &Pubkey,
+    config: &Pubkey,
+    yield_tokenizer: &Pubkey,
+    treasury: &Pubkey,
+    destination: &Pubkey,
+    amount: u64,
+) -> Result<Instruction, ProgramError> {
+    Ok(Instruction::new_with_borsh(
+        *program_id,
+        &YieldTokenizerInstruction::WithdrawFees { amount },
+        vec![
+            AccountMeta::new_readonly(*authority, true),
+            AccountMeta::new_readonly(*config, false),
+            AccountMeta::new_readonly(*yield_tokenizer, false),
+            AccountMeta::new(*treasury, false),
+            AccountMeta::new(*destination, false),
+            AccountMeta::new_readonly(spl_token::id(), false),
+        ],
+    ))
+}
+
+/// Pause or put the whole protocol into emergency mode, see `state::PauseState`
+pub fn set_pause(
+    program_id: &Pubkey,
+    authority: &Pubkey,
+    config: &Pubkey,
+    pause_state: PauseState,
+) -> Result<Instruction, ProgramError> {
+    Ok(Instruction::new_with_borsh(
+        *program_id,
+        &YieldTokenizerInstruction::SetPause { pause_state },
+        vec![
+            AccountMeta::new_readonly(*authority, true),
+            AccountMeta::new(*config, false),
+        ],
+    ))
+}
+
+/// Pause or put a single tokenizer into emergency mode, see `state::PauseState`
+pub fn set_tokenizer_pause(
+    program_id: &Pubkey,
+    authority: &Pubkey,
+    config: &Pubkey,
+    yield_tokenizer: &Pubkey,
+    pause_state: PauseState,
+) -> Result<Instruction, ProgramError> {
+    Ok(Instruction::new_with_borsh(
+        *program_id,
+        &YieldTokenizerInstruction::SetTokenizerPause { pause_state },
+        vec![
+            AccountMeta::new_readonly(*authority, true),
+            AccountMeta::new_readonly(*config, false),
+            AccountMeta::new(*yield_tokenizer, false),
+        ],
+    ))
+}
+
+/// Create the PT/LSU pool for a tokenizer
+#[allow(clippy::too_many_arguments)]
+pub fn init_pool(
+    program_id: &Pubkey,
+    authority: &Pubkey,
+    config: &Pubkey,
+    yield_tokenizer: &Pubkey,
+    lsu_mint: &Pubkey,
+    pt_mint: &Pubkey,
+    pool: &Pubkey,
+    lp_mint: &Pubkey,
+    pool_lsu_vault: &Pubkey,
+    pool_pt_vault: &Pubkey,
+    yield_token_program: &Pubkey,
+    scalar_root: i128,
+    initial_anchor: i128,
+    ln_fee_rate_root: i128,
+) -> Result<Instruction, ProgramError> {
+    Ok(Instruction::new_with_borsh(
+        *program_id,
+        &YieldTokenizerInstruction::InitializePool {
+            scalar_root,
+            initial_anchor,
+            ln_fee_rate_root,
+        },
+        vec![
+            AccountMeta::new(*authority, true),
+            AccountMeta::new_readonly(*config, false),
+            AccountMeta::new_readonly(*yield_tokenizer, false),
+            AccountMeta::new_readonly(*lsu_mint, false),
+            AccountMeta::new_readonly(*pt_mint, false),
+            AccountMeta::new(*pool, false),
+            AccountMeta::new(*lp_mint, false),
+            AccountMeta::new(*pool_lsu_vault, false),
+            AccountMeta::new(*pool_pt_vault, false),
+            AccountMeta::new_readonly(spl_token::id(), false),
+            AccountMeta::new_readonly(*yield_token_program, false),
+            AccountMeta::new_readonly(spl_associated_token_account::id(), false),
+            AccountMeta::new_readonly(system_program::id(), false),
+        ],
+    ))
+}
+
+/// Provide PT and LSU to a pool for LP, taking at most `pt_amount` and `lsu_amount`
+#[allow(clippy::too_many_arguments)]
+pub fn add_liquidity(
+    program_id: &Pubkey,
+    provider: &Pubkey,
+    config: &Pubkey,
+    yield_tokenizer: &Pubkey,
+    lsu_mint: &Pubkey,
+    lsu_exchange_rate_account: &Pubkey,
+    pt_mint: &Pubkey,
+    pool: &Pubkey,
+    lp_mint: &Pubkey,
+    pool_lsu_vault: &Pubkey,
+    pool_pt_vault: &Pubkey,
+    provider_lsu_ata: &Pubkey,
+    provider_pt_ata: &Pubkey,
+    provider_lp_ata: &Pubkey,
+    yield_token_program: &Pubkey,
+    pt_amount: u64,
+    lsu_amount: u64,
+    min_lp_out: u64,
+) -> Result<Instruction, ProgramError> {
+    Ok(Instruction::new_with_borsh(
+        *program_id,
+        &YieldTokenizerInstruction::AddLiquidity {
+            pt_amount,
+            lsu_amount,
+            min_lp_out,
+        },
+        vec![
+            AccountMeta::new(*provider, true),
+            AccountMeta::new_readonly(*config, false),
+            AccountMeta::new(*yield_tokenizer, false),
+            AccountMeta::new_readonly(*lsu_mint, false),
+            AccountMeta::new_readonly(*lsu_exchange_rate_account, false),
+            AccountMeta::new_readonly(*pt_mint, false),
+            AccountMeta::new(*pool, false),
+            AccountMeta::new(*lp_mint, false),
+            AccountMeta::new(*pool_lsu_vault, false),
+            AccountMeta::new(*pool_pt_vault, false),
+            AccountMeta::new(*provider_lsu_ata, false),
+            AccountMeta::new(*provider_pt_ata, false),
+            AccountMeta::new(*provider_lp_ata, false),
+            AccountMeta::new_readonly(spl_token::id(), false),
+            AccountMeta::new_readonly(*yield_token_program, false),
+            AccountMeta::new_readonly(spl_associated_token_account::id(), false),
+            AccountMeta::new_readonly(system_program::id(), false),
+        ],
+    ))
+}
+
+/// Burn LP for a proportional share of the pool's PT and LSU
+#[allow(clippy::too_many_arguments)]
+pub fn remove_liquidity(
+    program_id: &Pubkey,
+    provider: &Pubkey,
+    config: &Pubkey,
+    yield_tokenizer: &Pubkey,
+    lsu_mint: &Pubkey,
+    pt_mint: &Pubkey,
+    pool: &Pubkey,
+    lp_mint: &Pubkey,
+    pool_lsu_vault: &Pubkey,
+    pool_pt_vault: &Pubkey,
+    provider_lsu_ata: &Pubkey,
+    provider_pt_ata: &Pubkey,
+    provider_lp_ata: &Pubkey,
+    yield_token_program: &Pubkey,
+    lp_amount: u64,
+    min_pt_out: u64,
+    min_lsu_out: u64,
+) -> Result<Instruction, ProgramError> {
+    Ok(Instruction::new_with_borsh(
+        *program_id,
+        &YieldTokenizerInstruction::RemoveLiquidity {
+            lp_amount,
+            min_pt_out,
+            min_lsu_out,
+        },
+        vec![
+            AccountMeta::new(*provider, true),
+            AccountMeta::new_readonly(*config, false),
+            AccountMeta::new_readonly(*yield_tokenizer, false),
+            AccountMeta::new_readonly(*lsu_mint, false),
+            AccountMeta::new_readonly(*pt_mint, false),
+            AccountMeta::new(*pool, false),
+            AccountMeta::new(*lp_mint, false),
+            AccountMeta::new(*pool_lsu_vault, false),
+            AccountMeta::new(*pool_pt_vault, false),
+            AccountMeta::new(*provider_lsu_ata, false),
+            AccountMeta::new(*provider_pt_ata, false),
+            AccountMeta::new(*provider_lp_ata, false),
+            AccountMeta::new_readonly(spl_token::id(), false),
+            AccountMeta::new_readonly(*yield_token_program, false),
+        ],
+    ))
+}
+
+/// Buy PT from a pool with exactly `lsu_in` LSU
+#[allow(clippy::too_many_arguments)]
+pub fn swap_exact_lsu_for_pt(
+    program_id: &Pubkey,
+    trader: &Pubkey,
+    config: &Pubkey,
+    yield_tokenizer: &Pubkey,
+    lsu_mint: &Pubkey,
+    lsu_exchange_rate_account: &Pubkey,
+    pt_mint: &Pubkey,
+    pool: &Pubkey,
+    pool_lsu_vault: &Pubkey,
+    pool_pt_vault: &Pubkey,
+    trader_lsu_ata: &Pubkey,
+    trader_pt_ata: &Pubkey,
+    yield_token_program: &Pubkey,
+    lsu_in: u64,
+    min_pt_out: u64,
+) -> Result<Instruction, ProgramError> {
+    Ok(Instruction::new_with_borsh(
+        *program_id,
+        &YieldTokenizerInstruction::SwapExactLsuForPt { lsu_in, min_pt_out },
+        swap_accounts(
+            trader,
+            config,
+            yield_tokenizer,
+            lsu_mint,
+            lsu_exchange_rate_account,
+            pt_mint,
+            pool,
+            pool_lsu_vault,
+            pool_pt_vault,
+            trader_lsu_ata,
+            trader_pt_ata,
+            yield_token_program,
+        ),
+    ))
+}
+
+/// Sell exactly `pt_in` PT to a pool for LSU
+#[allow(clippy::too_many_arguments)]
+pub fn swap_exact_pt_for_lsu(
+    program_id: &Pubkey,
+    trader: &Pubkey,
+    config: &Pubkey,
+    yield_tokenizer: &Pubkey,
+    lsu_mint: &Pubkey,
+    lsu_exchange_rate_account: &Pubkey,
+    pt_mint: &Pubkey,
+    pool: &Pubkey,
+    pool_lsu_vault: &Pubkey,
+    pool_pt_vault: &Pubkey,
+    trader_lsu_ata: &Pubkey,
+    trader_pt_ata: &Pubkey,
+    yield_token_program: &Pubkey,
+    pt_in: u64,
+    min_lsu_out: u64,
+) -> Result<Instruction, ProgramError> {
+    Ok(Instruction::new_with_borsh(
+        *program_id,
+        &YieldTokenizerInstruction::SwapExactPtForLsu { pt_in, min_lsu_out },
+        swap_accounts(
+            trader,
+            config,
+            yield_tokenizer,
+            lsu_mint,
+            lsu_exchange_rate_account,
+            pt_mint,
+            pool,
+            pool_lsu_vault,
+            pool_pt_vault,
+            trader_lsu_ata,
+            trader_pt_ata,
+            yield_token_program,
+        ),
+    ))
+}
+
+#[allow(clippy::too_many_arguments)]
+fn swap_accounts(
+    trader: &Pubkey,
+    config: &Pubkey,
+    yield_tokenizer: &Pubkey,
+    lsu_mint: &Pubkey,
+    lsu_exchange_rate_account: &Pubkey,
+    pt_mint: &Pubkey,
+    pool: &Pubkey,
+    pool_lsu_vault: &Pubkey,
+    pool_pt_vault: &Pubkey,
+    trader_lsu_ata: &Pubkey,
+    trader_pt_ata: &Pubkey,
+    yield_token_program: &Pubkey,
+) -> Vec<AccountMeta> {
+    vec![
+        AccountMeta::new(*trader, true),
+        AccountMeta::new_readonly(*config, false),
+        AccountMeta::new(*yield_tokenizer, false),
+        AccountMeta::new_readonly(*lsu_mint, false),
+        AccountMeta::new_readonly(*lsu_exchange_rate_account, false),
+        AccountMeta::new_readonly(*pt_mint, false),
+        AccountMeta::new(*pool, false),
+        AccountMeta::new(*pool_lsu_vault, false),
+        AccountMeta::new(*pool_pt_vault, false),
+        AccountMeta::new(*trader_lsu_ata, false),
+        AccountMeta::new(*trader_pt_ata, false),
+        AccountMeta::new_readonly(spl_token::id(), false),
+        AccountMeta::new_readonly(*yield_token_program, false),
+        AccountMeta::new_readonly(spl_associated_token_account::id(), false),
+        AccountMeta::new_readonly(system_program::id(), false),
+    ]
+}
+
+/// Buy YT with exactly `lsu_in` LSU through flash tokenization against the PT pool
+#[allow(clippy::too_many_arguments)]
+pub fn swap_exact_lsu_for_yt(
+    program_id: &Pubkey,
+    trader: &Pubkey,
+    config: &Pubkey,
+    yield_tokenizer: &Pubkey,
+    lsu_mint: &Pubkey,
+    lsu_exchange_rate_account: &Pubkey,
+    pt_mint: &Pubkey,
+    yt_mint: &Pubkey,
+    lsu_vault: &Pubkey,
+    pool: &Pubkey,
+    pool_lsu_vault: &Pubkey,
+    pool_pt_vault: &Pubkey,
+    trader_lsu_ata: &Pubkey,
+    trader_yt_ata: &Pubkey,
+    trader_position: &Pubkey,
+    yield_token_program: &Pubkey,
+    lsu_in: u64,
+    min_yt_out: u64,
+) -> Result<Instruction, ProgramError> {
+    Ok(Instruction::new_with_borsh(
+        *program_id,
+        &YieldTokenizerInstruction::SwapExactLsuForYt { lsu_in, min_yt_out },
+        vec![
+            AccountMeta::new(*trader, true),
+            AccountMeta::new_readonly(*config, false),
+            AccountMeta::new(*yield_tokenizer, false),
+            AccountMeta::new_readonly(*lsu_mint, false),
+            AccountMeta::new_readonly(*lsu_exchange_rate_account, false),
+            AccountMeta::new(*pt_mint, false),
             AccountMeta::new(*yt_mint, false),
             AccountMeta::new(*lsu_vault, false),
-            AccountMeta::new(*yt_vault, false),
-            AccountMeta::new(*redeemer_lsu_ata, false),
-            AccountMeta::new(*redeemer_yt_ata, false),
+            AccountMeta::new(*pool, false),
+            AccountMeta::new(*pool_lsu_vault, false),
+            AccountMeta::new(*pool_pt_vault, false),
+            AccountMeta::new(*trader_lsu_ata, false),
+            AccountMeta::new(*trader_yt_ata, false),
+            AccountMeta::new(*trader_position, false),
+            AccountMeta::new_readonly(spl_token::id(), false),
+            AccountMeta::new_readonly(*yield_token_program, false),
+            AccountMeta::new_readonly(spl_associated_token_account::id(), false),
+            AccountMeta::new_readonly(system_program::id(), false),
+        ],
+    ))
+}
+
+/// Sell exactly `yt_in` YT for LSU by buying back its PT leg from the pool and redeeming the
+/// pair. Yield accrued by the trader is paid out alongside, net of the protocol's yield fee.
+#[allow(clippy::too_many_arguments)]
+pub fn swap_exact_yt_for_lsu(
+    program_id: &Pubkey,
+    trader: &Pubkey,
+    config: &Pubkey,
+    yield_tokenizer: &Pubkey,
+    lsu_mint: &Pubkey,
+    lsu_exchange_rate_account: &Pubkey,
+    pt_mint: &Pubkey,
+    yt_mint: &Pubkey,
+    lsu_vault: &Pubkey,
+    treasury: &Pubkey,
+    pool: &Pubkey,
+    pool_lsu_vault: &Pubkey,
+    pool_pt_vault: &Pubkey,
+    trader_lsu_ata: &Pubkey,
+    trader_yt_ata: &Pubkey,
+    trader_position: &Pubkey,
+    yield_token_program: &Pubkey,
+    yt_in: u64,
+    min_lsu_out: u64,
+) -> Result<Instruction, ProgramError> {
+    Ok(Instruction::new_with_borsh(
+        *program_id,
+        &YieldTokenizerInstruction::SwapExactYtForLsu { yt_in, min_lsu_out },
+        vec![
+            AccountMeta::new(*trader, true),
+            AccountMeta::new_readonly(*config, false),
+            AccountMeta::new(*yield_tokenizer, false),
+            AccountMeta::new_readonly(*lsu_mint, false),
+            AccountMeta::new_readonly(*lsu_exchange_rate_account, false),
+            AccountMeta::new(*pt_mint, false),
+            AccountMeta::new(*yt_mint, false),
+            AccountMeta::new(*lsu_vault, false),
+            AccountMeta::new(*treasury, false),
+            AccountMeta::new(*pool, false),
+            AccountMeta::new(*pool_lsu_vault, false),
+            AccountMeta::new(*pool_pt_vault, false),
+            AccountMeta::new(*trader_lsu_ata, false),
+            AccountMeta::new(*trader_yt_ata, false),
+            AccountMeta::new(*trader_position, false),
+            AccountMeta::new_readonly(spl_token::id(), false),
+            AccountMeta::new_readonly(*yield_token_program, false),
+            AccountMeta::new_readonly(spl_associated_token_account::id(), false),
+            AccountMeta::new_readonly(system_program::id(), false),
+        ],
+    ))
+}
+
+/// Roll `amount` PT of a matured series, plus the final yield on its YT, into PT and YT of a
+/// live series of the same LSU
+#[allow(clippy::too_many_arguments)]
+pub fn rollover(
+    program_id: &Pubkey,
+    owner: &Pubkey,
+    config: &Pubkey,
+    lsu_mint: &Pubkey,
+    matured_yield_tokenizer: &Pubkey,
+    matured_lsu_exchange_rate_account: &Pubkey,
+    matured_pt_mint: &Pubkey,
+    matured_yt_mint: &Pubkey,
+    matured_lsu_vault: &Pubkey,
+    matured_treasury: &Pubkey,
+    owner_matured_pt_ata: &Pubkey,
+    owner_matured_yt_ata: &Pubkey,
+    owner_matured_position: &Pubkey,
+    yield_tokenizer: &Pubkey,
+    lsu_exchange_rate_account: &Pubkey,
+    pt_mint: &Pubkey,
+    yt_mint: &Pubkey,
+    lsu_vault: &Pubkey,
+    owner_pt_ata: &Pubkey,
+    owner_yt_ata: &Pubkey,
+    owner_position: &Pubkey,
+    matured_yield_token_program: &Pubkey,
+    yield_token_program: &Pubkey,
+    amount: u64,
+) -> Result<Instruction, ProgramError> {
+    Ok(Instruction::new_with_borsh(
+        *program_id,
+        &YieldTokenizerInstruction::Rollover { amount },
+        vec![
+            AccountMeta::new(*owner, true),
+            AccountMeta::new_readonly(*config, false),
+            AccountMeta::new_readonly(*lsu_mint, false),
+            AccountMeta::new(*matured_yield_tokenizer, false),
+            AccountMeta::new_readonly(*matured_lsu_exchange_rate_account, false),
+            AccountMeta::new(*matured_pt_mint, false),
+            AccountMeta::new(*matured_yt_mint, false),
+            AccountMeta::new(*matured_lsu_vault, false),
+            AccountMeta::new(*matured_treasury, false),
+            AccountMeta::new(*owner_matured_pt_ata, false),
+            AccountMeta::new(*owner_matured_yt_ata, false),
+            AccountMeta::new(*owner_matured_position, false),
+            AccountMeta::new(*yield_tokenizer, false),
+            AccountMeta::new_readonly(*lsu_exchange_rate_account, false),
+            AccountMeta::new(*pt_mint, false),
+            AccountMeta::new(*yt_mint, false),
+            AccountMeta::new(*lsu_vault, false),
+            AccountMeta::new(*owner_pt_ata, false),
+            AccountMeta::new(*owner_yt_ata, false),
+            AccountMeta::new(*owner_position, false),
+            AccountMeta::new_readonly(spl_token::id(), false),
+            AccountMeta::new_readonly(*matured_yield_token_program, false),
+            AccountMeta::new_readonly(*yield_token_program, false),
+            AccountMeta::new_readonly(spl_associated_token_account::id(), false),
+            AccountMeta::new_readonly(system_program::id(), false),
+        ],
+    ))
+}
+
+/// Close a fully redeemed tokenizer, sending its remaining fees to `destination` and its rent to
+/// `initializer`
+#[allow(clippy::too_many_arguments)]
+pub fn close_yield_tokenizer(
+    program_id: &Pubkey,
+    authority: &Pubkey,
+    config: &Pubkey,
+    yield_tokenizer: &Pubkey,
+    initializer: &Pubkey,
+    pt_mint: &Pubkey,
+    yt_mint: &Pubkey,
+    lsu_vault: &Pubkey,
+    treasury: &Pubkey,
+    destination: &Pubkey,
+    yield_token_program: &Pubkey,
+) -> Result<Instruction, ProgramError> {
+    Ok(Instruction::new_with_borsh(
+        *program_id,
+        &YieldTokenizerInstruction::CloseYieldTokenizer,
+        vec![
+            AccountMeta::new_readonly(*authority, true),
+            AccountMeta::new_readonly(*config, false),
+            AccountMeta::new(*yield_tokenizer, false),
+            AccountMeta::new(*initializer, false),
+            AccountMeta::new_readonly(*pt_mint, false),
+            AccountMeta::new_readonly(*yt_mint, false),
+            AccountMeta::new(*lsu_vault, false),
+            AccountMeta::new(*treasury, false),
+            AccountMeta::new(*destination, false),
+            AccountMeta::new_readonly(spl_token::id(), false),
+            AccountMeta::new_readonly(*yield_token_program, false),
+        ],
+    ))
+}
+
+/// Upgrade a tokenizer, pool, position or config account to the current layout
+pub fn migrate_state(
+    program_id: &Pubkey,
+    payer: &Pubkey,
+    account: &Pubkey,
+) -> Result<Instruction, ProgramError> {
+    Ok(Instruction::new_with_borsh(
+        *program_id,
+        &YieldTokenizerInstruction::MigrateState,
+        vec![
+            AccountMeta::new(*payer, true),
+            AccountMeta::new(*account, false),
+            AccountMeta::new_readonly(system_program::id(), false),
+        ],
+    ))
+}
+
+/// Upgrade a version 0 tokenizer to the current layout, creating its treasury
+#[allow(clippy::too_many_arguments)]
+pub fn migrate_yield_tokenizer(
+    program_id: &Pubkey,
+    payer: &Pubkey,
+    yield_tokenizer: &Pubkey,
+    config: &Pubkey,
+    lsu_mint: &Pubkey,
+    lsu_exchange_rate_account: &Pubkey,
+    treasury: &Pubkey,
+) -> Result<Instruction, ProgramError> {
+    Ok(Instruction::new_with_borsh(
+        *program_id,
+        &YieldTokenizerInstruction::MigrateState,
+        vec![
+            AccountMeta::new(*payer, true),
+            AccountMeta::new(*yield_tokenizer, false),
+            AccountMeta::new_readonly(system_program::id(), false),
+            AccountMeta::new_readonly(*config, false),
+            AccountMeta::new_readonly(*lsu_mint, false),
+            AccountMeta::new_readonly(*lsu_exchange_rate_account, false),
+            AccountMeta::new(*treasury, false),
+            AccountMeta::new_readonly(spl_token::id(), false),
         ],
     ))
 }
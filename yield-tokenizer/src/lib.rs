@@ -1,58 +1,226 @@
-use solana_program::pubkey::Pubkey;
+use solana_program::{
+    pubkey,
+    pubkey::{Pubkey, PubkeyError},
+};
 
+pub mod accounts;
+pub mod adapter;
+pub mod amm;
+pub mod cpi;
+#[cfg(not(feature = "no-entrypoint"))]
 pub mod entrypoint;
 pub mod error;
+pub mod events;
 pub mod instruction;
+pub mod math;
+pub mod maturity;
+pub mod oracle;
 pub mod processor;
+pub mod resolver;
 pub mod state;
 
-pub(crate) const MSOL: &str = "mSoLzYCxHdYgdzU16g5QSh3i5K3z3KZK7ytfqcJm7So";
-pub(crate) const JITOSOL: &str = "J1toso1uCk3RLmjorhTtrVwY9HJ7X8V9yYac6Y7kGCPn";
-pub(crate) const BSOL: &str = "bSo13r4TkiE4KumL71LsHTPpL2euBYLFx6h9HP3piy1";
-pub(crate) const JSOL: &str = "7Q2afV64in6N6SeZsAAB81TJzwDoD6zpqmHkzi9Dcavn";
+pub(crate) const MSOL: Pubkey = pubkey!("mSoLzYCxHdYgdzU16g5QSh3i5K3z3KZK7ytfqcJm7So");
+pub(crate) const JITOSOL: Pubkey = pubkey!("J1toso1uCk3RLmjorhTtrVwY9HJ7X8V9yYac6Y7kGCPn");
+pub(crate) const BSOL: Pubkey = pubkey!("bSo13r4TkiE4KumL71LsHTPpL2euBYLFx6h9HP3piy1");
+pub(crate) const JSOL: Pubkey = pubkey!("7Q2afV64in6N6SeZsAAB81TJzwDoD6zpqmHkzi9Dcavn");
 
 pub(crate) const LSD_SEED: &[u8; 6] = b"___lsd";
 pub(crate) const PT_SEED: &[u8; 5] = b"___bt";
 pub(crate) const YT_SEED: &[u8; 5] = b"___yt";
+pub(crate) const POSITION_SEED: &[u8; 6] = b"___pos";
+pub(crate) const CONFIG_SEED: &[u8; 9] = b"___config";
+pub(crate) const TREASURY_SEED: &[u8; 11] = b"___treasury";
+pub(crate) const POOL_SEED: &[u8; 7] = b"___pool";
+pub(crate) const LP_SEED: &[u8; 5] = b"___lp";
 
 solana_program::declare_id!("LSDjBzV1CdC4zeXETyLnoUddeBeQAvXXRo49j8rSguH");
 
-pub fn get_yield_tokenizer_address(lsu_mint: &Pubkey, maturity_date: i64) -> Pubkey {
-    let (yield_tokenizer_addr, _) = Pubkey::find_program_address(
+pub fn get_yield_tokenizer_address(
+    program_id: &Pubkey,
+    lsu_mint: &Pubkey,
+    maturity_date: i64,
+) -> Pubkey {
+    let (yield_tokenizer_addr, _) =
+        find_yield_tokenizer_address(program_id, lsu_mint, maturity_date);
+    yield_tokenizer_addr
+}
+
+pub fn find_yield_tokenizer_address(
+    program_id: &Pubkey,
+    lsu_mint: &Pubkey,
+    maturity_date: i64,
+) -> (Pubkey, u8) {
+    Pubkey::find_program_address(
         &[
             crate::LSD_SEED,
             lsu_mint.as_ref(),
             &maturity_date.to_le_bytes(),
         ],
-        &crate::id(),
-    );
-    yield_tokenizer_addr
+        program_id,
+    )
 }
 
-pub fn get_yield_token_address_from_lsu_and_expiry(
+/// Tokenizer address from its stored bump, skipping the bump search
+pub fn create_yield_tokenizer_address(
+    program_id: &Pubkey,
+    lsu_mint: &Pubkey,
+    maturity_date: i64,
+    bump: u8,
+) -> Result<Pubkey, PubkeyError> {
+    Pubkey::create_program_address(
+        &[
+            crate::LSD_SEED,
+            lsu_mint.as_ref(),
+            &maturity_date.to_le_bytes(),
+            &[bump],
+        ],
+        program_id,
+    )
+}
+
+pub fn get_yield_token_address_from_lsu_and_maturity(
+    program_id: &Pubkey,
     lsu_mint: &Pubkey,
     maturity_date: i64,
 ) -> Pubkey {
-    get_yield_token_address(&get_yield_tokenizer_address(lsu_mint, maturity_date))
+    get_yield_token_address(
+        program_id,
+        &get_yield_tokenizer_address(program_id, lsu_mint, maturity_date),
+    )
 }
 
-pub fn get_yield_token_address(yield_tokenizer: &Pubkey) -> Pubkey {
-    let (yield_token_addr, _) =
-        Pubkey::find_program_address(&[yield_tokenizer.as_ref(), crate::YT_SEED], &crate::id());
+#[deprecated(note = "use `get_yield_token_address_from_lsu_and_maturity`")]
+pub fn get_yield_token_address_from_lsu_and_expiry(
+    program_id: &Pubkey,
+    lsu_mint: &Pubkey,
+    maturity_date: i64,
+) -> Pubkey {
+    get_yield_token_address_from_lsu_and_maturity(program_id, lsu_mint, maturity_date)
+}
 
+pub fn get_yield_token_address(program_id: &Pubkey, yield_tokenizer: &Pubkey) -> Pubkey {
+    let (yield_token_addr, _) = find_yield_token_address(program_id, yield_tokenizer);
     yield_token_addr
 }
 
-pub fn get_principal_token_address_from_lsu_and_expiry(
+pub fn find_yield_token_address(program_id: &Pubkey, yield_tokenizer: &Pubkey) -> (Pubkey, u8) {
+    Pubkey::find_program_address(&[yield_tokenizer.as_ref(), crate::YT_SEED], program_id)
+}
+
+pub fn get_principal_token_address_from_lsu_and_maturity(
+    program_id: &Pubkey,
     lsu_mint: &Pubkey,
     maturity_date: i64,
 ) -> Pubkey {
-    get_principal_token_address(&get_yield_tokenizer_address(lsu_mint, maturity_date))
+    get_principal_token_address(
+        program_id,
+        &get_yield_tokenizer_address(program_id, lsu_mint, maturity_date),
+    )
 }
 
-pub fn get_principal_token_address(yield_tokenizer: &Pubkey) -> Pubkey {
-    let (principal_token_addr, _) =
-        Pubkey::find_program_address(&[yield_tokenizer.as_ref(), crate::PT_SEED], &crate::id());
+#[deprecated(note = "use `get_principal_token_address_from_lsu_and_maturity`")]
+pub fn get_principal_token_address_from_lsu_and_expiry(
+    program_id: &Pubkey,
+    lsu_mint: &Pubkey,
+    maturity_date: i64,
+) -> Pubkey {
+    get_principal_token_address_from_lsu_and_maturity(program_id, lsu_mint, maturity_date)
+}
 
+pub fn get_principal_token_address(program_id: &Pubkey, yield_tokenizer: &Pubkey) -> Pubkey {
+    let (principal_token_addr, _) = find_principal_token_address(program_id, yield_tokenizer);
     principal_token_addr
 }
+
+pub fn find_principal_token_address(program_id: &Pubkey, yield_tokenizer: &Pubkey) -> (Pubkey, u8) {
+    Pubkey::find_program_address(&[yield_tokenizer.as_ref(), crate::PT_SEED], program_id)
+}
+
+pub fn get_yield_position_address(
+    program_id: &Pubkey,
+    yield_tokenizer: &Pubkey,
+    owner: &Pubkey,
+) -> Pubkey {
+    let (position_addr, _) = find_yield_position_address(program_id, yield_tokenizer, owner);
+    position_addr
+}
+
+pub fn find_yield_position_address(
+    program_id: &Pubkey,
+    yield_tokenizer: &Pubkey,
+    owner: &Pubkey,
+) -> (Pubkey, u8) {
+    Pubkey::find_program_address(
+        &[
+            crate::POSITION_SEED,
+            yield_tokenizer.as_ref(),
+            owner.as_ref(),
+        ],
+        program_id,
+    )
+}
+
+pub fn get_config_address(program_id: &Pubkey) -> Pubkey {
+    let (config_addr, _) = find_config_address(program_id);
+    config_addr
+}
+
+pub fn find_config_address(program_id: &Pubkey) -> (Pubkey, u8) {
+    Pubkey::find_program_address(&[crate::CONFIG_SEED], program_id)
+}
+
+/// LSU token account holding the protocol fees collected by a tokenizer
+pub fn get_treasury_address(program_id: &Pubkey, yield_tokenizer: &Pubkey) -> Pubkey {
+    let (treasury_addr, _) = find_treasury_address(program_id, yield_tokenizer);
+    treasury_addr
+}
+
+pub fn find_treasury_address(program_id: &Pubkey, yield_tokenizer: &Pubkey) -> (Pubkey, u8) {
+    Pubkey::find_program_address(
+        &[crate::TREASURY_SEED, yield_tokenizer.as_ref()],
+        program_id,
+    )
+}
+
+/// Treasury address from its stored bump, skipping the bump search
+pub fn create_treasury_address(
+    program_id: &Pubkey,
+    yield_tokenizer: &Pubkey,
+    bump: u8,
+) -> Result<Pubkey, PubkeyError> {
+    Pubkey::create_program_address(
+        &[crate::TREASURY_SEED, yield_tokenizer.as_ref(), &[bump]],
+        program_id,
+    )
+}
+
+/// PT/LSU pool for a tokenizer's PT
+pub fn get_pool_address(program_id: &Pubkey, yield_tokenizer: &Pubkey) -> Pubkey {
+    let (pool_addr, _) = find_pool_address(program_id, yield_tokenizer);
+    pool_addr
+}
+
+pub fn find_pool_address(program_id: &Pubkey, yield_tokenizer: &Pubkey) -> (Pubkey, u8) {
+    Pubkey::find_program_address(&[crate::POOL_SEED, yield_tokenizer.as_ref()], program_id)
+}
+
+/// Pool address from its stored bump, skipping the bump search
+pub fn create_pool_address(
+    program_id: &Pubkey,
+    yield_tokenizer: &Pubkey,
+    bump: u8,
+) -> Result<Pubkey, PubkeyError> {
+    Pubkey::create_program_address(
+        &[crate::POOL_SEED, yield_tokenizer.as_ref(), &[bump]],
+        program_id,
+    )
+}
+
+pub fn get_lp_mint_address(program_id: &Pubkey, pool: &Pubkey) -> Pubkey {
+    let (lp_mint_addr, _) = find_lp_mint_address(program_id, pool);
+    lp_mint_addr
+}
+
+pub fn find_lp_mint_address(program_id: &Pubkey, pool: &Pubkey) -> (Pubkey, u8) {
+    Pubkey::find_program_address(&[pool.as_ref(), crate::LP_SEED], program_id)
+}
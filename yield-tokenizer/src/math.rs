@@ -0,0 +1,521 @@
+use solana_program::program_error::ProgramError;
+
+/// Fixed-point one, every value in this module is scaled by 1e18
+pub const ONE: i128 = 1_000_000_000_000_000_000;
+
+pub const SECONDS_PER_YEAR: i128 = 365 * 86_400;
+
+/// Largest input to `exp`, e^40 is about 2.35e17
+pub const MAX_EXP_INPUT: i128 = 40 * ONE;
+
+const LN_2: i128 = 693_147_180_559_945_309;
+/// ln(2) scaled by 1e36 so multiples of it can be rounded in either direction
+const LN_2_E36: i128 = 693_147_180_559_945_309_417_232_121_458_176_568;
+
+/// Direction to round a result in. Callers pick whichever favors the protocol, e.g. `Up` for the
+/// price of PT the protocol sells and `Down` for the price of PT it buys.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Rounding {
+    Down,
+    Up,
+}
+
+impl Rounding {
+    pub fn opposite(self) -> Self {
+        match self {
+            Rounding::Down => Rounding::Up,
+            Rounding::Up => Rounding::Down,
+        }
+    }
+}
+
+/// `a * b / denominator`, rounded towards negative or positive infinity
+pub fn mul_div(
+    a: i128,
+    b: i128,
+    denominator: i128,
+    rounding: Rounding,
+) -> Result<i128, ProgramError> {
+    if denominator == 0 {
+        return Err(ProgramError::InvalidArgument);
+    }
+    let numerator = a.checked_mul(b).ok_or(ProgramError::ArithmeticOverflow)?;
+
+    let quotient = numerator / denominator;
+    let remainder = numerator % denominator;
+    if remainder == 0 {
+        return Ok(quotient);
+    }
+    // `/` truncates towards zero, so the exact quotient is above `quotient` when it's positive
+    let exact_is_above = (remainder > 0) == (denominator > 0);
+    match (rounding, exact_is_above) {
+        (Rounding::Up, true) => Ok(quotient + 1),
+        (Rounding::Down, false) => Ok(quotient - 1),
+        _ => Ok(quotient),
+    }
+}
+
+pub fn mul(a: i128, b: i128, rounding: Rounding) -> Result<i128, ProgramError> {
+    mul_div(a, b, ONE, rounding)
+}
+
+pub fn div(a: i128, b: i128, rounding: Rounding) -> Result<i128, ProgramError> {
+    mul_div(a, ONE, b, rounding)
+}
+
+/// e^x. `Down` never exceeds the exact value and `Up` is never below it, both are within about
+/// 1e-16 relative of it.
+pub fn exp(x: i128, rounding: Rounding) -> Result<i128, ProgramError> {
+    if x > MAX_EXP_INPUT {
+        return Err(ProgramError::ArithmeticOverflow);
+    }
+    if x == 0 {
+        return Ok(ONE);
+    }
+    if x < 0 {
+        // Below -MAX_EXP_INPUT the result is under 5e-18, which e^-MAX_EXP_INPUT still bounds
+        if x < -MAX_EXP_INPUT {
+            return match rounding {
+                Rounding::Down => Ok(0),
+                Rounding::Up => exp(-MAX_EXP_INPUT, Rounding::Up),
+            };
+        }
+        let reciprocal = exp(-x, rounding.opposite())?;
+        return mul_div(ONE, ONE, reciprocal, rounding);
+    }
+
+    // x = k * ln(2) + r, with r rounded the same way as the result
+    let mut k = x / LN_2;
+    let mut r = x - mul_div(k, LN_2_E36, ONE, rounding.opposite())?;
+    if r < 0 {
+        k -= 1;
+        r = x - mul_div(k, LN_2_E36, ONE, rounding.opposite())?;
+    }
+
+    // Taylor series of e^r, every term is positive so rounding each one bounds the sum
+    let mut sum = ONE;
+    let mut term = ONE;
+    let mut i = 1;
+    loop {
+        term = mul_div(term, r, i * ONE, rounding)?;
+        match rounding {
+            Rounding::Down if term == 0 => break,
+            // With r < 1 the remaining terms add up to less than the current one
+            Rounding::Up if term <= 1 => {
+                sum += term + 1;
+                break;
+            }
+            _ => sum += term,
+        }
+        i += 1;
+    }
+
+    sum.checked_mul(1 << k)
+        .ok_or(ProgramError::ArithmeticOverflow)
+}
+
+/// Natural log of `x`, which must be positive. `Down` never exceeds the exact value and `Up` is
+/// never below it, both are within a few hundred units of 1e-18.
+pub fn ln(x: i128, rounding: Rounding) -> Result<i128, ProgramError> {
+    if x <= 0 {
+        return Err(ProgramError::InvalidArgument);
+    }
+
+    // x = y * 2^k with y in [1, 2]
+    let mut y = x;
+    let mut k: i128 = 0;
+    while y >= 2 * ONE {
+        y = match rounding {
+            Rounding::Down => y >> 1,
+            Rounding::Up => (y + 1) >> 1,
+        };
+        k += 1;
+    }
+    while y < ONE {
+        y <<= 1;
+        k -= 1;
+    }
+
+    let k_ln_2 = mul_div(k, LN_2_E36, ONE, rounding)?;
+    if y == ONE {
+        return Ok(k_ln_2);
+    }
+
+    // ln(y) = 2 * atanh(z) with z = (y - 1) / (y + 1) in [0, 1/3], a series of positive terms
+    let z = mul_div(y - ONE, ONE, y + ONE, rounding)?;
+    let z_squared = mul(z, z, rounding)?;
+    let mut sum = z;
+    let mut power = z;
+    let mut n = 3;
+    loop {
+        power = mul(power, z_squared, rounding)?;
+        let term = mul_div(power, 1, n, rounding)?;
+        match rounding {
+            Rounding::Down if term == 0 => break,
+            // Terms shrink by at least z^2 <= 1/9, so the rest add up to less than this one
+            Rounding::Up if term <= 1 => {
+                sum += term + 1;
+                break;
+            }
+            _ => sum += term,
+        }
+        n += 2;
+    }
+
+    Ok(2 * sum + k_ln_2)
+}
+
+/// Time left until `maturity_date` as a fraction of a 365 day year, zero once matured
+pub fn years_to_maturity(
+    now: i64,
+    maturity_date: i64,
+    rounding: Rounding,
+) -> Result<i128, ProgramError> {
+    if now >= maturity_date {
+        return Ok(0);
+    }
+    mul_div(
+        (maturity_date - now) as i128,
+        ONE,
+        SECONDS_PER_YEAR,
+        rounding,
+    )
+}
+
+/// Price of PT in the accounting asset it redeems for, `e^(-ln_implied_rate * years)`, where
+/// `ln_implied_rate` is `ln(1 + implied_rate)`
+pub fn pt_price_from_ln_implied_rate(
+    ln_implied_rate: i128,
+    years: i128,
+    rounding: Rounding,
+) -> Result<i128, ProgramError> {
+    let exponent = mul(ln_implied_rate, years, rounding.opposite())?;
+    exp(-exponent, rounding)
+}
+
+/// Price of PT in the accounting asset it redeems for, `(1 + implied_rate)^-years`
+pub fn pt_price_from_implied_rate(
+    implied_rate: i128,
+    years: i128,
+    rounding: Rounding,
+) -> Result<i128, ProgramError> {
+    let ln_implied_rate = ln(ONE + implied_rate, rounding.opposite())?;
+    pt_price_from_ln_implied_rate(ln_implied_rate, years, rounding)
+}
+
+/// `ln(1 + implied_rate)` implied by a PT price, `-ln(pt_price) / years`
+pub fn ln_implied_rate_from_pt_price(
+    pt_price: i128,
+    years: i128,
+    rounding: Rounding,
+) -> Result<i128, ProgramError> {
+    if years <= 0 {
+        return Err(ProgramError::InvalidArgument);
+    }
+    let ln_pt_price = ln(pt_price, rounding.opposite())?;
+    mul_div(-ln_pt_price, ONE, years, rounding)
+}
+
+/// Annually compounded rate implied by a PT price, `pt_price^(-1 / years) - 1`
+pub fn implied_rate_from_pt_price(
+    pt_price: i128,
+    years: i128,
+    rounding: Rounding,
+) -> Result<i128, ProgramError> {
+    let ln_implied_rate = ln_implied_rate_from_pt_price(pt_price, years, rounding)?;
+    Ok(exp(ln_implied_rate, rounding)? - ONE)
+}
+
+/// Price of YT in the accounting asset, PT and YT together are always worth one unit
+pub fn yt_price_from_implied_rate(
+    implied_rate: i128,
+    years: i128,
+    rounding: Rounding,
+) -> Result<i128, ProgramError> {
+    Ok(ONE - pt_price_from_implied_rate(implied_rate, years, rounding.opposite())?)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const DAY: i64 = 86_400;
+
+    /// Checks `down <= floor(exact)`, `up >= ceil(exact)` and both are within `tolerance`
+    fn assert_bounds(down: i128, up: i128, exact_floor: i128, tolerance: i128) {
+        assert!(
+            down <= exact_floor,
+            "{} rounded down above {}",
+            down,
+            exact_floor
+        );
+        assert!(up > exact_floor, "{} rounded up below {}", up, exact_floor);
+        assert!(
+            exact_floor - down <= tolerance,
+            "{} too far below {}",
+            down,
+            exact_floor
+        );
+        assert!(
+            up - exact_floor <= tolerance + 1,
+            "{} too far above {}",
+            up,
+            exact_floor
+        );
+    }
+
+    fn assert_exp(x: i128, exact_floor: i128, tolerance: i128) {
+        assert_bounds(
+            exp(x, Rounding::Down).unwrap(),
+            exp(x, Rounding::Up).unwrap(),
+            exact_floor,
+            tolerance,
+        );
+    }
+
+    fn assert_ln(x: i128, exact_floor: i128, tolerance: i128) {
+        assert_bounds(
+            ln(x, Rounding::Down).unwrap(),
+            ln(x, Rounding::Up).unwrap(),
+            exact_floor,
+            tolerance,
+        );
+    }
+
+    #[test]
+    fn test_mul_div_rounding() {
+        assert_eq!(mul_div(7, 1, 2, Rounding::Down).unwrap(), 3);
+        assert_eq!(mul_div(7, 1, 2, Rounding::Up).unwrap(), 4);
+        assert_eq!(mul_div(-7, 1, 2, Rounding::Down).unwrap(), -4);
+        assert_eq!(mul_div(-7, 1, 2, Rounding::Up).unwrap(), -3);
+        assert_eq!(mul_div(7, 1, -2, Rounding::Down).unwrap(), -4);
+        assert_eq!(mul_div(-7, 1, -2, Rounding::Up).unwrap(), 4);
+        assert_eq!(mul_div(6, 1, 2, Rounding::Up).unwrap(), 3);
+        assert_eq!(mul_div(6, 1, -2, Rounding::Down).unwrap(), -3);
+    }
+
+    #[test]
+    fn test_mul_div_errors() {
+        assert_eq!(
+            mul_div(1, 1, 0, Rounding::Down),
+            Err(ProgramError::InvalidArgument)
+        );
+        assert_eq!(
+            mul_div(i128::MAX, 2, 1, Rounding::Down),
+            Err(ProgramError::ArithmeticOverflow)
+        );
+    }
+
+    #[test]
+    fn test_mul_and_div() {
+        assert_eq!(mul(ONE / 2, 3 * ONE, Rounding::Down).unwrap(), 3 * ONE / 2);
+        assert_eq!(
+            div(ONE, 3 * ONE, Rounding::Down).unwrap(),
+            333_333_333_333_333_333
+        );
+        assert_eq!(
+            div(ONE, 3 * ONE, Rounding::Up).unwrap(),
+            333_333_333_333_333_334
+        );
+    }
+
+    #[test]
+    fn test_exp_zero() {
+        assert_eq!(exp(0, Rounding::Down).unwrap(), ONE);
+        assert_eq!(exp(0, Rounding::Up).unwrap(), ONE);
+    }
+
+    #[test]
+    fn test_exp_known_values() {
+        assert_exp(ONE, 2_718_281_828_459_045_235, 100);
+        assert_exp(ONE / 2, 1_648_721_270_700_128_146, 100);
+        assert_exp(-ONE, 367_879_441_171_442_321, 100);
+        assert_exp(5 * ONE / 2, 12_182_493_960_703_473_438, 1_000);
+        assert_exp(10 * ONE, 22_026_465_794_806_716_516_957, 1_000_000);
+        assert_exp(1, 1_000_000_000_000_000_001, 2);
+        assert_exp(-1, 999_999_999_999_999_999, 2);
+        assert_exp(LN_2, 1_999_999_999_999_999_999, 100);
+    }
+
+    #[test]
+    fn test_exp_limits() {
+        let down = exp(MAX_EXP_INPUT, Rounding::Down).unwrap();
+        let up = exp(MAX_EXP_INPUT, Rounding::Up).unwrap();
+        let exact = 235_385_266_837_019_985_407_899_910_749_034_804;
+        assert!(down <= exact && up > exact);
+        // Relative error below 1e-15
+        assert!((up - down) < exact / 1_000_000_000_000_000);
+
+        assert_exp(-MAX_EXP_INPUT, 4, 1);
+        assert_eq!(exp(-MAX_EXP_INPUT - 1, Rounding::Down).unwrap(), 0);
+        assert!(exp(-MAX_EXP_INPUT - 1, Rounding::Up).unwrap() >= 1);
+        assert_eq!(
+            exp(MAX_EXP_INPUT + 1, Rounding::Down),
+            Err(ProgramError::ArithmeticOverflow)
+        );
+    }
+
+    #[test]
+    fn test_exp_is_monotonic() {
+        let mut previous = 0;
+        for i in -200..=200 {
+            let value = exp(i * ONE / 20, Rounding::Down).unwrap();
+            assert!(value >= previous);
+            previous = value;
+        }
+    }
+
+    #[test]
+    fn test_ln_one() {
+        assert_eq!(ln(ONE, Rounding::Down).unwrap(), 0);
+        assert_eq!(ln(ONE, Rounding::Up).unwrap(), 0);
+    }
+
+    #[test]
+    fn test_ln_known_values() {
+        assert_ln(2 * ONE, 693_147_180_559_945_309, 100);
+        assert_ln(ONE / 2, -693_147_180_559_945_310, 100);
+        assert_ln(10 * ONE, 2_302_585_092_994_045_684, 100);
+        assert_ln(11 * ONE / 10, 95_310_179_804_324_860, 100);
+        assert_ln(95 * ONE / 100, -51_293_294_387_550_534, 100);
+        assert_ln(ONE - 1, -2, 100);
+        assert_ln(1_000_000_000, -20_723_265_836_946_411_157, 100);
+        assert_ln(100 * ONE * ONE, 46_051_701_859_880_913_680, 100);
+    }
+
+    #[test]
+    fn test_ln_invalid_input() {
+        assert_eq!(ln(0, Rounding::Down), Err(ProgramError::InvalidArgument));
+        assert_eq!(ln(-ONE, Rounding::Up), Err(ProgramError::InvalidArgument));
+    }
+
+    #[test]
+    fn test_exp_ln_round_trip() {
+        for x in [
+            ONE / 1000,
+            ONE / 3,
+            ONE,
+            7 * ONE / 3,
+            50 * ONE,
+            12_345 * ONE,
+        ] {
+            let down = exp(ln(x, Rounding::Down).unwrap(), Rounding::Down).unwrap();
+            let up = exp(ln(x, Rounding::Up).unwrap(), Rounding::Up).unwrap();
+            assert!(down <= x && up >= x);
+            assert!((up - down) <= x / 1_000_000_000_000_000 + 10);
+        }
+    }
+
+    #[test]
+    fn test_years_to_maturity() {
+        assert_eq!(
+            years_to_maturity(0, 365 * DAY, Rounding::Down).unwrap(),
+            ONE
+        );
+        assert_eq!(
+            years_to_maturity(100, 100 + 73 * DAY, Rounding::Down).unwrap(),
+            ONE / 5
+        );
+        assert_eq!(
+            years_to_maturity(0, 1, Rounding::Down).unwrap(),
+            31_709_791_983
+        );
+        assert_eq!(
+            years_to_maturity(0, 1, Rounding::Up).unwrap(),
+            31_709_791_984
+        );
+        assert_eq!(years_to_maturity(10, 10, Rounding::Up).unwrap(), 0);
+        assert_eq!(years_to_maturity(11, 10, Rounding::Up).unwrap(), 0);
+    }
+
+    #[test]
+    fn test_pt_price_from_implied_rate() {
+        // 10% for a year
+        assert_bounds(
+            pt_price_from_implied_rate(ONE / 10, ONE, Rounding::Down).unwrap(),
+            pt_price_from_implied_rate(ONE / 10, ONE, Rounding::Up).unwrap(),
+            909_090_909_090_909_090,
+            200,
+        );
+        // 10% for half a year
+        assert_bounds(
+            pt_price_from_implied_rate(ONE / 10, ONE / 2, Rounding::Down).unwrap(),
+            pt_price_from_implied_rate(ONE / 10, ONE / 2, Rounding::Up).unwrap(),
+            953_462_589_245_592_315,
+            200,
+        );
+        // 5% for a quarter
+        assert_bounds(
+            pt_price_from_implied_rate(ONE / 20, ONE / 4, Rounding::Down).unwrap(),
+            pt_price_from_implied_rate(ONE / 20, ONE / 4, Rounding::Up).unwrap(),
+            987_876_547_423_074_104,
+            200,
+        );
+    }
+
+    #[test]
+    fn test_pt_price_at_maturity() {
+        assert_eq!(
+            pt_price_from_implied_rate(ONE / 10, 0, Rounding::Down).unwrap(),
+            ONE
+        );
+        assert_eq!(
+            pt_price_from_ln_implied_rate(ONE, 0, Rounding::Down).unwrap(),
+            ONE
+        );
+    }
+
+    #[test]
+    fn test_implied_rate_from_pt_price() {
+        // PT at 0.95 with half a year left
+        assert_bounds(
+            ln_implied_rate_from_pt_price(95 * ONE / 100, ONE / 2, Rounding::Down).unwrap(),
+            ln_implied_rate_from_pt_price(95 * ONE / 100, ONE / 2, Rounding::Up).unwrap(),
+            102_586_588_775_101_066,
+            200,
+        );
+        assert_bounds(
+            implied_rate_from_pt_price(95 * ONE / 100, ONE / 2, Rounding::Down).unwrap(),
+            implied_rate_from_pt_price(95 * ONE / 100, ONE / 2, Rounding::Up).unwrap(),
+            108_033_240_997_229_916,
+            200,
+        );
+        assert_eq!(
+            implied_rate_from_pt_price(ONE, ONE, Rounding::Down).unwrap(),
+            0
+        );
+        assert_eq!(
+            implied_rate_from_pt_price(ONE, 0, Rounding::Down),
+            Err(ProgramError::InvalidArgument)
+        );
+    }
+
+    #[test]
+    fn test_implied_rate_round_trip() {
+        let years = years_to_maturity(0, 200 * DAY, Rounding::Down).unwrap();
+        for rate in [ONE / 100, ONE / 15, ONE / 4, 2 * ONE] {
+            let pt_price = pt_price_from_implied_rate(rate, years, Rounding::Down).unwrap();
+            let implied_rate = implied_rate_from_pt_price(pt_price, years, Rounding::Down).unwrap();
+            assert!(implied_rate >= rate - 1_000);
+            assert!(implied_rate <= rate + 1_000);
+        }
+    }
+
+    #[test]
+    fn test_yt_price() {
+        assert_bounds(
+            yt_price_from_implied_rate(ONE / 10, ONE, Rounding::Down).unwrap(),
+            yt_price_from_implied_rate(ONE / 10, ONE, Rounding::Up).unwrap(),
+            90_909_090_909_090_909,
+            200,
+        );
+        assert_eq!(
+            yt_price_from_implied_rate(ONE / 10, 0, Rounding::Up).unwrap(),
+            0
+        );
+        // PT and YT prices rounded in opposite directions add up to one
+        let pt_price = pt_price_from_implied_rate(ONE / 10, ONE, Rounding::Up).unwrap();
+        let yt_price = yt_price_from_implied_rate(ONE / 10, ONE, Rounding::Down).unwrap();
+        assert_eq!(pt_price + yt_price, ONE);
+    }
+}
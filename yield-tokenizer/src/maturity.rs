@@ -0,0 +1,214 @@
+pub const DAY_SEC: i64 = 86_400;
+
+/// Furthest a maturity date can be from the time the tokenizer is initialized
+pub const MAX_MATURITY_HORIZON: i64 = 731 * DAY_SEC;
+
+/// Checks `maturity_date` is 00:00 UTC on a month-end that is in the future and within
+/// `MAX_MATURITY_HORIZON` of `now`. Aligning maturities means every series for an LSU and month
+/// maps to a single tokenizer, e.g. `mSOL-2027-03-31`.
+pub fn is_valid_maturity_date(maturity_date: i64, now: i64) -> bool {
+    if maturity_date <= now || maturity_date - now > MAX_MATURITY_HORIZON {
+        return false;
+    }
+    if maturity_date.rem_euclid(DAY_SEC) != 0 {
+        return false;
+    }
+
+    let (year, month, day) = civil_from_days(maturity_date.div_euclid(DAY_SEC));
+    day == days_in_month(year, month)
+}
+
+/// Timestamp of 00:00 UTC on the last day of `month`
+pub fn month_end_maturity_date(year: i64, month: u32) -> Option<i64> {
+    if !(1..=12).contains(&month) {
+        return None;
+    }
+    Some(days_from_civil(year, month, days_in_month(year, month)) * DAY_SEC)
+}
+
+/// Parses a `YYYY-MM-DD` month-end date into a maturity timestamp
+pub fn parse_maturity_date(date: &str) -> Option<i64> {
+    let bytes = date.as_bytes();
+    let is_digits = |range: std::ops::Range<usize>| bytes[range].iter().all(u8::is_ascii_digit);
+    if bytes.len() != 10
+        || bytes[4] != b'-'
+        || bytes[7] != b'-'
+        || !is_digits(0..4)
+        || !is_digits(5..7)
+        || !is_digits(8..10)
+    {
+        return None;
+    }
+    let year = date[0..4].parse::<i64>().ok()?;
+    let month = date[5..7].parse::<u32>().ok()?;
+    let day = date[8..10].parse::<u32>().ok()?;
+
+    let maturity_date = month_end_maturity_date(year, month)?;
+    if day != days_in_month(year, month) {
+        return None;
+    }
+    Some(maturity_date)
+}
+
+const MONTHS: [&str; 12] = [
+    "JAN", "FEB", "MAR", "APR", "MAY", "JUN", "JUL", "AUG", "SEP", "OCT", "NOV", "DEC",
+];
+
+/// Formats a maturity timestamp as `DDMMMYYYY`, e.g. `31MAR2027`, for token names
+pub fn format_maturity_label(maturity_date: i64) -> String {
+    let (year, month, day) = civil_from_days(maturity_date.div_euclid(DAY_SEC));
+    format!("{:02}{}{:04}", day, MONTHS[month as usize - 1], year)
+}
+
+/// Formats a maturity timestamp as `YYYY-MM-DD`
+pub fn format_maturity_date(maturity_date: i64) -> String {
+    let (year, month, day) = civil_from_days(maturity_date.div_euclid(DAY_SEC));
+    format!("{:04}-{:02}-{:02}", year, month, day)
+}
+
+pub fn days_in_month(year: i64, month: u32) -> u32 {
+    match month {
+        2 if year % 4 == 0 && (year % 100 != 0 || year % 400 == 0) => 29,
+        2 => 28,
+        4 | 6 | 9 | 11 => 30,
+        _ => 31,
+    }
+}
+
+/// Days since 1970-01-01 to (year, month, day), Howard Hinnant's `civil_from_days`
+pub fn civil_from_days(days: i64) -> (i64, u32, u32) {
+    let z = days + 719_468;
+    let era = z.div_euclid(146_097);
+    let doe = z.rem_euclid(146_097);
+    let yoe = (doe - doe / 1_460 + doe / 36_524 - doe / 146_096) / 365;
+    let doy = doe - (365 * yoe + yoe / 4 - yoe / 100);
+    let mp = (5 * doy + 2) / 153;
+    let day = (doy - (153 * mp + 2) / 5 + 1) as u32;
+    let month = if mp < 10 { mp + 3 } else { mp - 9 } as u32;
+    let year = yoe + era * 400 + i64::from(month <= 2);
+    (year, month, day)
+}
+
+/// (year, month, day) to days since 1970-01-01, Howard Hinnant's `days_from_civil`
+pub fn days_from_civil(year: i64, month: u32, day: u32) -> i64 {
+    let year = year - i64::from(month <= 2);
+    let era = year.div_euclid(400);
+    let yoe = year.rem_euclid(400);
+    let mp = (month as i64 + 9) % 12;
+    let doy = (153 * mp + 2) / 5 + day as i64 - 1;
+    let doe = yoe * 365 + yoe / 4 - yoe / 100 + doy;
+    era * 146_097 + doe - 719_468
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const NOW: i64 = 1_792_281_600; // 2026-10-18
+
+    #[test]
+    fn test_leap_februaries() {
+        assert_eq!(parse_maturity_date("2028-02-29"), Some(1_835_395_200));
+        assert_eq!(parse_maturity_date("2028-02-28"), None);
+        assert_eq!(parse_maturity_date("2100-02-28"), Some(4_107_456_000));
+        assert_eq!(parse_maturity_date("2100-02-29"), None);
+        assert_eq!(parse_maturity_date("2000-02-29"), Some(951_782_400));
+        assert_eq!(parse_maturity_date("2000-02-28"), None);
+
+        assert_eq!(days_in_month(2028, 2), 29);
+        assert_eq!(days_in_month(2100, 2), 28);
+        assert_eq!(days_in_month(2000, 2), 29);
+        assert_eq!(days_in_month(2027, 2), 28);
+    }
+
+    #[test]
+    fn test_valid_maturity_dates() {
+        let march_2027 = parse_maturity_date("2027-03-31").unwrap();
+        assert!(is_valid_maturity_date(march_2027, NOW));
+        assert!(is_valid_maturity_date(1_835_395_200, NOW));
+        assert_eq!(month_end_maturity_date(2027, 3), Some(march_2027));
+        assert_eq!(month_end_maturity_date(2027, 13), None);
+        assert_eq!(month_end_maturity_date(2027, 0), None);
+    }
+
+    #[test]
+    fn test_rejects_non_midnight_and_non_month_end() {
+        let march_2027 = parse_maturity_date("2027-03-31").unwrap();
+        assert!(!is_valid_maturity_date(march_2027 + 1, NOW));
+        assert!(!is_valid_maturity_date(march_2027 + 12 * 3_600, NOW));
+        assert!(!is_valid_maturity_date(march_2027 - 1, NOW));
+        assert!(!is_valid_maturity_date(march_2027 - DAY_SEC, NOW));
+        assert!(!is_valid_maturity_date(march_2027 + DAY_SEC, NOW));
+    }
+
+    #[test]
+    fn test_maturity_horizon() {
+        let march_2027 = parse_maturity_date("2027-03-31").unwrap();
+        assert!(is_valid_maturity_date(
+            march_2027,
+            march_2027 - MAX_MATURITY_HORIZON
+        ));
+        assert!(!is_valid_maturity_date(
+            march_2027,
+            march_2027 - MAX_MATURITY_HORIZON - 1
+        ));
+        assert!(is_valid_maturity_date(march_2027, march_2027 - 1));
+        assert!(!is_valid_maturity_date(march_2027, march_2027));
+        assert!(!is_valid_maturity_date(march_2027, march_2027 + DAY_SEC));
+    }
+
+    #[test]
+    fn test_civil_round_trip() {
+        assert_eq!(civil_from_days(0), (1970, 1, 1));
+        assert_eq!(civil_from_days(-1), (1969, 12, 31));
+        assert_eq!(civil_from_days(21_243), (2028, 2, 29));
+        assert_eq!(days_from_civil(2100, 2, 28), 47_540);
+        assert_eq!(days_from_civil(2000, 2, 29), 11_016);
+
+        // 1600-03-01 to 2400-03-01, covering every leap rule on both sides of the epoch
+        for days in -135_080..157_114 {
+            let (year, month, day) = civil_from_days(days);
+            assert!((1..=12).contains(&month) && (1..=days_in_month(year, month)).contains(&day));
+            assert_eq!(days_from_civil(year, month, day), days);
+        }
+    }
+
+    #[test]
+    fn test_rejects_malformed_dates() {
+        for date in [
+            "",
+            "2027",
+            "2027-03",
+            "2027-3-31",
+            "27-03-31",
+            "2027-03-031",
+            "2027/03/31",
+            "2027-03-31 ",
+            " 2027-03-31",
+            "+2027-03-31",
+            "2027-+3-31",
+            "2027-03-3a",
+            "2027-13-31",
+            "2027-00-31",
+            "2027-03-30",
+            "2027-03-32",
+            "2027-04-31",
+            "２０２７-03-31",
+        ] {
+            assert_eq!(parse_maturity_date(date), None, "{:?}", date);
+        }
+    }
+
+    #[test]
+    fn test_format_maturity() {
+        let march_2027 = parse_maturity_date("2027-03-31").unwrap();
+        assert_eq!(format_maturity_label(march_2027), "31MAR2027");
+        assert_eq!(format_maturity_date(march_2027), "2027-03-31");
+        assert_eq!(format_maturity_label(1_835_395_200), "29FEB2028");
+        assert_eq!(format_maturity_label(4_107_456_000), "28FEB2100");
+        assert_eq!(
+            parse_maturity_date(&format_maturity_date(1_835_395_200)),
+            Some(1_835_395_200)
+        );
+    }
+}
@@ -0,0 +1,424 @@
+use {
+    crate::{
+        error::YieldTokenizerError,
+        math::{exp, pt_price_from_ln_implied_rate, years_to_maturity, Rounding, ONE},
+        state::{PtPool, YieldTokenizerState},
+    },
+    borsh::{BorshDeserialize, BorshSchema, BorshSerialize},
+    solana_program::{program_error::ProgramError, pubkey::Pubkey},
+};
+
+/// Observations kept by each pool's oracle
+pub const ORACLE_CARDINALITY: usize = 96;
+
+/// Minimum seconds between observations, so the buffer covers at least
+/// `ORACLE_CARDINALITY * OBSERVATION_INTERVAL`, 96 minutes, however often the pool trades
+pub const OBSERVATION_INTERVAL: i64 = 60;
+
+#[derive(
+    Clone, Copy, Debug, Default, PartialEq, Eq, BorshDeserialize, BorshSerialize, BorshSchema,
+)]
+pub struct Observation {
+    /// Zero for slots that haven't been written yet
+    pub timestamp: i64,
+    pub ln_implied_rate_cumulative: i128,
+}
+
+/// Time-weighted record of a pool's `ln(1 + implied_rate)`. The rate only moves on trades, so
+/// the cumulative is exact at every update and the TWAP over any window is its difference divided
+/// by the window.
+#[derive(BorshDeserialize, BorshSerialize, BorshSchema)]
+pub struct RateOracle {
+    /// Integral of `ln_implied_rate` over time, in seconds, up to `last_update`
+    pub ln_implied_rate_cumulative: i128,
+    pub last_update: i64,
+    /// Slot the next observation is written to, the oldest one once the buffer has wrapped
+    pub index: u16,
+    /// Ring buffer of `ORACLE_CARDINALITY` observations
+    pub observations: Vec<Observation>,
+}
+
+impl Default for RateOracle {
+    fn default() -> Self {
+        Self {
+            ln_implied_rate_cumulative: 0,
+            last_update: 0,
+            index: 0,
+            observations: vec![Observation::default(); ORACLE_CARDINALITY],
+        }
+    }
+}
+
+impl RateOracle {
+    /// Starts the record at `now`, when the pool's first deposit sets its rate
+    pub fn initialize(&mut self, now: i64) {
+        *self = Self::default();
+        self.last_update = now;
+        self.push(now);
+    }
+
+    /// Accumulates `ln_implied_rate`, the rate in effect since the last update, up to `now`.
+    /// Called before every trade so a trade never affects the rate recorded for its own block.
+    pub fn update(&mut self, now: i64, ln_implied_rate: i128) -> Result<(), ProgramError> {
+        if now <= self.last_update {
+            return Ok(());
+        }
+        self.ln_implied_rate_cumulative = ln_implied_rate
+            .checked_mul((now - self.last_update) as i128)
+            .and_then(|accrued| self.ln_implied_rate_cumulative.checked_add(accrued))
+            .ok_or(ProgramError::ArithmeticOverflow)?;
+        self.last_update = now;
+
+        if now - self.newest().timestamp >= OBSERVATION_INTERVAL {
+            self.push(now);
+        }
+        Ok(())
+    }
+
+    /// Cumulative at `timestamp`, extrapolated from the last update at `ln_implied_rate` or
+    /// interpolated between the observations around it
+    pub fn cumulative_at(
+        &self,
+        timestamp: i64,
+        ln_implied_rate: i128,
+    ) -> Result<i128, ProgramError> {
+        if timestamp >= self.last_update {
+            return ln_implied_rate
+                .checked_mul((timestamp - self.last_update) as i128)
+                .and_then(|accrued| self.ln_implied_rate_cumulative.checked_add(accrued))
+                .ok_or(ProgramError::ArithmeticOverflow);
+        }
+
+        let head = Observation {
+            timestamp: self.last_update,
+            ln_implied_rate_cumulative: self.ln_implied_rate_cumulative,
+        };
+        let mut before: Option<Observation> = None;
+        for observation in self.chronological().chain(std::iter::once(head)) {
+            if observation.timestamp > timestamp {
+                let before = before.ok_or(YieldTokenizerError::InsufficientOracleHistory)?;
+                let elapsed = (timestamp - before.timestamp) as i128;
+                let span = (observation.timestamp - before.timestamp) as i128;
+                return Ok(before.ln_implied_rate_cumulative
+                    + (observation.ln_implied_rate_cumulative
+                        - before.ln_implied_rate_cumulative)
+                        * elapsed
+                        / span);
+            }
+            before = Some(observation);
+        }
+        Err(YieldTokenizerError::InsufficientOracleHistory.into())
+    }
+
+    fn push(&mut self, now: i64) {
+        let index = self.index as usize;
+        self.observations[index] = Observation {
+            timestamp: now,
+            ln_implied_rate_cumulative: self.ln_implied_rate_cumulative,
+        };
+        self.index = ((index + 1) % ORACLE_CARDINALITY) as u16;
+    }
+
+    fn newest(&self) -> Observation {
+        self.observations[(self.index as usize + ORACLE_CARDINALITY - 1) % ORACLE_CARDINALITY]
+    }
+
+    /// Written observations, oldest first
+    fn chronological(&self) -> impl Iterator<Item = Observation> + '_ {
+        let (newer, older) = self.observations.split_at(self.index as usize);
+        older
+            .iter()
+            .chain(newer)
+            .copied()
+            .filter(|observation| observation.timestamp != 0)
+    }
+}
+
+impl PtPool {
+    /// `ln(1 + implied_rate)` averaged over the `window` seconds before `now`
+    pub fn twap_ln_implied_rate(&self, now: i64, window: i64) -> Result<i128, ProgramError> {
+        if window <= 0 {
+            return Err(ProgramError::InvalidArgument);
+        }
+        if self.lp_supply == 0 {
+            return Err(YieldTokenizerError::InsufficientOracleHistory.into());
+        }
+        let end = self.oracle.cumulative_at(now, self.last_ln_implied_rate)?;
+        let start = self
+            .oracle
+            .cumulative_at(now - window, self.last_ln_implied_rate)?;
+        Ok((end - start) / window as i128)
+    }
+}
+
+/// Annually compounded implied rate of a pool averaged over the `window` seconds before `now`,
+/// read from the pool account's data
+pub fn get_twap_implied_rate(
+    pool_data: &[u8],
+    now: i64,
+    window: i64,
+) -> Result<i128, ProgramError> {
+    let pool = PtPool::unpack(pool_data)?;
+    Ok(exp(pool.twap_ln_implied_rate(now, window)?, Rounding::Down)? - ONE)
+}
+
+/// Price of PT, in the principal it redeems for, at the pool's TWAP implied rate over the
+/// `window` seconds before `now`. Reaches one at maturity. `program_id` is the tokenizer program
+/// both accounts belong to.
+pub fn get_twap_pt_price(
+    program_id: &Pubkey,
+    pool_data: &[u8],
+    yield_tokenizer_data: &[u8],
+    now: i64,
+    window: i64,
+) -> Result<i128, ProgramError> {
+    let pool = PtPool::unpack(pool_data)?;
+    let yield_tokenizer = YieldTokenizerState::unpack(yield_tokenizer_data)?;
+    if pool.yield_tokenizer
+        != crate::get_yield_tokenizer_address(
+            program_id,
+            &yield_tokenizer.lsu_mint,
+            yield_tokenizer.maturity_date,
+        )
+    {
+        return Err(YieldTokenizerError::InvalidYieldTokenizerAddress.into());
+    }
+
+    let years = years_to_maturity(now, yield_tokenizer.maturity_date, Rounding::Up)?;
+    pt_price_from_ln_implied_rate(
+        pool.twap_ln_implied_rate(now, window)?,
+        years,
+        Rounding::Down,
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const START: i64 = 1_792_281_600;
+    const RATE: i128 = 50_000_000_000_000_000; // ln(1.05127)
+
+    /// Oracle updated every `interval` seconds at a constant `RATE`, `updates` times
+    fn constant_rate_oracle(interval: i64, updates: i64) -> RateOracle {
+        let mut oracle = RateOracle::default();
+        oracle.initialize(START);
+        for i in 1..=updates {
+            oracle.update(START + i * interval, RATE).unwrap();
+        }
+        oracle
+    }
+
+    fn written(oracle: &RateOracle) -> Vec<Observation> {
+        oracle.chronological().collect()
+    }
+
+    #[test]
+    fn test_initialize() {
+        let oracle = constant_rate_oracle(OBSERVATION_INTERVAL, 0);
+        assert_eq!(
+            written(&oracle),
+            vec![Observation {
+                timestamp: START,
+                ln_implied_rate_cumulative: 0,
+            }]
+        );
+        assert_eq!(oracle.index, 1);
+        assert_eq!(oracle.observations.len(), ORACLE_CARDINALITY);
+    }
+
+    #[test]
+    fn test_observations_are_throttled() {
+        let mut oracle = constant_rate_oracle(OBSERVATION_INTERVAL, 0);
+
+        // Updates within the interval accumulate without writing an observation
+        for elapsed in [1, 10, OBSERVATION_INTERVAL - 1] {
+            oracle.update(START + elapsed, RATE).unwrap();
+            assert_eq!(written(&oracle).len(), 1);
+        }
+        assert_eq!(
+            oracle.ln_implied_rate_cumulative,
+            RATE * (OBSERVATION_INTERVAL - 1) as i128
+        );
+
+        oracle.update(START + OBSERVATION_INTERVAL, RATE).unwrap();
+        assert_eq!(written(&oracle).len(), 2);
+
+        // Updates at or before the last one are ignored
+        oracle
+            .update(START + OBSERVATION_INTERVAL, 2 * RATE)
+            .unwrap();
+        oracle.update(START, 2 * RATE).unwrap();
+        assert_eq!(oracle.last_update, START + OBSERVATION_INTERVAL);
+        assert_eq!(
+            oracle.ln_implied_rate_cumulative,
+            RATE * OBSERVATION_INTERVAL as i128
+        );
+    }
+
+    #[test]
+    fn test_ring_buffer_wraps() {
+        let updates = 2 * ORACLE_CARDINALITY as i64 + 5;
+        let oracle = constant_rate_oracle(OBSERVATION_INTERVAL, updates);
+
+        let observations = written(&oracle);
+        assert_eq!(observations.len(), ORACLE_CARDINALITY);
+        assert_eq!(
+            oracle.index as usize,
+            (updates as usize + 1) % ORACLE_CARDINALITY
+        );
+
+        // Oldest first, the newest is the last update
+        let oldest = updates - ORACLE_CARDINALITY as i64 + 1;
+        for (i, observation) in observations.iter().enumerate() {
+            let elapsed = (oldest + i as i64) * OBSERVATION_INTERVAL;
+            assert_eq!(observation.timestamp, START + elapsed);
+            assert_eq!(
+                observation.ln_implied_rate_cumulative,
+                RATE * elapsed as i128
+            );
+        }
+        assert_eq!(oracle.newest().timestamp, oracle.last_update);
+    }
+
+    #[test]
+    fn test_cumulative_at_observations() {
+        let oracle = constant_rate_oracle(OBSERVATION_INTERVAL, 10);
+
+        // Exactly at each observation, including the oldest
+        for observation in written(&oracle) {
+            assert_eq!(
+                oracle.cumulative_at(observation.timestamp, RATE).unwrap(),
+                observation.ln_implied_rate_cumulative
+            );
+        }
+
+        assert_eq!(
+            oracle.cumulative_at(START - 1, RATE),
+            Err(YieldTokenizerError::InsufficientOracleHistory.into())
+        );
+    }
+
+    #[test]
+    fn test_cumulative_at_interpolates() {
+        let mut oracle = RateOracle::default();
+        oracle.initialize(START);
+        oracle.update(START + 100, RATE).unwrap();
+        oracle.update(START + 400, 3 * RATE).unwrap();
+
+        // `RATE` for the first 100s then `3 * RATE` for the next 300s
+        assert_eq!(oracle.cumulative_at(START + 25, 0).unwrap(), RATE * 25);
+        assert_eq!(oracle.cumulative_at(START + 100, 0).unwrap(), RATE * 100);
+        assert_eq!(
+            oracle.cumulative_at(START + 250, 0).unwrap(),
+            RATE * 100 + 3 * RATE * 150
+        );
+        assert_eq!(oracle.cumulative_at(START + 400, 0).unwrap(), RATE * 1_000);
+
+        // Past the last update the current rate is extrapolated
+        assert_eq!(
+            oracle.cumulative_at(START + 410, 2 * RATE).unwrap(),
+            RATE * 1_000 + 2 * RATE * 10
+        );
+    }
+
+    #[test]
+    fn test_cumulative_at_between_update_and_observation() {
+        // The last update doesn't always write an observation, the cumulative at it still counts
+        let mut oracle = RateOracle::default();
+        oracle.initialize(START);
+        oracle.update(START + 90, RATE).unwrap();
+        oracle.update(START + 120, 2 * RATE).unwrap();
+        assert_eq!(written(&oracle).len(), 2);
+
+        assert_eq!(
+            oracle.cumulative_at(START + 100, 0).unwrap(),
+            RATE * 90 + 2 * RATE * 10
+        );
+    }
+
+    #[test]
+    fn test_history_after_wrap() {
+        let updates = ORACLE_CARDINALITY as i64 + 20;
+        let oracle = constant_rate_oracle(OBSERVATION_INTERVAL, updates);
+        let oldest = written(&oracle)[0].timestamp;
+
+        assert_eq!(
+            oracle.cumulative_at(oldest, RATE).unwrap(),
+            RATE * (oldest - START) as i128
+        );
+        assert_eq!(
+            oracle.cumulative_at(oldest + 30, RATE).unwrap(),
+            RATE * (oldest + 30 - START) as i128
+        );
+        // Overwritten observations are gone
+        assert_eq!(
+            oracle.cumulative_at(oldest - 1, RATE),
+            Err(YieldTokenizerError::InsufficientOracleHistory.into())
+        );
+        assert_eq!(
+            oracle.cumulative_at(START, RATE),
+            Err(YieldTokenizerError::InsufficientOracleHistory.into())
+        );
+    }
+
+    fn pool(oracle: RateOracle) -> PtPool {
+        PtPool {
+            last_ln_implied_rate: RATE,
+            pt_reserve: 1_000,
+            lsu_reserve: 1_000,
+            lp_supply: 1_000,
+            oracle,
+            ..PtPool::new(
+                Default::default(),
+                Default::default(),
+                Default::default(),
+                Default::default(),
+                50 * ONE,
+                ONE,
+                0,
+                255,
+            )
+        }
+    }
+
+    #[test]
+    fn test_twap_constant_rate() {
+        let oracle = constant_rate_oracle(OBSERVATION_INTERVAL, 30);
+        let pool = pool(oracle);
+        let now = START + 30 * OBSERVATION_INTERVAL + 45;
+
+        for window in [1, 45, OBSERVATION_INTERVAL, 17 * OBSERVATION_INTERVAL + 13] {
+            assert_eq!(pool.twap_ln_implied_rate(now, window).unwrap(), RATE);
+        }
+        assert_eq!(pool.twap_ln_implied_rate(now, now - START).unwrap(), RATE);
+        assert_eq!(
+            pool.twap_ln_implied_rate(now, now - START + 1),
+            Err(YieldTokenizerError::InsufficientOracleHistory.into())
+        );
+        assert_eq!(
+            pool.twap_ln_implied_rate(now, 0),
+            Err(ProgramError::InvalidArgument)
+        );
+    }
+
+    #[test]
+    fn test_twap_rate_change() {
+        // `RATE` for 600s, then `3 * RATE` since
+        let mut oracle = constant_rate_oracle(OBSERVATION_INTERVAL, 10);
+        oracle.update(START + 900, 3 * RATE).unwrap();
+        let mut pool = pool(oracle);
+        pool.last_ln_implied_rate = 3 * RATE;
+        let now = START + 900;
+
+        // Half the last 600s at each rate
+        assert_eq!(pool.twap_ln_implied_rate(now, 600).unwrap(), 2 * RATE);
+        assert_eq!(pool.twap_ln_implied_rate(now + 300, 600).unwrap(), 3 * RATE);
+
+        pool.lp_supply = 0;
+        assert_eq!(
+            pool.twap_ln_implied_rate(now, 600),
+            Err(YieldTokenizerError::InsufficientOracleHistory.into())
+        );
+    }
+}
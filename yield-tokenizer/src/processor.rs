@@ -1,40 +1,67 @@
 use {
     crate::{
+        accounts::{
+            AddLiquidityAccounts, AddLsuAccounts, ClaimYieldAccounts, CloseYieldTokenizerAccounts,
+            InitializeConfigAccounts, InitializePoolAccounts, InitializeYieldTokenizerAccounts,
+            MigrateStateAccounts, MigrateYieldTokenizerAccounts, RedeemAccounts,
+            RedeemFromPtAccounts, RemoveLiquidityAccounts, RemoveLsuAccounts, RolloverAccounts,
+            SetTokenizerPauseAccounts, SwapExactLsuForYtAccounts, SwapExactYtForLsuAccounts,
+            SwapPtAccounts, TokenizeYieldAccounts, TransferYtAccounts, UpdateConfigAccounts,
+            WithdrawFeesAccounts,
+        },
+        adapter::LsuAdapter,
+        amm::{
+            proportional_deposit, proportional_withdrawal, MAX_LN_FEE_RATE_ROOT, MAX_SCALAR_ROOT,
+        },
         error::YieldTokenizerError,
-        get_principal_token_address, get_yield_token_address, get_yield_tokenizer_address,
-        instruction::{Expiry, YieldTokenizerInstruction},
-        state::{YieldTokenizerState, LSD_LEN},
+        events::YieldTokenizerEvent,
+        find_pool_address, find_yield_position_address, find_yield_tokenizer_address,
+        get_config_address, get_yield_position_address,
+        instruction::YieldTokenizerInstruction,
+        math::ONE,
+        maturity::{format_maturity_date, format_maturity_label, is_valid_maturity_date},
+        state::{
+            config_len, pool_len, position_len, PauseState, ProtocolConfig, PtPool, SupportedLsu,
+            YieldPosition, YieldTokenizerState, HEADER_LEN, LEGACY_YIELD_TOKENIZER_LEN,
+            MAX_CLOSE_DUST, MAX_FEE_BPS, MAX_LSU_SYMBOL_LEN, MAX_SUPPORTED_LSUS,
+            PROTOCOL_CONFIG_DISCRIMINATOR, PROTOCOL_CONFIG_VERSION, PT_POOL_DISCRIMINATOR,
+            PT_POOL_VERSION, YIELD_POSITION_DISCRIMINATOR, YIELD_POSITION_VERSION,
+            YIELD_TOKENIZER_DISCRIMINATOR, YIELD_TOKENIZER_VERSION,
+        },
     },
-    borsh::{BorshDeserialize, BorshSerialize},
+    borsh::BorshSerialize,
     solana_program::{
-        account_info::{next_account_info, AccountInfo},
+        account_info::AccountInfo,
         borsh1::try_from_slice_unchecked,
         clock,
         entrypoint::ProgramResult,
         program::{invoke, invoke_signed},
         program_error::ProgramError,
+        program_pack::Pack,
         pubkey::Pubkey,
         system_instruction, system_program,
         sysvar::{rent, Sysvar},
     },
-    spl_associated_token_account::get_associated_token_address,
     spl_token,
+    spl_token_2022::{
+        extension::{metadata_pointer, ExtensionType, StateWithExtensions},
+        state::{Account, Mint},
+    },
+    spl_token_metadata_interface::state::{Field, TokenMetadata},
 };
 
-const YEAR_SEC: usize = 31_536_000;
-
-struct YieldTokenizer {}
+pub struct YieldTokenizerProcessor {}
 
-impl YieldTokenizer {
-    fn process(program_id: &Pubkey, accounts: &[AccountInfo], data: &[u8]) -> ProgramResult {
+impl YieldTokenizerProcessor {
+    pub fn process(program_id: &Pubkey, accounts: &[AccountInfo], data: &[u8]) -> ProgramResult {
         let instruction: YieldTokenizerInstruction = match try_from_slice_unchecked(data) {
             Ok(ix) => ix,
             Err(_) => return Err(ProgramError::InvalidInstructionData),
         };
 
         match instruction {
-            YieldTokenizerInstruction::InitializeYieldTokenizer { expiry } => {
-                Self::process_init_yield_tokenizer(program_id, accounts, expiry)
+            YieldTokenizerInstruction::InitializeYieldTokenizer { maturity_date } => {
+                Self::process_init_yield_tokenizer(program_id, accounts, maturity_date)
             }
             YieldTokenizerInstruction::TokenizeYield { amount } => {
                 Self::process_tokenize_yield(program_id, accounts, amount)
@@ -48,203 +75,265 @@ impl YieldTokenizer {
             YieldTokenizerInstruction::ClaimYield => {
                 Self::process_claim_yield(program_id, accounts)
             }
+            YieldTokenizerInstruction::TransferYt { amount } => {
+                Self::process_transfer_yt(program_id, accounts, amount)
+            }
+            YieldTokenizerInstruction::InitializeConfig => {
+                Self::process_init_config(program_id, accounts)
+            }
+            YieldTokenizerInstruction::AddLsu { adapter, symbol } => {
+                Self::process_add_lsu(program_id, accounts, adapter, symbol)
+            }
+            YieldTokenizerInstruction::RemoveLsu => Self::process_remove_lsu(program_id, accounts),
+            YieldTokenizerInstruction::SetAuthority { new_authority } => {
+                Self::process_set_authority(program_id, accounts, new_authority)
+            }
+            YieldTokenizerInstruction::SetFees {
+                yield_fee_bps,
+                redemption_fee_bps,
+            } => Self::process_set_fees(program_id, accounts, yield_fee_bps, redemption_fee_bps),
+            YieldTokenizerInstruction::WithdrawFees { amount } => {
+                Self::process_withdraw_fees(program_id, accounts, amount)
+            }
+            YieldTokenizerInstruction::SetPause { pause_state } => {
+                Self::process_set_pause(program_id, accounts, pause_state)
+            }
+            YieldTokenizerInstruction::SetTokenizerPause { pause_state } => {
+                Self::process_set_tokenizer_pause(program_id, accounts, pause_state)
+            }
+            YieldTokenizerInstruction::InitializePool {
+                scalar_root,
+                initial_anchor,
+                ln_fee_rate_root,
+            } => Self::process_init_pool(
+                program_id,
+                accounts,
+                scalar_root,
+                initial_anchor,
+                ln_fee_rate_root,
+            ),
+            YieldTokenizerInstruction::AddLiquidity {
+                pt_amount,
+                lsu_amount,
+                min_lp_out,
+            } => {
+                Self::process_add_liquidity(program_id, accounts, pt_amount, lsu_amount, min_lp_out)
+            }
+            YieldTokenizerInstruction::RemoveLiquidity {
+                lp_amount,
+                min_pt_out,
+                min_lsu_out,
+            } => Self::process_remove_liquidity(
+                program_id,
+                accounts,
+                lp_amount,
+                min_pt_out,
+                min_lsu_out,
+            ),
+            YieldTokenizerInstruction::SwapExactLsuForPt { lsu_in, min_pt_out } => {
+                Self::process_swap_exact_lsu_for_pt(program_id, accounts, lsu_in, min_pt_out)
+            }
+            YieldTokenizerInstruction::SwapExactPtForLsu { pt_in, min_lsu_out } => {
+                Self::process_swap_exact_pt_for_lsu(program_id, accounts, pt_in, min_lsu_out)
+            }
+            YieldTokenizerInstruction::SwapExactLsuForYt { lsu_in, min_yt_out } => {
+                Self::process_swap_exact_lsu_for_yt(program_id, accounts, lsu_in, min_yt_out)
+            }
+            YieldTokenizerInstruction::SwapExactYtForLsu { yt_in, min_lsu_out } => {
+                Self::process_swap_exact_yt_for_lsu(program_id, accounts, yt_in, min_lsu_out)
+            }
+            YieldTokenizerInstruction::Rollover { amount } => {
+                Self::process_rollover(program_id, accounts, amount)
+            }
+            YieldTokenizerInstruction::CloseYieldTokenizer => {
+                Self::process_close_yield_tokenizer(program_id, accounts)
+            }
+            YieldTokenizerInstruction::MigrateState => {
+                Self::process_migrate_state(program_id, accounts)
+            }
         }
     }
 
     fn process_init_yield_tokenizer(
         program_id: &Pubkey,
         accounts: &[AccountInfo],
-        expiry: Expiry,
+        maturity_date: i64,
     ) -> Result<(), ProgramError> {
-        let accounts_iter = &mut accounts.iter();
-
-        let authority = next_account_info(accounts_iter)?;
-        let yield_tokenizer = next_account_info(accounts_iter)?;
-        let lsu_mint = next_account_info(accounts_iter)?;
-        let pt_mint = next_account_info(accounts_iter)?;
-        let yt_mint = next_account_info(accounts_iter)?;
-        let lsu_vault = next_account_info(accounts_iter)?;
-        let pt_vault = next_account_info(accounts_iter)?;
-        let yt_vault = next_account_info(accounts_iter)?;
-        let token_program = next_account_info(accounts_iter)?;
-        let atoken_program = next_account_info(accounts_iter)?;
-        let system_program = next_account_info(accounts_iter)?;
+        let InitializeYieldTokenizerAccounts {
+            authority,
+            yield_tokenizer,
+            lsu_mint,
+            lsu_exchange_rate_account,
+            pt_mint,
+            yt_mint,
+            lsu_vault,
+            treasury,
+            token_program,
+            yield_token_program,
+            atoken_program,
+            system_program,
+            config_data,
+            supported_lsu,
+            pt_bump,
+            yt_bump,
+            treasury_bump,
+            ..
+        } = InitializeYieldTokenizerAccounts::try_from((program_id, accounts))?;
 
-        let rent = rent::Rent::get()?;
         let clock = clock::Clock::get()?;
 
-        //TODO: Change this to be the maturity date - 0000 UTC
-        let expiry = match expiry {
-            Expiry::TwelveMonths => clock.unix_timestamp + YEAR_SEC as i64,
-            Expiry::EighteenMonths => clock.unix_timestamp + (YEAR_SEC as f64 * 1.5) as i64,
-            Expiry::TwentyFourMonths => clock.unix_timestamp + (YEAR_SEC * 2) as i64,
-        };
-
-        // Safety Checks
-        if yield_tokenizer.key != &get_yield_tokenizer_address(lsu_mint.key, expiry) {
-            return Err(YieldTokenizerError::InvalidYieldTokenizerAddress.into());
-        }
-        if pt_mint.key != &get_principal_token_address(yield_tokenizer.key) {
-            return Err(YieldTokenizerError::InvalidPrincipalToken.into());
-        }
-        if yt_mint.key != &get_yield_token_address(yield_tokenizer.key) {
-            return Err(YieldTokenizerError::InvalidYieldToken.into());
-        }
-        if lsu_vault.key != &get_associated_token_address(yield_tokenizer.key, lsu_mint.key) {
-            return Err(YieldTokenizerError::LSUTokenAccountMismatch.into());
+        if !is_valid_maturity_date(maturity_date, clock.unix_timestamp) {
+            return Err(YieldTokenizerError::InvalidMaturityDate.into());
         }
-        if token_program.key != &spl_token::id() {
-            return Err(ProgramError::IncorrectProgramId);
-        }
-        if atoken_program.key != &spl_associated_token_account::id() {
-            return Err(ProgramError::IncorrectProgramId);
+
+        if config_data.pause_state != PauseState::Active {
+            return Err(YieldTokenizerError::Paused.into());
         }
-        if system_program.key != &system_program::id() {
-            return Err(ProgramError::IncorrectProgramId);
+        let (yield_tokenizer_addr, bump) =
+            find_yield_tokenizer_address(program_id, lsu_mint.key, maturity_date);
+        if yield_tokenizer.key != &yield_tokenizer_addr {
+            return Err(YieldTokenizerError::InvalidYieldTokenizerAddress.into());
         }
 
-        let (yield_tokenizer_addr, bump) = Pubkey::find_program_address(
+        // The vault may have been created ahead of init since anyone can open an ATA
+        invoke(
+            &spl_associated_token_account::instruction::create_associated_token_account_idempotent(
+                authority.key,
+                yield_tokenizer.key,
+                lsu_mint.key,
+                token_program.key,
+            ),
             &[
-                crate::LSD_SEED,
-                lsu_mint.key.as_ref(),
-                &expiry.to_le_bytes(),
+                authority.clone(),
+                lsu_vault.clone(),
+                yield_tokenizer.clone(),
+                lsu_mint.clone(),
+                system_program.clone(),
+                token_program.clone(),
+                atoken_program.clone(),
             ],
-            program_id,
-        );
-
-        if yield_tokenizer.owner != program_id {
-            let size = LSD_LEN;
-            let required_lamports = rent
-                .minimum_balance(size)
-                .max(1)
-                .saturating_sub(yield_tokenizer.lamports());
-
-            invoke(
-                &system_instruction::transfer(
-                    authority.key,
-                    &yield_tokenizer.key,
-                    required_lamports,
-                ),
-                &[
-                    authority.clone(),
-                    yield_tokenizer.clone(),
-                    system_program.clone(),
-                ],
-            )?;
-
-            invoke_signed(
-                &system_instruction::allocate(&yield_tokenizer.key, size as u64),
-                &[yield_tokenizer.clone(), system_program.clone()],
-                &[&[
-                    crate::LSD_SEED,
-                    lsu_mint.key.as_ref(),
-                    &expiry.to_le_bytes(),
-                    &[bump],
-                ]],
-            )?;
+        )?;
 
-            invoke_signed(
-                &system_instruction::assign(&yield_tokenizer.key, program_id),
-                &[yield_tokenizer.clone(), system_program.clone()],
-                &[&[
-                    crate::LSD_SEED,
-                    lsu_mint.key.as_ref(),
-                    &expiry.to_le_bytes(),
-                    &[bump],
-                ]],
+        // Treasury is a PDA token account so there's one per tokenizer, owned by the tokenizer
+        if treasury.owner != token_program.key {
+            Self::create_treasury(
+                authority,
+                treasury,
+                yield_tokenizer,
+                lsu_mint,
+                token_program,
+                system_program,
+                treasury_bump,
             )?;
         }
 
-        // Check if vault exists
-        if lsu_vault.owner != token_program.key {
-            invoke(
-                &spl_associated_token_account::instruction::create_associated_token_account(
-                    authority.key,
-                    yield_tokenizer.key,
-                    lsu_mint.key,
-                    token_program.key,
-                ),
-                &[
-                    authority.clone(),
-                    lsu_vault.clone(),
-                    yield_tokenizer.clone(),
-                    lsu_mint.clone(),
-                    system_program.clone(),
-                    token_program.clone(),
-                    atoken_program.clone(),
-                ],
-            )?;
-        }
+        // PT and YT mirror the LSU's decimals so amounts convert at the same scale
+        let decimals = spl_token::state::Mint::unpack(&lsu_mint.data.borrow())?.decimals;
 
         //Mint checks
-        if pt_mint.owner != token_program.key {
-            invoke(
-                &spl_token::instruction::initialize_mint(
-                    &spl_token::id(),
+        let maturity_date_bytes = maturity_date.to_le_bytes();
+        let signer_seeds: &[&[u8]] = &[
+            crate::LSD_SEED,
+            lsu_mint.key.as_ref(),
+            &maturity_date_bytes,
+            &[bump],
+        ];
+
+        // Closing a series leaves its mints behind, so re-initializing it reuses them
+        if pt_mint.owner != yield_token_program.key {
+            Self::create_yield_token_mint(
+                authority,
+                pt_mint,
+                yield_tokenizer,
+                yield_token_program,
+                system_program,
+                &[yield_tokenizer.key.as_ref(), crate::PT_SEED, &[pt_bump]],
+                signer_seeds,
+                None,
+                decimals,
+                &Self::yield_token_metadata(
+                    "PT",
                     pt_mint.key,
                     yield_tokenizer.key,
-                    None,
-                    9,
+                    &supported_lsu.symbol,
+                    maturity_date,
                 )?,
-                &[
-                    yield_tokenizer.clone(),
-                    pt_mint.clone(),
-                    token_program.clone(),
-                ],
             )?;
         }
 
-        if yt_mint.owner != token_program.key {
-            invoke(
-                &spl_token::instruction::initialize_mint(
-                    &spl_token::id(),
+        if yt_mint.owner != yield_token_program.key {
+            Self::create_yield_token_mint(
+                authority,
+                yt_mint,
+                yield_tokenizer,
+                yield_token_program,
+                system_program,
+                &[yield_tokenizer.key.as_ref(), crate::YT_SEED, &[yt_bump]],
+                signer_seeds,
+                Some(yield_tokenizer.key),
+                decimals,
+                &Self::yield_token_metadata(
+                    "YT",
                     yt_mint.key,
                     yield_tokenizer.key,
-                    None,
-                    9,
+                    &supported_lsu.symbol,
+                    maturity_date,
                 )?,
-                &[
-                    yield_tokenizer.clone(),
-                    yt_mint.clone(),
-                    token_program.clone(),
-                ],
             )?;
         }
 
-        // We also need to instantiate vaults for the PT and YT for burns
-        if pt_vault.owner != token_program.key {
-            invoke_signed(
-                &spl_associated_token_account::instruction::create_associated_token_account(
-                    authority.key,
-                    yield_tokenizer.key,
-                    pt_mint.key,
-                    token_program.key,
-                )?,
-                &[],
-            )
-        }
-
-        if yt_vault.owner != token_program.key {
-            invoke_signed(
-                &spl_associated_token_account::instruction::create_associated_token_account(
-                    authority.key,
-                    yield_tokenizer.key,
-                    yt_mint,
-                    token_program.key,
-                ),
-            )
-        }
+        let exchange_rate = supported_lsu.adapter.get_exchange_rate(
+            lsu_mint.key,
+            lsu_exchange_rate_account,
+            clock.epoch,
+            false,
+        )?;
 
         let yield_tokenizer_data = YieldTokenizerState {
+            discriminator: YIELD_TOKENIZER_DISCRIMINATOR,
+            version: YIELD_TOKENIZER_VERSION,
             pt: *pt_mint.key,
             yt: *yt_mint.key,
-            maturity_date: dummy_clock_expiry,
+            maturity_date,
             lsu_mint: *lsu_mint.key,
             lsu_vault: *lsu_vault.key,
+            adapter: supported_lsu.adapter,
+            lsu_exchange_rate_account: supported_lsu.exchange_rate_account,
+            initial_exchange_rate: exchange_rate,
+            last_exchange_rate: exchange_rate,
+            maturity_exchange_rate: 0,
+            yield_index: 0,
+            pause_state: PauseState::Active,
+            initializer: *authority.key,
+            bump,
+            pt_bump,
+            yt_bump,
+            treasury_bump,
         };
 
+        // Sized from the state itself, none of its fields vary in length. The accounts check
+        // has already refused a tokenizer that exists, so it's always created here.
+        Self::create_pda_account(
+            program_id,
+            authority,
+            yield_tokenizer,
+            system_program,
+            borsh::object_length(&yield_tokenizer_data)?,
+            signer_seeds,
+        )?;
+
         yield_tokenizer_data.serialize(&mut &mut yield_tokenizer.data.borrow_mut()[..])?;
 
+        YieldTokenizerEvent::InitializeYieldTokenizer {
+            yield_tokenizer: *yield_tokenizer.key,
+            initializer: *authority.key,
+            lsu_mint: *lsu_mint.key,
+            maturity_date,
+            exchange_rate,
+            timestamp: clock.unix_timestamp,
+        }
+        .emit()?;
+
         Ok(())
     }
 
@@ -253,79 +342,60 @@ impl YieldTokenizer {
         accounts: &[AccountInfo],
         amount: u64,
     ) -> Result<(), ProgramError> {
-        let accounts_iter = &mut accounts.iter();
-
-        let buyer = next_account_info(accounts_iter)?;
-        let yield_tokenizer = next_account_info(accounts_iter)?;
-        let lsu_mint = next_account_info(accounts_iter)?;
-        let pt_mint = next_account_info(accounts_iter)?;
-        let yt_mint = next_account_info(accounts_iter)?;
-        let lsu_vault = next_account_info(accounts_iter)?;
-        let buyer_lsu_ata = next_account_info(accounts_iter)?;
-        let buyer_pt_ata = next_account_info(accounts_iter)?;
-        let buyer_yt_ata = next_account_info(accounts_iter)?;
-        let token_program = next_account_info(accounts_iter)?;
-        let atoken_program = next_account_info(accounts_iter)?;
-        let system_program = next_account_info(accounts_iter)?;
+        let TokenizeYieldAccounts {
+            buyer,
+            yield_tokenizer,
+            lsu_exchange_rate_account,
+            pt_mint,
+            yt_mint,
+            lsu_vault,
+            buyer_lsu_ata,
+            buyer_pt_ata,
+            buyer_yt_ata,
+            buyer_position_account,
+            token_program,
+            yield_token_program,
+            system_program,
+            config_data,
+            mut yield_tokenizer_data,
+            ..
+        } = TokenizeYieldAccounts::try_from((program_id, accounts))?;
 
         let clock = clock::Clock::get()?;
-        let now = clock.unix_timestamp as i64;
-
-        let yield_tokenizer_data =
-            YieldTokenizerState::try_from_slice(&yield_tokenizer.data.borrow())?;
-
-        // Safety checks
-        if program_id != &crate::id() {
-            return Err(ProgramError::IncorrectProgramId);
-        }
-        if yield_tokenizer.key
-            != &get_yield_tokenizer_address(lsu_mint.key, yield_tokenizer_data.maturity_date)
-            || lsu_vault.key != &yield_tokenizer_data.lsu_vault
-        {
-            return Err(YieldTokenizerError::InvalidYieldTokenizerAddress.into());
-        }
-        if lsu_mint.key != &yield_tokenizer_data.lsu_mint {
-            return Err(YieldTokenizerError::InvalidLSU.into());
-        }
-        if lsu_vault.key != &get_associated_token_address(yield_tokenizer.key, lsu_mint.key) {
-            return Err(YieldTokenizerError::InvalidVault.into());
-        }
-        if pt_mint.key != &yield_tokenizer_data.pt {
-            return Err(YieldTokenizerError::InvalidPrincipalToken.into());
-        }
-        if yt_mint.key != &yield_tokenizer_data.yt {
-            return Err(YieldTokenizerError::InvalidYieldToken.into());
-        }
-        if buyer_lsu_ata.key != &get_associated_token_address(buyer.key, lsu_mint.key) {
-            return Err(YieldTokenizerError::LSUTokenAccountMismatch.into());
-        }
-        if buyer_pt_ata.key != &get_associated_token_address(buyer.key, pt_mint.key) {
-            return Err(YieldTokenizerError::InvalidPrincipalToken.into());
-        }
-        if buyer_yt_ata.key != &get_associated_token_address(buyer.key, yt_mint.key) {
-            return Err(YieldTokenizerError::InvalidYieldToken.into());
-        }
-        if token_program.key != &spl_token::id() {
-            return Err(ProgramError::IncorrectProgramId);
-        }
-        if atoken_program.key != &spl_associated_token_account::id() {
-            return Err(ProgramError::IncorrectProgramId);
-        }
-        if system_program.key != &system_program::id() {
-            return Err(ProgramError::IncorrectProgramId);
-        }
+        let now = clock.unix_timestamp;
+        let bump = yield_tokenizer_data.bump;
 
         //Check if expiry has elapsed
         if now > yield_tokenizer_data.maturity_date {
             return Err(YieldTokenizerError::Expired.into());
         }
 
-        // Get value of PT token at current timestamp
-        // Get fixed yield of PT at current timestamp
+        Self::check_pause_state(&config_data, &yield_tokenizer_data, false)?;
+
+        let exchange_rate = Self::get_exchange_rate(
+            &yield_tokenizer_data,
+            lsu_exchange_rate_account,
+            &clock,
+            false,
+        )?;
+        yield_tokenizer_data.update_exchange_rate(exchange_rate, now);
+
+        let mut buyer_position = Self::load_or_create_position(
+            program_id,
+            buyer,
+            buyer.key,
+            yield_tokenizer,
+            buyer_position_account,
+            system_program,
+            yield_tokenizer_data.yield_index,
+        )?;
+        buyer_position.settle(
+            Self::token_balance(buyer_yt_ata)?,
+            yield_tokenizer_data.yield_index,
+        )?;
 
-        // Get value of YT token at current timestamp
-        // Get implied yield of YT at current timestamp
-        // Get average yield of underlying at current timestamp
+        // PT and YT are denominated in the SOL value of one LSU at initialization
+        let principal = yield_tokenizer_data.lsu_to_principal(amount)?;
 
         // Deposit LSU amount into LSU token vault
         invoke(
@@ -345,55 +415,81 @@ impl YieldTokenizer {
             ],
         )?;
 
+        let maturity_date = yield_tokenizer_data.maturity_date.to_le_bytes();
+        let signer_seeds: &[&[u8]] = &[
+            crate::LSD_SEED,
+            yield_tokenizer_data.lsu_mint.as_ref(),
+            &maturity_date,
+            &[bump],
+        ];
+
         // Mint corresponding PT
         invoke_signed(
-            &spl_token::instruction::mint_to(
-                token_program.key,
+            &spl_token_2022::instruction::mint_to(
+                yield_token_program.key,
                 pt_mint.key,
                 buyer_pt_ata.key,
-                buyer.key,
-                &[yield_tokenizer.key],
-                amount,
+                yield_tokenizer.key,
+                &[],
+                principal,
             )?,
             &[
-                buyer.clone(),
                 pt_mint.clone(),
                 buyer_pt_ata.clone(),
-                token_program.clone(),
+                yield_tokenizer.clone(),
+                yield_token_program.clone(),
             ],
-            &[&[
-                crate::LSD_SEED,
-                yield_tokenizer_data.lsu_mint.as_ref(),
-                &yield_tokenizer_data.maturity_date.to_le_bytes(),
-            ]],
+            &[signer_seeds],
         )?;
 
         // Mint corresponding YT
+        Self::thaw_yt_account(
+            buyer_yt_ata,
+            yt_mint,
+            yield_tokenizer,
+            yield_token_program,
+            signer_seeds,
+        )?;
         invoke_signed(
-            &spl_token::instruction::mint_to(
-                token_program.key,
+            &spl_token_2022::instruction::mint_to(
+                yield_token_program.key,
                 yt_mint.key,
                 buyer_yt_ata.key,
-                buyer.key,
-                &[yield_tokenizer.key],
-                amount,
+                yield_tokenizer.key,
+                &[],
+                principal,
             )?,
             &[
                 yt_mint.clone(),
                 buyer_yt_ata.clone(),
-                buyer.clone(),
                 yield_tokenizer.clone(),
+                yield_token_program.clone(),
             ],
-            &[&[
-                crate::LSD_SEED,
-                yield_tokenizer_data.lsu_mint.as_ref(),
-                &yield_tokenizer_data.maturity_date.to_le_bytes(),
-            ]],
+            &[signer_seeds],
         )?;
+        Self::freeze_yt_account(
+            buyer_yt_ata,
+            yt_mint,
+            yield_tokenizer,
+            yield_token_program,
+            signer_seeds,
+        )?;
+
+        buyer_position.serialize(&mut &mut buyer_position_account.data.borrow_mut()[..])?;
 
         // Update yield tokenizer state
         yield_tokenizer_data.serialize(&mut &mut yield_tokenizer.data.borrow_mut()[..])?;
 
+        YieldTokenizerEvent::TokenizeYield {
+            yield_tokenizer: *yield_tokenizer.key,
+            user: *buyer.key,
+            lsu_amount: amount,
+            principal,
+            exchange_rate: yield_tokenizer_data.last_exchange_rate,
+            timestamp: now,
+        }
+        .emit()?;
+
         Ok(())
     }
 
@@ -402,129 +498,166 @@ impl YieldTokenizer {
         accounts: &[AccountInfo],
         amount: u64,
     ) -> Result<(), ProgramError> {
-        let accounts_iter = &mut accounts.iter();
-
-        let redeemer = next_account_info(accounts_iter)?;
-        let yield_tokenizer = next_account_info(accounts_iter)?;
-        let lsu_mint = next_account_info(accounts_iter)?;
-        let pt_mint = next_account_info(accounts_iter)?;
-        let yt_mint = next_account_info(accounts_iter)?;
-        let lsu_vault = next_account_info(accounts_iter)?;
-        let pt_vault = next_account_info(accounts_iter)?;
-        let yt_vault = next_account_info(accounts_iter)?;
-        let redeemer_lsu_ata = next_account_info(accounts_iter)?;
-        let redeemer_pt_ata = next_account_info(accounts_iter)?;
-        let redeemer_yt_ata = next_account_info(accounts_iter)?;
-        let token_program = next_account_info(accounts_iter)?;
-        let atoken_program = next_account_info(accounts_iter)?;
-        let system_program = next_account_info(accounts_iter)?;
+        let RedeemAccounts {
+            redeemer,
+            yield_tokenizer,
+            lsu_exchange_rate_account,
+            pt_mint,
+            yt_mint,
+            lsu_vault,
+            treasury,
+            redeemer_lsu_ata,
+            redeemer_pt_ata,
+            redeemer_yt_ata,
+            redeemer_position_account,
+            token_program,
+            yield_token_program,
+            system_program,
+            config_data,
+            mut yield_tokenizer_data,
+            ..
+        } = RedeemAccounts::try_from((program_id, accounts))?;
 
         let clock = clock::Clock::get()?;
+        let bump = yield_tokenizer_data.bump;
 
-        let yield_tokenizer_data =
-            YieldTokenizerState::try_from_slice(&yield_tokenizer.data.borrow())?;
-
-        if program_id != &crate::id() {
-            return Err(ProgramError::IncorrectProgramId);
-        }
-        if yield_tokenizer.key
-            != &get_yield_tokenizer_address(
-                &yield_tokenizer_data.lsu_mint,
-                yield_tokenizer_data.maturity_date,
-            )
-            || lsu_vault.key != &yield_tokenizer_data.lsu_vault
+        // The adapter isn't trusted in emergency mode, exits settle at the last observed rate
+        let exchange_rate = if Self::check_pause_state(&config_data, &yield_tokenizer_data, true)?
+            == PauseState::Emergency
         {
-            return Err(YieldTokenizerError::InvalidYieldTokenizerAddress.into());
-        }
-        if lsu_mint.key != &yield_tokenizer_data.lsu_mint {
-            return Err(YieldTokenizerError::InvalidLSU.into());
-        }
-        if lsu_vault.key != &get_associated_token_address(yield_tokenizer.key, lsu_mint.key) {
-            return Err(YieldTokenizerError::InvalidVault.into());
-        }
-        if pt_mint.key != &yield_tokenizer_data.pt {
-            return Err(YieldTokenizerError::InvalidPrincipalToken.into());
-        }
-        if yt_mint.key != &yield_tokenizer_data.yt {
-            return Err(YieldTokenizerError::InvalidYieldToken.into());
-        }
-        if redeemer_lsu_ata.key != &get_associated_token_address(redeemer.key, lsu_mint.key) {
-            return Err(YieldTokenizerError::LSUTokenAccountMismatch.into());
-        }
-        if redeemer_pt_ata.key != &get_associated_token_address(redeemer.key, pt_mint.key) {
-            return Err(YieldTokenizerError::InvalidPrincipalToken.into());
-        }
-        if redeemer_yt_ata.key != &get_associated_token_address(redeemer.key, yt_mint.key) {
-            return Err(YieldTokenizerError::InvalidYieldToken.into());
-        }
-        if token_program.key != &spl_token::id() {
-            return Err(ProgramError::IncorrectProgramId);
-        }
-        if atoken_program.key != &spl_associated_token_account::id() {
-            return Err(ProgramError::IncorrectProgramId);
-        }
-        if system_program.key != &system_program::id() {
-            return Err(ProgramError::IncorrectProgramId);
-        }
+            yield_tokenizer_data.last_exchange_rate
+        } else {
+            Self::get_exchange_rate(
+                &yield_tokenizer_data,
+                lsu_exchange_rate_account,
+                &clock,
+                true,
+            )?
+        };
+        yield_tokenizer_data.update_exchange_rate(exchange_rate, clock.unix_timestamp);
+
+        // Settle the redeemer's yield before their YT balance changes
+        let mut redeemer_position = Self::load_or_create_position(
+            program_id,
+            redeemer,
+            redeemer.key,
+            yield_tokenizer,
+            redeemer_position_account,
+            system_program,
+            yield_tokenizer_data.yield_index,
+        )?;
+        redeemer_position.settle(
+            Self::token_balance(redeemer_yt_ata)?,
+            yield_tokenizer_data.yield_index,
+        )?;
 
-        // Transfer PT
+        // The pair is worth its principal, paid out together with any accrued yield net of the
+        // protocol fee
+        let yield_fee = config_data.yield_fee(redeemer_position.accrued_yield);
+        let lsu_amount = yield_tokenizer_data
+            .principal_to_lsu(amount)?
+            .checked_add(redeemer_position.accrued_yield - yield_fee)
+            .ok_or(ProgramError::ArithmeticOverflow)?;
+        redeemer_position.accrued_yield = 0;
+
+        let maturity_date = yield_tokenizer_data.maturity_date.to_le_bytes();
+        let signer_seeds: &[&[u8]] = &[
+            crate::LSD_SEED,
+            yield_tokenizer_data.lsu_mint.as_ref(),
+            &maturity_date,
+            &[bump],
+        ];
+
+        // Burn PT
         invoke(
-            &spl_token::instruction::transfer(
-                token_program.key,
+            &spl_token_2022::instruction::burn(
+                yield_token_program.key,
                 redeemer_pt_ata.key,
-                pt_vault.key,
+                pt_mint.key,
                 redeemer.key,
                 &[],
                 amount,
             )?,
-            &[redeemer_yt_ata.clone(), pt_vault.clone(), redeemer.clone()],
+            &[
+                redeemer_pt_ata.clone(),
+                pt_mint.clone(),
+                redeemer.clone(),
+                yield_token_program.clone(),
+            ],
         )?;
 
-        // Transfer YT
+        // Burn YT
+        Self::thaw_yt_account(
+            redeemer_yt_ata,
+            yt_mint,
+            yield_tokenizer,
+            yield_token_program,
+            signer_seeds,
+        )?;
         invoke(
-            &spl_token::instruction::transfer(
-                token_program.key,
+            &spl_token_2022::instruction::burn(
+                yield_token_program.key,
                 redeemer_yt_ata.key,
-                yt_vault.key,
+                yt_mint.key,
                 redeemer.key,
                 &[],
                 amount,
             )?,
-            &[redeemer_yt_ata.clone(), yt_vault.clone(), redeemer.clone()],
+            &[
+                redeemer_yt_ata.clone(),
+                yt_mint.clone(),
+                redeemer.clone(),
+                yield_token_program.clone(),
+            ],
+        )?;
+        Self::freeze_yt_account(
+            redeemer_yt_ata,
+            yt_mint,
+            yield_tokenizer,
+            yield_token_program,
+            signer_seeds,
         )?;
 
-        // Burn PT
-        invoke_signed(
-            &spl_token::instruction::burn(
-                token_program.key,
-                pt_vault.key,
-                pt_mint.key,
-                &[yield_tokenizer.key],
-
-                
-                
-            )
-        )
-
-        // Burn YT
+        // Transfer LSU
         invoke_signed(
-            &spl_token::instruction::burn(
+            &spl_token::instruction::transfer(
                 token_program.key,
-
-
-
-                
+                lsu_vault.key,
+                redeemer_lsu_ata.key,
+                yield_tokenizer.key,
+                &[],
+                lsu_amount,
             )?,
-            &[&[
-                
-            ]],
+            &[
+                lsu_vault.clone(),
+                redeemer_lsu_ata.clone(),
+                yield_tokenizer.clone(),
+                token_program.clone(),
+            ],
+            &[signer_seeds],
+        )?;
+        Self::collect_fee(
+            lsu_vault,
+            treasury,
+            yield_tokenizer,
+            token_program,
+            signer_seeds,
+            yield_fee,
         )?;
-        // invoke_signed()
-
-        // Transfer LSU
-        // invoke_signed()
 
+        redeemer_position.serialize(&mut &mut redeemer_position_account.data.borrow_mut()[..])?;
         yield_tokenizer_data.serialize(&mut &mut yield_tokenizer.data.borrow_mut()[..])?;
+        YieldTokenizerEvent::Redeem {
+            yield_tokenizer: *yield_tokenizer.key,
+            user: *redeemer.key,
+            principal: amount,
+            lsu_amount,
+            fee: yield_fee,
+            exchange_rate: yield_tokenizer_data.last_exchange_rate,
+            timestamp: clock.unix_timestamp,
+        }
+        .emit()?;
+
         Ok(())
     }
 
@@ -533,98 +666,75 @@ impl YieldTokenizer {
         accounts: &[AccountInfo],
         amount: u64,
     ) -> Result<(), ProgramError> {
-        let accounts_iter = &mut accounts.iter();
-
-        let redeemer = next_account_info(accounts_iter)?;
-        let yield_tokenizer = next_account_info(accounts_iter)?;
-        let lsu_mint = next_account_info(accounts_iter)?;
-        let pt_mint = next_account_info(accounts_iter)?;
-        let lsu_vault = next_account_info(accounts_iter)?;
-        let pt_vault = next_account_info(accounts_iter)?;
-        let redeemer_lsu_ata = next_account_info(accounts_iter)?;
-        let redeemer_pt_ata = next_account_info(accounts_iter)?;
-        let token_program = next_account_info(accounts_iter)?;
-        let atoken_program = next_account_info(accounts_iter)?;
-        let system_program = next_account_info(accounts_iter)?;
+        let RedeemFromPtAccounts {
+            redeemer,
+            yield_tokenizer,
+            lsu_exchange_rate_account,
+            pt_mint,
+            lsu_vault,
+            treasury,
+            redeemer_lsu_ata,
+            redeemer_pt_ata,
+            token_program,
+            yield_token_program,
+            config_data,
+            mut yield_tokenizer_data,
+            ..
+        } = RedeemFromPtAccounts::try_from((program_id, accounts))?;
 
         let clock = clock::Clock::get()?;
-
-        let yield_tokenizer_data =
-            YieldTokenizerState::try_from_slice(&yield_tokenizer.data.borrow())?;
-
-        if program_id != &crate::id() {
-            return Err(ProgramError::IncorrectProgramId);
-        }
-        if yield_tokenizer.key
-            != &get_yield_tokenizer_address(lsu_mint.key, yield_tokenizer_data.maturity_date)
-            || lsu_vault.key != &yield_tokenizer_data.lsu_vault
-        {
-            return Err(YieldTokenizerError::InvalidYieldTokenizerAddress.into());
-        }
-        if lsu_mint.key != &yield_tokenizer_data.lsu_mint {
-            return Err(YieldTokenizerError::InvalidLSU.into());
-        }
-        if lsu_vault.key != &get_associated_token_address(yield_tokenizer.key, lsu_mint.key) {
-            return Err(YieldTokenizerError::InvalidVault.into());
-        }
-        if pt_mint.key != &yield_tokenizer_data.pt {
-            return Err(YieldTokenizerError::InvalidPrincipalToken.into());
-        }
-        if redeemer_lsu_ata.key != &get_associated_token_address(redeemer.key, lsu_mint.key) {
-            return Err(YieldTokenizerError::LSUTokenAccountMismatch.into());
-        }
-        if redeemer_pt_ata.key != &get_associated_token_address(redeemer.key, pt_mint.key) {
-            return Err(YieldTokenizerError::InvalidPrincipalToken.into());
-        }
-        if token_program.key != &spl_token::id() {
-            return Err(ProgramError::IncorrectProgramId);
-        }
-        if atoken_program.key != &spl_associated_token_account::id() {
-            return Err(ProgramError::IncorrectProgramId);
-        }
-        if system_program.key != &system_program::id() {
-            return Err(ProgramError::IncorrectProgramId);
-        }
+        let bump = yield_tokenizer_data.bump;
         // Check if expiry has elapsed
         if clock.unix_timestamp < yield_tokenizer_data.maturity_date {
             return Err(YieldTokenizerError::Immature.into());
         }
 
-        // Send PT to program
+        // Freezes the maturity rate on the first redemption after maturity
+        // The adapter isn't trusted in emergency mode, exits settle at the last observed rate
+        let exchange_rate = if Self::check_pause_state(&config_data, &yield_tokenizer_data, true)?
+            == PauseState::Emergency
+        {
+            yield_tokenizer_data.last_exchange_rate
+        } else {
+            Self::get_exchange_rate(
+                &yield_tokenizer_data,
+                lsu_exchange_rate_account,
+                &clock,
+                true,
+            )?
+        };
+        yield_tokenizer_data.update_exchange_rate(exchange_rate, clock.unix_timestamp);
+
+        // Burn PT
         invoke(
-            &spl_token::instruction::transfer(
-                token_program.key,
+            &spl_token_2022::instruction::burn(
+                yield_token_program.key,
                 redeemer_pt_ata.key,
-                pt_vault.key,
-                yield_tokenizer.key,
+                pt_mint.key,
+                redeemer.key,
                 &[],
                 amount,
             )?,
             &[
                 redeemer_pt_ata.clone(),
-                pt_vault.clone(),
+                pt_mint.clone(),
                 redeemer.clone(),
-                token_program.clone(),
+                yield_token_program.clone(),
             ],
         )?;
 
-        // Program burns PT
-        invoke_signed(
-            &spl_token::instruction::burn(
-                token_program.key,
-                pt_vault.key,
-                pt_mint.key,
-                yield_tokenizer.key,
-                &[yield_tokenizer.key],
-                amount,
-            )?,
-            &[pt_vault.clone(), pt_mint.clone(), yield_tokenizer.clone()],
-            &[&[
-                crate::LSD_SEED,
-                yield_tokenizer_data.lsu_mint.as_ref(),
-                &yield_tokenizer_data.maturity_date.to_le_bytes(),
-            ]],
-        )?;
+        // PT is worth its SOL principal at the maturity rate, `amount * initial / maturity` LSU.
+        // The rest of the LSU backing it is yield owed to YT holders.
+        let lsu_amount = yield_tokenizer_data.principal_to_lsu(amount)?;
+        let redemption_fee = config_data.redemption_fee(lsu_amount);
+
+        let maturity_date = yield_tokenizer_data.maturity_date.to_le_bytes();
+        let signer_seeds: &[&[u8]] = &[
+            crate::LSD_SEED,
+            yield_tokenizer_data.lsu_mint.as_ref(),
+            &maturity_date,
+            &[bump],
+        ];
 
         // Program sends LSU to redeemer
         invoke_signed(
@@ -633,8 +743,8 @@ impl YieldTokenizer {
                 lsu_vault.key,
                 redeemer_lsu_ata.key,
                 yield_tokenizer.key,
-                &[yield_tokenizer.key],
-                amount,
+                &[],
+                lsu_amount - redemption_fee,
             )?,
             &[
                 lsu_vault.clone(),
@@ -642,16 +752,31 @@ impl YieldTokenizer {
                 yield_tokenizer.clone(),
                 token_program.clone(),
             ],
-            &[&[
-                crate::LSD_SEED,
-                yield_tokenizer_data.lsu_mint.as_ref(),
-                &yield_tokenizer_data.maturity_date.to_le_bytes(),
-            ]],
+            &[signer_seeds],
+        )?;
+        Self::collect_fee(
+            lsu_vault,
+            treasury,
+            yield_tokenizer,
+            token_program,
+            signer_seeds,
+            redemption_fee,
         )?;
 
         // Update program state
         yield_tokenizer_data.serialize(&mut &mut yield_tokenizer.data.borrow_mut()[..])?;
 
+        YieldTokenizerEvent::RedeemFromPt {
+            yield_tokenizer: *yield_tokenizer.key,
+            user: *redeemer.key,
+            principal: amount,
+            lsu_amount: lsu_amount - redemption_fee,
+            fee: redemption_fee,
+            exchange_rate: yield_tokenizer_data.last_exchange_rate,
+            timestamp: clock.unix_timestamp,
+        }
+        .emit()?;
+
         Ok(())
     }
 
@@ -659,129 +784,2435 @@ impl YieldTokenizer {
         program_id: &Pubkey,
         accounts: &[AccountInfo],
     ) -> Result<(), ProgramError> {
-        let accounts_iter = &mut accounts.iter();
-
-        let claimer = next_account_info(accounts_iter)?;
-        let yield_tokenizer = next_account_info(accounts_iter)?;
-        let lsu_mint = next_account_info(accounts_iter)?;
-        let yt_mint = next_account_info(accounts_iter)?;
-        let lsu_vault = next_account_info(accounts_iter)?;
-        let yt_vault = next_account_info(accounts_iter)?;
-        let claimer_lsu_ata = next_account_info(accounts_iter)?;
-        let claimer_yt_ata = next_account_info(accounts_iter)?;
-        let token_program = next_account_info(accounts_iter)?;
-        let atoken_program = next_account_info(accounts_iter)?;
-        let system_program = next_account_info(accounts_iter)?;
+        let ClaimYieldAccounts {
+            claimer,
+            yield_tokenizer,
+            lsu_exchange_rate_account,
+            lsu_vault,
+            treasury,
+            claimer_lsu_ata,
+            claimer_yt_ata,
+            claimer_position_account,
+            token_program,
+            system_program,
+            config_data,
+            mut yield_tokenizer_data,
+            ..
+        } = ClaimYieldAccounts::try_from((program_id, accounts))?;
 
         let clock = clock::Clock::get()?;
+        let bump = yield_tokenizer_data.bump;
 
-        let yield_tokenizer_data =
-            YieldTokenizerState::try_from_slice(&yield_tokenizer.data.borrow())?;
+        // Yield stops accruing once the maturity rate is frozen but stays claimable
+        Self::check_pause_state(&config_data, &yield_tokenizer_data, false)?;
 
-        if program_id != &crate::id() {
-            return Err(ProgramError::IncorrectProgramId);
-        }
-        if yield_tokenizer.key
-            != &get_yield_tokenizer_address(lsu_mint.key, yield_tokenizer_data.maturity_date)
-            || lsu_vault.key != &yield_tokenizer_data.lsu_vault
-        {
-            return Err(YieldTokenizerError::InvalidYieldTokenizerAddress.into());
-        }
-        if lsu_mint.key != &yield_tokenizer_data.lsu_mint {
-            return Err(YieldTokenizerError::InvalidLSU.into());
-        }
-        if lsu_vault.key != &get_associated_token_address(yield_tokenizer.key, lsu_mint.key) {
-            return Err(YieldTokenizerError::InvalidVault.into());
-        }
-        if yt_mint.key != &yield_tokenizer_data.yt {
-            return Err(YieldTokenizerError::InvalidYieldToken.into());
+        let exchange_rate = Self::get_exchange_rate(
+            &yield_tokenizer_data,
+            lsu_exchange_rate_account,
+            &clock,
+            false,
+        )?;
+        yield_tokenizer_data.update_exchange_rate(exchange_rate, clock.unix_timestamp);
+
+        // Get accrued yield
+        let mut claimer_position = Self::load_or_create_position(
+            program_id,
+            claimer,
+            claimer.key,
+            yield_tokenizer,
+            claimer_position_account,
+            system_program,
+            yield_tokenizer_data.yield_index,
+        )?;
+        claimer_position.settle(
+            Self::token_balance(claimer_yt_ata)?,
+            yield_tokenizer_data.yield_index,
+        )?;
+        let yield_owed = claimer_position.accrued_yield;
+        let yield_fee = config_data.yield_fee(yield_owed);
+        claimer_position.accrued_yield = 0;
+
+        let maturity_date = yield_tokenizer_data.maturity_date.to_le_bytes();
+        let signer_seeds: &[&[u8]] = &[
+            crate::LSD_SEED,
+            yield_tokenizer_data.lsu_mint.as_ref(),
+            &maturity_date,
+            &[bump],
+        ];
+
+        // Program sends LSU to claimer, YT is kept by the claimer
+        if yield_owed > yield_fee {
+            invoke_signed(
+                &spl_token::instruction::transfer(
+                    token_program.key,
+                    lsu_vault.key,
+                    claimer_lsu_ata.key,
+                    yield_tokenizer.key,
+                    &[],
+                    yield_owed - yield_fee,
+                )?,
+                &[
+                    lsu_vault.clone(),
+                    claimer_lsu_ata.clone(),
+                    yield_tokenizer.clone(),
+                    token_program.clone(),
+                ],
+                &[signer_seeds],
+            )?;
         }
-        if claimer_lsu_ata.key != &get_associated_token_address(claimer.key, lsu_mint.key) {
-            return Err(YieldTokenizerError::LSUTokenAccountMismatch.into());
+        Self::collect_fee(
+            lsu_vault,
+            treasury,
+            yield_tokenizer,
+            token_program,
+            signer_seeds,
+            yield_fee,
+        )?;
+
+        claimer_position.serialize(&mut &mut claimer_position_account.data.borrow_mut()[..])?;
+
+        // Update program state
+        yield_tokenizer_data.serialize(&mut &mut yield_tokenizer.data.borrow_mut()[..])?;
+
+        YieldTokenizerEvent::ClaimYield {
+            yield_tokenizer: *yield_tokenizer.key,
+            user: *claimer.key,
+            lsu_amount: yield_owed - yield_fee,
+            fee: yield_fee,
+            exchange_rate: yield_tokenizer_data.last_exchange_rate,
+            timestamp: clock.unix_timestamp,
         }
-        if claimer_yt_ata.key != &get_associated_token_address(claimer.key, yt_mint.key) {
-            return Err(YieldTokenizerError::InvalidYieldToken.into());
+        .emit()?;
+
+        Ok(())
+    }
+
+    fn process_transfer_yt(
+        program_id: &Pubkey,
+        accounts: &[AccountInfo],
+        amount: u64,
+    ) -> Result<(), ProgramError> {
+        let TransferYtAccounts {
+            sender,
+            yield_tokenizer,
+            lsu_exchange_rate_account,
+            yt_mint,
+            sender_yt_ata,
+            sender_position_account,
+            recipient,
+            recipient_yt_ata,
+            recipient_position_account,
+            token_program,
+            atoken_program,
+            system_program,
+            config_data,
+            mut yield_tokenizer_data,
+            ..
+        } = TransferYtAccounts::try_from((program_id, accounts))?;
+
+        let clock = clock::Clock::get()?;
+        let bump = yield_tokenizer_data.bump;
+
+        Self::check_pause_state(&config_data, &yield_tokenizer_data, false)?;
+
+        let exchange_rate = Self::get_exchange_rate(
+            &yield_tokenizer_data,
+            lsu_exchange_rate_account,
+            &clock,
+            false,
+        )?;
+        yield_tokenizer_data.update_exchange_rate(exchange_rate, clock.unix_timestamp);
+
+        // Settle both sides at the current index so yield earned so far stays with the sender
+        let mut sender_position = Self::load_or_create_position(
+            program_id,
+            sender,
+            sender.key,
+            yield_tokenizer,
+            sender_position_account,
+            system_program,
+            yield_tokenizer_data.yield_index,
+        )?;
+        sender_position.settle(
+            Self::token_balance(sender_yt_ata)?,
+            yield_tokenizer_data.yield_index,
+        )?;
+
+        let mut recipient_position = Self::load_or_create_position(
+            program_id,
+            sender,
+            recipient.key,
+            yield_tokenizer,
+            recipient_position_account,
+            system_program,
+            yield_tokenizer_data.yield_index,
+        )?;
+        recipient_position.settle(
+            Self::token_balance(recipient_yt_ata)?,
+            yield_tokenizer_data.yield_index,
+        )?;
+
+        invoke(
+            &spl_associated_token_account::instruction::create_associated_token_account_idempotent(
+                sender.key,
+                recipient.key,
+                yt_mint.key,
+                token_program.key,
+            ),
+            &[
+                sender.clone(),
+                recipient_yt_ata.clone(),
+                recipient.clone(),
+                yt_mint.clone(),
+                system_program.clone(),
+                token_program.clone(),
+                atoken_program.clone(),
+            ],
+        )?;
+
+        let maturity_date = yield_tokenizer_data.maturity_date.to_le_bytes();
+        let signer_seeds: &[&[u8]] = &[
+            crate::LSD_SEED,
+            yield_tokenizer_data.lsu_mint.as_ref(),
+            &maturity_date,
+            &[bump],
+        ];
+
+        Self::thaw_yt_account(
+            sender_yt_ata,
+            yt_mint,
+            yield_tokenizer,
+            token_program,
+            signer_seeds,
+        )?;
+        Self::thaw_yt_account(
+            recipient_yt_ata,
+            yt_mint,
+            yield_tokenizer,
+            token_program,
+            signer_seeds,
+        )?;
+
+        let decimals = StateWithExtensions::<Mint>::unpack(&yt_mint.data.borrow())?
+            .base
+            .decimals;
+        invoke(
+            &spl_token_2022::instruction::transfer_checked(
+                token_program.key,
+                sender_yt_ata.key,
+                yt_mint.key,
+                recipient_yt_ata.key,
+                sender.key,
+                &[],
+                amount,
+                decimals,
+            )?,
+            &[
+                sender_yt_ata.clone(),
+                yt_mint.clone(),
+                recipient_yt_ata.clone(),
+                sender.clone(),
+                token_program.clone(),
+            ],
+        )?;
+
+        Self::freeze_yt_account(
+            sender_yt_ata,
+            yt_mint,
+            yield_tokenizer,
+            token_program,
+            signer_seeds,
+        )?;
+        Self::freeze_yt_account(
+            recipient_yt_ata,
+            yt_mint,
+            yield_tokenizer,
+            token_program,
+            signer_seeds,
+        )?;
+
+        sender_position.serialize(&mut &mut sender_position_account.data.borrow_mut()[..])?;
+        recipient_position.serialize(&mut &mut recipient_position_account.data.borrow_mut()[..])?;
+        yield_tokenizer_data.serialize(&mut &mut yield_tokenizer.data.borrow_mut()[..])?;
+
+        YieldTokenizerEvent::TransferYt {
+            yield_tokenizer: *yield_tokenizer.key,
+            user: *sender.key,
+            recipient: *recipient.key,
+            amount,
+            exchange_rate: yield_tokenizer_data.last_exchange_rate,
+            timestamp: clock.unix_timestamp,
         }
-        if token_program.key != &spl_token::id() {
-            return Err(ProgramError::IncorrectProgramId);
+        .emit()?;
+
+        Ok(())
+    }
+
+    fn process_init_config(program_id: &Pubkey, accounts: &[AccountInfo]) -> ProgramResult {
+        let InitializeConfigAccounts {
+            authority,
+            config,
+            system_program,
+            config_bump,
+            ..
+        } = InitializeConfigAccounts::try_from((program_id, accounts))?;
+
+        Self::create_pda_account(
+            program_id,
+            authority,
+            config,
+            system_program,
+            config_len()?,
+            &[crate::CONFIG_SEED, &[config_bump]],
+        )?;
+
+        let config_data = ProtocolConfig::new(*authority.key);
+
+        config_data.serialize(&mut &mut config.data.borrow_mut()[..])?;
+
+        Ok(())
+    }
+
+    fn process_add_lsu(
+        program_id: &Pubkey,
+        accounts: &[AccountInfo],
+        adapter: LsuAdapter,
+        symbol: String,
+    ) -> ProgramResult {
+        let AddLsuAccounts {
+            config,
+            lsu_mint,
+            lsu_exchange_rate_account,
+            mut config_data,
+            ..
+        } = AddLsuAccounts::try_from((program_id, accounts))?;
+
+        let clock = clock::Clock::get()?;
+
+        if config_data.get_lsu(lsu_mint.key).is_some() {
+            return Err(YieldTokenizerError::LSUAlreadySupported.into());
         }
-        if atoken_program.key != &spl_associated_token_account::id() {
-            return Err(ProgramError::IncorrectProgramId);
+        if config_data.lsus.len() >= MAX_SUPPORTED_LSUS {
+            return Err(YieldTokenizerError::SupportedLSUListFull.into());
         }
-        if system_program.key != &system_program::id() {
-            return Err(ProgramError::IncorrectProgramId);
+        if symbol.is_empty() || symbol.len() > MAX_LSU_SYMBOL_LEN {
+            return Err(YieldTokenizerError::InvalidLSUSymbol.into());
         }
-        // Check if expiry has elapsed
-        if clock.unix_timestamp >= yield_tokenizer_data.maturity_date {
-            return Err(YieldTokenizerError::Expired.into());
+
+        // Reading a rate checks the account belongs to the adapter's program and backs the mint
+        adapter.get_exchange_rate(lsu_mint.key, lsu_exchange_rate_account, clock.epoch, false)?;
+
+        config_data.lsus.push(SupportedLsu {
+            mint: *lsu_mint.key,
+            adapter,
+            exchange_rate_account: *lsu_exchange_rate_account.key,
+            symbol,
+        });
+
+        config_data.serialize(&mut &mut config.data.borrow_mut()[..])?;
+
+        Ok(())
+    }
+
+    /// Existing tokenizers for the LSU keep working, only new series are blocked
+    fn process_remove_lsu(program_id: &Pubkey, accounts: &[AccountInfo]) -> ProgramResult {
+        let RemoveLsuAccounts {
+            config,
+            lsu_mint,
+            mut config_data,
+            ..
+        } = RemoveLsuAccounts::try_from((program_id, accounts))?;
+
+        if config_data.get_lsu(lsu_mint.key).is_none() {
+            return Err(YieldTokenizerError::InvalidLSU.into());
         }
+        config_data.lsus.retain(|lsu| &lsu.mint != lsu_mint.key);
 
-        // Get accrued yield
+        config_data.serialize(&mut &mut config.data.borrow_mut()[..])?;
 
-        // Send YT to program
-        // This seems to be erroneous logic since we want to be able to claim any tokens up till
-        // the unix_timestamp but the current logic implies we only collect at maturity
-        // It may be that we need to apply a wrapper around the YT token... fuck me
-        // invoke(
-        //     &spl_token::instruction::transfer(
-        //         token_program.key,
-        //         claimer_yt_ata.key,
-        //         yt_vault.key,
-        //         claimer.key,
-        //         &[claimer.key],
-        //     )?,
-        //     &[
-        //         claimer_yt_ata.clone(),
-        //         yt_vault.clone(),
-        //         claimer.clone(),
-        //         token_program.clone(),
-        //     ],
-        // )?;
-
-        // Program burns YT - which would mean no burning here????
-        // invoke_signed(
-        //     &spl_token::instruction::burn(
-
-        //     )
-        // )
-
-        // Program sends LSU to claimer
-        // invoke_signed()
+        Ok(())
+    }
 
-        // Update program state
-        yield_tokenizer_data.serialize(&mut &mut yield_tokenizer.data.borrow_mut()[..])?;
+    fn process_set_authority(
+        program_id: &Pubkey,
+        accounts: &[AccountInfo],
+        new_authority: Pubkey,
+    ) -> ProgramResult {
+        let UpdateConfigAccounts {
+            config,
+            mut config_data,
+            ..
+        } = UpdateConfigAccounts::try_from((program_id, accounts))?;
+
+        config_data.authority = new_authority;
+
+        config_data.serialize(&mut &mut config.data.borrow_mut()[..])?;
 
         Ok(())
     }
 
-    fn get_pt_value(ib_token_price: f64, token_price: f64, now: i64) -> i64 {
-        (1i64 - (
-            token_price as i64 / 
-                (ib_token_price as i64 ^ YEAR_SEC as i64 / (YEAR_SEC as i64 - now))))
-        + 1i64
+    fn process_set_fees(
+        program_id: &Pubkey,
+        accounts: &[AccountInfo],
+        yield_fee_bps: u16,
+        redemption_fee_bps: u16,
+    ) -> ProgramResult {
+        let UpdateConfigAccounts {
+            config,
+            mut config_data,
+            ..
+        } = UpdateConfigAccounts::try_from((program_id, accounts))?;
+
+        if yield_fee_bps > MAX_FEE_BPS || redemption_fee_bps > MAX_FEE_BPS {
+            return Err(YieldTokenizerError::InvalidFee.into());
+        }
+        config_data.yield_fee_bps = yield_fee_bps;
+        config_data.redemption_fee_bps = redemption_fee_bps;
+
+        config_data.serialize(&mut &mut config.data.borrow_mut()[..])?;
+
+        Ok(())
     }
 
-    fn validate_lsu() -> Result<(), ProgramError> {
-        unimplemented!();
+    fn process_withdraw_fees(
+        program_id: &Pubkey,
+        accounts: &[AccountInfo],
+        amount: u64,
+    ) -> ProgramResult {
+        let WithdrawFeesAccounts {
+            yield_tokenizer,
+            treasury,
+            destination,
+            token_program,
+            yield_tokenizer_data,
+            ..
+        } = WithdrawFeesAccounts::try_from((program_id, accounts))?;
+
+        invoke_signed(
+            &spl_token::instruction::transfer(
+                token_program.key,
+                treasury.key,
+                destination.key,
+                yield_tokenizer.key,
+                &[],
+                amount,
+            )?,
+            &[
+                treasury.clone(),
+                destination.clone(),
+                yield_tokenizer.clone(),
+                token_program.clone(),
+            ],
+            &[&[
+                crate::LSD_SEED,
+                yield_tokenizer_data.lsu_mint.as_ref(),
+                &yield_tokenizer_data.maturity_date.to_le_bytes(),
+                &[yield_tokenizer_data.bump],
+            ]],
+        )?;
+
+        Ok(())
     }
 
-    fn calc_yield_owed() -> Result<(), ProgramError> {
-        unimplemented!();
+    fn process_set_pause(
+        program_id: &Pubkey,
+        accounts: &[AccountInfo],
+        pause_state: PauseState,
+    ) -> ProgramResult {
+        let UpdateConfigAccounts {
+            config,
+            mut config_data,
+            ..
+        } = UpdateConfigAccounts::try_from((program_id, accounts))?;
+
+        config_data.pause_state = pause_state;
+
+        config_data.serialize(&mut &mut config.data.borrow_mut()[..])?;
+
+        Ok(())
     }
 
-    fn calc_required_lsu_for_yield_owed() -> Result<(), ProgramError> {
-        unimplemented!();
+    fn process_set_tokenizer_pause(
+        program_id: &Pubkey,
+        accounts: &[AccountInfo],
+        pause_state: PauseState,
+    ) -> ProgramResult {
+        let SetTokenizerPauseAccounts {
+            yield_tokenizer,
+            mut yield_tokenizer_data,
+            ..
+        } = SetTokenizerPauseAccounts::try_from((program_id, accounts))?;
+
+        yield_tokenizer_data.pause_state = pause_state;
+
+        yield_tokenizer_data.serialize(&mut &mut yield_tokenizer.data.borrow_mut()[..])?;
+
+        Ok(())
     }
 
-    fn check_maturity() -> Result<(), ProgramError> {
-        unimplemented!();
+    fn process_init_pool(
+        program_id: &Pubkey,
+        accounts: &[AccountInfo],
+        scalar_root: i128,
+        initial_anchor: i128,
+        ln_fee_rate_root: i128,
+    ) -> ProgramResult {
+        let InitializePoolAccounts {
+            authority,
+            yield_tokenizer,
+            lsu_mint,
+            pt_mint,
+            pool,
+            lp_mint,
+            pool_lsu_vault,
+            pool_pt_vault,
+            token_program,
+            yield_token_program,
+            atoken_program,
+            system_program,
+            config_data,
+            yield_tokenizer_data,
+            pool_bump,
+            lp_mint_bump,
+            ..
+        } = InitializePoolAccounts::try_from((program_id, accounts))?;
+
+        let clock = clock::Clock::get()?;
+
+        if clock.unix_timestamp >= yield_tokenizer_data.maturity_date {
+            return Err(YieldTokenizerError::Expired.into());
+        }
+        Self::check_pause_state(&config_data, &yield_tokenizer_data, false)?;
+
+        if scalar_root <= 0
+            || scalar_root > MAX_SCALAR_ROOT
+            || initial_anchor < ONE
+            || !(0..=MAX_LN_FEE_RATE_ROOT).contains(&ln_fee_rate_root)
+        {
+            return Err(YieldTokenizerError::InvalidPoolParameters.into());
+        }
+
+        Self::create_pda_account(
+            program_id,
+            authority,
+            pool,
+            system_program,
+            pool_len()?,
+            &[crate::POOL_SEED, yield_tokenizer.key.as_ref(), &[pool_bump]],
+        )?;
+
+        // LP mirrors the LSU's decimals, like PT and YT
+        let decimals = spl_token::state::Mint::unpack(&lsu_mint.data.borrow())?.decimals;
+        if lp_mint.owner != token_program.key {
+            Self::create_pda_account(
+                token_program.key,
+                authority,
+                lp_mint,
+                system_program,
+                spl_token::state::Mint::LEN,
+                &[pool.key.as_ref(), crate::LP_SEED, &[lp_mint_bump]],
+            )?;
+            invoke(
+                &spl_token::instruction::initialize_mint2(
+                    token_program.key,
+                    lp_mint.key,
+                    pool.key,
+                    None,
+                    decimals,
+                )?,
+                &[lp_mint.clone(), token_program.clone()],
+            )?;
+        }
+
+        for (vault, mint, vault_token_program) in [
+            (pool_lsu_vault, lsu_mint, token_program),
+            (pool_pt_vault, pt_mint, yield_token_program),
+        ] {
+            invoke(
+                &spl_associated_token_account::instruction::create_associated_token_account_idempotent(
+                    authority.key,
+                    pool.key,
+                    mint.key,
+                    vault_token_program.key,
+                ),
+                &[
+                    authority.clone(),
+                    vault.clone(),
+                    pool.clone(),
+                    mint.clone(),
+                    system_program.clone(),
+                    vault_token_program.clone(),
+                    atoken_program.clone(),
+                ],
+            )?;
+        }
+
+        let pool_data = PtPool::new(
+            *yield_tokenizer.key,
+            *lp_mint.key,
+            *pool_lsu_vault.key,
+            *pool_pt_vault.key,
+            scalar_root,
+            initial_anchor,
+            ln_fee_rate_root,
+            pool_bump,
+        );
+
+        pool_data.serialize(&mut &mut pool.data.borrow_mut()[..])?;
+
+        Ok(())
     }
 
-    fn zero_coupon_pricing(
-        maturity_value: u64,
-        required_interest_rate: f64,
-        duration_till_maturity: u64,
-    ) -> Result<f64, ProgramError> {
-        unimplemented!();
+    fn process_add_liquidity(
+        program_id: &Pubkey,
+        accounts: &[AccountInfo],
+        pt_amount: u64,
+        lsu_amount: u64,
+        min_lp_out: u64,
+    ) -> ProgramResult {
+        let AddLiquidityAccounts {
+            provider,
+            yield_tokenizer,
+            lsu_exchange_rate_account,
+            pt_mint,
+            pool,
+            lp_mint,
+            pool_lsu_vault,
+            pool_pt_vault,
+            provider_lsu_ata,
+            provider_pt_ata,
+            provider_lp_ata,
+            token_program,
+            yield_token_program,
+            atoken_program,
+            system_program,
+            config_data,
+            mut yield_tokenizer_data,
+            mut pool_data,
+            pool_bump,
+            ..
+        } = AddLiquidityAccounts::try_from((program_id, accounts))?;
+
+        let clock = clock::Clock::get()?;
+
+        if clock.unix_timestamp >= yield_tokenizer_data.maturity_date {
+            return Err(YieldTokenizerError::Expired.into());
+        }
+        Self::check_pause_state(&config_data, &yield_tokenizer_data, false)?;
+
+        let exchange_rate = Self::get_exchange_rate(
+            &yield_tokenizer_data,
+            lsu_exchange_rate_account,
+            &clock,
+            false,
+        )?;
+        yield_tokenizer_data.update_exchange_rate(exchange_rate, clock.unix_timestamp);
+
+        let (lp_out, pt_used, lsu_used) = if pool_data.lp_supply == 0 {
+            let lp_out = pool_data.open(
+                &yield_tokenizer_data,
+                clock.unix_timestamp,
+                pt_amount,
+                lsu_amount,
+            )?;
+            (lp_out, pt_amount, lsu_amount)
+        } else {
+            let (lp_out, pt_used, lsu_used) =
+                proportional_deposit(&pool_data, pt_amount, lsu_amount)?;
+            pool_data.pt_reserve += pt_used;
+            pool_data.lsu_reserve += lsu_used;
+            pool_data.lp_supply += lp_out;
+            (lp_out, pt_used, lsu_used)
+        };
+        if lp_out < min_lp_out {
+            return Err(YieldTokenizerError::SlippageExceeded.into());
+        }
+
+        invoke(
+            &spl_token::instruction::transfer(
+                token_program.key,
+                provider_lsu_ata.key,
+                pool_lsu_vault.key,
+                provider.key,
+                &[],
+                lsu_used,
+            )?,
+            &[
+                provider_lsu_ata.clone(),
+                pool_lsu_vault.clone(),
+                provider.clone(),
+                token_program.clone(),
+            ],
+        )?;
+        Self::transfer_yield_token(
+            provider_pt_ata,
+            pt_mint,
+            pool_pt_vault,
+            provider,
+            yield_token_program,
+            &[],
+            pt_used,
+        )?;
+
+        invoke(
+            &spl_associated_token_account::instruction::create_associated_token_account_idempotent(
+                provider.key,
+                provider.key,
+                lp_mint.key,
+                token_program.key,
+            ),
+            &[
+                provider.clone(),
+                provider_lp_ata.clone(),
+                provider.clone(),
+                lp_mint.clone(),
+                system_program.clone(),
+                token_program.clone(),
+                atoken_program.clone(),
+            ],
+        )?;
+        invoke_signed(
+            &spl_token::instruction::mint_to(
+                token_program.key,
+                lp_mint.key,
+                provider_lp_ata.key,
+                pool.key,
+                &[],
+                lp_out,
+            )?,
+            &[
+                lp_mint.clone(),
+                provider_lp_ata.clone(),
+                pool.clone(),
+                token_program.clone(),
+            ],
+            &[&[crate::POOL_SEED, yield_tokenizer.key.as_ref(), &[pool_bump]]],
+        )?;
+
+        pool_data.serialize(&mut &mut pool.data.borrow_mut()[..])?;
+        yield_tokenizer_data.serialize(&mut &mut yield_tokenizer.data.borrow_mut()[..])?;
+
+        YieldTokenizerEvent::AddLiquidity {
+            yield_tokenizer: *yield_tokenizer.key,
+            user: *provider.key,
+            pt_amount: pt_used,
+            lsu_amount: lsu_used,
+            lp_amount: lp_out,
+            exchange_rate: yield_tokenizer_data.last_exchange_rate,
+            timestamp: clock.unix_timestamp,
+        }
+        .emit()?;
+
+        Ok(())
+    }
+
+    fn process_remove_liquidity(
+        program_id: &Pubkey,
+        accounts: &[AccountInfo],
+        lp_amount: u64,
+        min_pt_out: u64,
+        min_lsu_out: u64,
+    ) -> ProgramResult {
+        let RemoveLiquidityAccounts {
+            provider,
+            yield_tokenizer,
+            pt_mint,
+            pool,
+            lp_mint,
+            pool_lsu_vault,
+            pool_pt_vault,
+            provider_lsu_ata,
+            provider_pt_ata,
+            provider_lp_ata,
+            token_program,
+            yield_token_program,
+            config_data,
+            yield_tokenizer_data,
+            mut pool_data,
+            pool_bump,
+            ..
+        } = RemoveLiquidityAccounts::try_from((program_id, accounts))?;
+
+        // Withdrawing is proportional and doesn't read the exchange rate, so it's an exit
+        Self::check_pause_state(&config_data, &yield_tokenizer_data, true)?;
+
+        let (pt_out, lsu_out) = proportional_withdrawal(&pool_data, lp_amount)?;
+        if pt_out < min_pt_out || lsu_out < min_lsu_out {
+            return Err(YieldTokenizerError::SlippageExceeded.into());
+        }
+        pool_data.pt_reserve -= pt_out;
+        pool_data.lsu_reserve -= lsu_out;
+        pool_data.lp_supply -= lp_amount;
+
+        invoke(
+            &spl_token::instruction::burn(
+                token_program.key,
+                provider_lp_ata.key,
+                lp_mint.key,
+                provider.key,
+                &[],
+                lp_amount,
+            )?,
+            &[
+                provider_lp_ata.clone(),
+                lp_mint.clone(),
+                provider.clone(),
+                token_program.clone(),
+            ],
+        )?;
+
+        let pool_seeds: &[&[u8]] = &[crate::POOL_SEED, yield_tokenizer.key.as_ref(), &[pool_bump]];
+        invoke_signed(
+            &spl_token::instruction::transfer(
+                token_program.key,
+                pool_lsu_vault.key,
+                provider_lsu_ata.key,
+                pool.key,
+                &[],
+                lsu_out,
+            )?,
+            &[
+                pool_lsu_vault.clone(),
+                provider_lsu_ata.clone(),
+                pool.clone(),
+                token_program.clone(),
+            ],
+            &[pool_seeds],
+        )?;
+        Self::transfer_yield_token(
+            pool_pt_vault,
+            pt_mint,
+            provider_pt_ata,
+            pool,
+            yield_token_program,
+            &[pool_seeds],
+            pt_out,
+        )?;
+
+        pool_data.serialize(&mut &mut pool.data.borrow_mut()[..])?;
+
+        YieldTokenizerEvent::RemoveLiquidity {
+            yield_tokenizer: *yield_tokenizer.key,
+            user: *provider.key,
+            lp_amount,
+            pt_amount: pt_out,
+            lsu_amount: lsu_out,
+            exchange_rate: yield_tokenizer_data.last_exchange_rate,
+            timestamp: clock::Clock::get()?.unix_timestamp,
+        }
+        .emit()?;
+
+        Ok(())
+    }
+
+    fn process_swap_exact_lsu_for_pt(
+        program_id: &Pubkey,
+        accounts: &[AccountInfo],
+        lsu_in: u64,
+        min_pt_out: u64,
+    ) -> ProgramResult {
+        let SwapPtAccounts {
+            trader,
+            yield_tokenizer,
+            lsu_exchange_rate_account,
+            pt_mint,
+            pool,
+            pool_lsu_vault,
+            pool_pt_vault,
+            trader_lsu_ata,
+            trader_pt_ata,
+            token_program,
+            yield_token_program,
+            atoken_program,
+            system_program,
+            config_data,
+            mut yield_tokenizer_data,
+            mut pool_data,
+            pool_bump,
+            ..
+        } = SwapPtAccounts::try_from((program_id, accounts))?;
+
+        let clock = clock::Clock::get()?;
+
+        if clock.unix_timestamp >= yield_tokenizer_data.maturity_date {
+            return Err(YieldTokenizerError::Expired.into());
+        }
+        Self::check_pause_state(&config_data, &yield_tokenizer_data, false)?;
+
+        let exchange_rate = Self::get_exchange_rate(
+            &yield_tokenizer_data,
+            lsu_exchange_rate_account,
+            &clock,
+            false,
+        )?;
+        yield_tokenizer_data.update_exchange_rate(exchange_rate, clock.unix_timestamp);
+
+        let pt_out =
+            pool_data.swap_exact_lsu_for_pt(&yield_tokenizer_data, clock.unix_timestamp, lsu_in)?;
+        if pt_out < min_pt_out {
+            return Err(YieldTokenizerError::SlippageExceeded.into());
+        }
+
+        invoke(
+            &spl_token::instruction::transfer(
+                token_program.key,
+                trader_lsu_ata.key,
+                pool_lsu_vault.key,
+                trader.key,
+                &[],
+                lsu_in,
+            )?,
+            &[
+                trader_lsu_ata.clone(),
+                pool_lsu_vault.clone(),
+                trader.clone(),
+                token_program.clone(),
+            ],
+        )?;
+
+        invoke(
+            &spl_associated_token_account::instruction::create_associated_token_account_idempotent(
+                trader.key,
+                trader.key,
+                pt_mint.key,
+                yield_token_program.key,
+            ),
+            &[
+                trader.clone(),
+                trader_pt_ata.clone(),
+                trader.clone(),
+                pt_mint.clone(),
+                system_program.clone(),
+                yield_token_program.clone(),
+                atoken_program.clone(),
+            ],
+        )?;
+        Self::transfer_yield_token(
+            pool_pt_vault,
+            pt_mint,
+            trader_pt_ata,
+            pool,
+            yield_token_program,
+            &[&[crate::POOL_SEED, yield_tokenizer.key.as_ref(), &[pool_bump]]],
+            pt_out,
+        )?;
+
+        pool_data.serialize(&mut &mut pool.data.borrow_mut()[..])?;
+        yield_tokenizer_data.serialize(&mut &mut yield_tokenizer.data.borrow_mut()[..])?;
+
+        YieldTokenizerEvent::SwapExactLsuForPt {
+            yield_tokenizer: *yield_tokenizer.key,
+            user: *trader.key,
+            lsu_in,
+            pt_out,
+            ln_implied_rate: pool_data.last_ln_implied_rate,
+            exchange_rate: yield_tokenizer_data.last_exchange_rate,
+            timestamp: clock.unix_timestamp,
+        }
+        .emit()?;
+
+        Ok(())
+    }
+
+    fn process_swap_exact_pt_for_lsu(
+        program_id: &Pubkey,
+        accounts: &[AccountInfo],
+        pt_in: u64,
+        min_lsu_out: u64,
+    ) -> ProgramResult {
+        let SwapPtAccounts {
+            trader,
+            yield_tokenizer,
+            lsu_mint,
+            lsu_exchange_rate_account,
+            pt_mint,
+            pool,
+            pool_lsu_vault,
+            pool_pt_vault,
+            trader_lsu_ata,
+            trader_pt_ata,
+            token_program,
+            yield_token_program,
+            atoken_program,
+            system_program,
+            config_data,
+            mut yield_tokenizer_data,
+            mut pool_data,
+            pool_bump,
+            ..
+        } = SwapPtAccounts::try_from((program_id, accounts))?;
+
+        let clock = clock::Clock::get()?;
+
+        if clock.unix_timestamp >= yield_tokenizer_data.maturity_date {
+            return Err(YieldTokenizerError::Expired.into());
+        }
+        Self::check_pause_state(&config_data, &yield_tokenizer_data, false)?;
+
+        let exchange_rate = Self::get_exchange_rate(
+            &yield_tokenizer_data,
+            lsu_exchange_rate_account,
+            &clock,
+            false,
+        )?;
+        yield_tokenizer_data.update_exchange_rate(exchange_rate, clock.unix_timestamp);
+
+        let lsu_out =
+            pool_data.swap_exact_pt_for_lsu(&yield_tokenizer_data, clock.unix_timestamp, pt_in)?;
+        if lsu_out < min_lsu_out {
+            return Err(YieldTokenizerError::SlippageExceeded.into());
+        }
+
+        Self::transfer_yield_token(
+            trader_pt_ata,
+            pt_mint,
+            pool_pt_vault,
+            trader,
+            yield_token_program,
+            &[],
+            pt_in,
+        )?;
+
+        invoke(
+            &spl_associated_token_account::instruction::create_associated_token_account_idempotent(
+                trader.key,
+                trader.key,
+                lsu_mint.key,
+                token_program.key,
+            ),
+            &[
+                trader.clone(),
+                trader_lsu_ata.clone(),
+                trader.clone(),
+                lsu_mint.clone(),
+                system_program.clone(),
+                token_program.clone(),
+                atoken_program.clone(),
+            ],
+        )?;
+        invoke_signed(
+            &spl_token::instruction::transfer(
+                token_program.key,
+                pool_lsu_vault.key,
+                trader_lsu_ata.key,
+                pool.key,
+                &[],
+                lsu_out,
+            )?,
+            &[
+                pool_lsu_vault.clone(),
+                trader_lsu_ata.clone(),
+                pool.clone(),
+                token_program.clone(),
+            ],
+            &[&[crate::POOL_SEED, yield_tokenizer.key.as_ref(), &[pool_bump]]],
+        )?;
+
+        pool_data.serialize(&mut &mut pool.data.borrow_mut()[..])?;
+        yield_tokenizer_data.serialize(&mut &mut yield_tokenizer.data.borrow_mut()[..])?;
+
+        YieldTokenizerEvent::SwapExactPtForLsu {
+            yield_tokenizer: *yield_tokenizer.key,
+            user: *trader.key,
+            pt_in,
+            lsu_out,
+            ln_implied_rate: pool_data.last_ln_implied_rate,
+            exchange_rate: yield_tokenizer_data.last_exchange_rate,
+            timestamp: clock.unix_timestamp,
+        }
+        .emit()?;
+
+        Ok(())
+    }
+
+    fn process_swap_exact_lsu_for_yt(
+        program_id: &Pubkey,
+        accounts: &[AccountInfo],
+        lsu_in: u64,
+        min_yt_out: u64,
+    ) -> ProgramResult {
+        let SwapExactLsuForYtAccounts {
+            trader,
+            yield_tokenizer,
+            lsu_exchange_rate_account,
+            pt_mint,
+            yt_mint,
+            lsu_vault,
+            pool,
+            pool_lsu_vault,
+            pool_pt_vault,
+            trader_lsu_ata,
+            trader_yt_ata,
+            trader_position_account,
+            token_program,
+            yield_token_program,
+            system_program,
+            config_data,
+            mut yield_tokenizer_data,
+            mut pool_data,
+            pool_bump,
+            ..
+        } = SwapExactLsuForYtAccounts::try_from((program_id, accounts))?;
+
+        let clock = clock::Clock::get()?;
+        let now = clock.unix_timestamp;
+        let bump = yield_tokenizer_data.bump;
+
+        if now >= yield_tokenizer_data.maturity_date {
+            return Err(YieldTokenizerError::Expired.into());
+        }
+        Self::check_pause_state(&config_data, &yield_tokenizer_data, false)?;
+
+        let exchange_rate = Self::get_exchange_rate(
+            &yield_tokenizer_data,
+            lsu_exchange_rate_account,
+            &clock,
+            false,
+        )?;
+        yield_tokenizer_data.update_exchange_rate(exchange_rate, now);
+
+        let mut trader_position = Self::load_or_create_position(
+            program_id,
+            trader,
+            trader.key,
+            yield_tokenizer,
+            trader_position_account,
+            system_program,
+            yield_tokenizer_data.yield_index,
+        )?;
+        trader_position.settle(
+            Self::token_balance(trader_yt_ata)?,
+            yield_tokenizer_data.yield_index,
+        )?;
+
+        // Tokenize `lsu_in` plus the LSU the pool pays for the PT leg, which is minted straight
+        // into the pool, and keep the YT
+        let yt_out = pool_data.yt_out_for_exact_lsu_in(&yield_tokenizer_data, now, lsu_in)?;
+        if yt_out == 0 || yt_out < min_yt_out {
+            return Err(YieldTokenizerError::SlippageExceeded.into());
+        }
+        let lsu_from_pool = pool_data.swap_exact_pt_for_lsu(&yield_tokenizer_data, now, yt_out)?;
+
+        invoke(
+            &spl_token::instruction::transfer(
+                token_program.key,
+                trader_lsu_ata.key,
+                lsu_vault.key,
+                trader.key,
+                &[],
+                lsu_in,
+            )?,
+            &[
+                trader_lsu_ata.clone(),
+                lsu_vault.clone(),
+                trader.clone(),
+                token_program.clone(),
+            ],
+        )?;
+        invoke_signed(
+            &spl_token::instruction::transfer(
+                token_program.key,
+                pool_lsu_vault.key,
+                lsu_vault.key,
+                pool.key,
+                &[],
+                lsu_from_pool,
+            )?,
+            &[
+                pool_lsu_vault.clone(),
+                lsu_vault.clone(),
+                pool.clone(),
+                token_program.clone(),
+            ],
+            &[&[crate::POOL_SEED, yield_tokenizer.key.as_ref(), &[pool_bump]]],
+        )?;
+
+        let maturity_date = yield_tokenizer_data.maturity_date.to_le_bytes();
+        let signer_seeds: &[&[u8]] = &[
+            crate::LSD_SEED,
+            yield_tokenizer_data.lsu_mint.as_ref(),
+            &maturity_date,
+            &[bump],
+        ];
+
+        invoke_signed(
+            &spl_token_2022::instruction::mint_to(
+                yield_token_program.key,
+                pt_mint.key,
+                pool_pt_vault.key,
+                yield_tokenizer.key,
+                &[],
+                yt_out,
+            )?,
+            &[
+                pt_mint.clone(),
+                pool_pt_vault.clone(),
+                yield_tokenizer.clone(),
+                yield_token_program.clone(),
+            ],
+            &[signer_seeds],
+        )?;
+
+        Self::thaw_yt_account(
+            trader_yt_ata,
+            yt_mint,
+            yield_tokenizer,
+            yield_token_program,
+            signer_seeds,
+        )?;
+        invoke_signed(
+            &spl_token_2022::instruction::mint_to(
+                yield_token_program.key,
+                yt_mint.key,
+                trader_yt_ata.key,
+                yield_tokenizer.key,
+                &[],
+                yt_out,
+            )?,
+            &[
+                yt_mint.clone(),
+                trader_yt_ata.clone(),
+                yield_tokenizer.clone(),
+                yield_token_program.clone(),
+            ],
+            &[signer_seeds],
+        )?;
+        Self::freeze_yt_account(
+            trader_yt_ata,
+            yt_mint,
+            yield_tokenizer,
+            yield_token_program,
+            signer_seeds,
+        )?;
+
+        trader_position.serialize(&mut &mut trader_position_account.data.borrow_mut()[..])?;
+        pool_data.serialize(&mut &mut pool.data.borrow_mut()[..])?;
+        yield_tokenizer_data.serialize(&mut &mut yield_tokenizer.data.borrow_mut()[..])?;
+
+        YieldTokenizerEvent::SwapExactLsuForYt {
+            yield_tokenizer: *yield_tokenizer.key,
+            user: *trader.key,
+            lsu_in,
+            yt_out,
+            ln_implied_rate: pool_data.last_ln_implied_rate,
+            exchange_rate: yield_tokenizer_data.last_exchange_rate,
+            timestamp: now,
+        }
+        .emit()?;
+
+        Ok(())
+    }
+
+    fn process_swap_exact_yt_for_lsu(
+        program_id: &Pubkey,
+        accounts: &[AccountInfo],
+        yt_in: u64,
+        min_lsu_out: u64,
+    ) -> ProgramResult {
+        let SwapExactYtForLsuAccounts {
+            trader,
+            yield_tokenizer,
+            lsu_exchange_rate_account,
+            pt_mint,
+            yt_mint,
+            lsu_vault,
+            treasury,
+            pool,
+            pool_lsu_vault,
+            pool_pt_vault,
+            trader_lsu_ata,
+            trader_yt_ata,
+            trader_position_account,
+            token_program,
+            yield_token_program,
+            system_program,
+            config_data,
+            mut yield_tokenizer_data,
+            mut pool_data,
+            pool_bump,
+            ..
+        } = SwapExactYtForLsuAccounts::try_from((program_id, accounts))?;
+
+        let clock = clock::Clock::get()?;
+        let now = clock.unix_timestamp;
+        let bump = yield_tokenizer_data.bump;
+
+        if now >= yield_tokenizer_data.maturity_date {
+            return Err(YieldTokenizerError::Expired.into());
+        }
+        Self::check_pause_state(&config_data, &yield_tokenizer_data, false)?;
+
+        let exchange_rate = Self::get_exchange_rate(
+            &yield_tokenizer_data,
+            lsu_exchange_rate_account,
+            &clock,
+            false,
+        )?;
+        yield_tokenizer_data.update_exchange_rate(exchange_rate, now);
+
+        // Settle the trader's yield before their YT balance changes, it's paid out with the sale
+        let mut trader_position = Self::load_or_create_position(
+            program_id,
+            trader,
+            trader.key,
+            yield_tokenizer,
+            trader_position_account,
+            system_program,
+            yield_tokenizer_data.yield_index,
+        )?;
+        trader_position.settle(
+            Self::token_balance(trader_yt_ata)?,
+            yield_tokenizer_data.yield_index,
+        )?;
+        let yield_fee = config_data.yield_fee(trader_position.accrued_yield);
+
+        // Buy back the PT leg from the pool and redeem it with the YT, the pool is paid out of
+        // the redeemed LSU
+        let lsu_to_pool = pool_data.swap_lsu_for_exact_pt(&yield_tokenizer_data, now, yt_in)?;
+        let lsu_out = yield_tokenizer_data
+            .principal_to_lsu(yt_in)?
+            .checked_sub(lsu_to_pool)
+            .ok_or(YieldTokenizerError::InsufficientLiquidity)?;
+        if lsu_out < min_lsu_out {
+            return Err(YieldTokenizerError::SlippageExceeded.into());
+        }
+        let lsu_amount = lsu_out
+            .checked_add(trader_position.accrued_yield - yield_fee)
+            .ok_or(ProgramError::ArithmeticOverflow)?;
+        trader_position.accrued_yield = 0;
+
+        let maturity_date = yield_tokenizer_data.maturity_date.to_le_bytes();
+        let signer_seeds: &[&[u8]] = &[
+            crate::LSD_SEED,
+            yield_tokenizer_data.lsu_mint.as_ref(),
+            &maturity_date,
+            &[bump],
+        ];
+
+        // Burn the PT leg straight out of the pool
+        invoke_signed(
+            &spl_token_2022::instruction::burn(
+                yield_token_program.key,
+                pool_pt_vault.key,
+                pt_mint.key,
+                pool.key,
+                &[],
+                yt_in,
+            )?,
+            &[
+                pool_pt_vault.clone(),
+                pt_mint.clone(),
+                pool.clone(),
+                yield_token_program.clone(),
+            ],
+            &[&[crate::POOL_SEED, yield_tokenizer.key.as_ref(), &[pool_bump]]],
+        )?;
+
+        Self::thaw_yt_account(
+            trader_yt_ata,
+            yt_mint,
+            yield_tokenizer,
+            yield_token_program,
+            signer_seeds,
+        )?;
+        invoke(
+            &spl_token_2022::instruction::burn(
+                yield_token_program.key,
+                trader_yt_ata.key,
+                yt_mint.key,
+                trader.key,
+                &[],
+                yt_in,
+            )?,
+            &[
+                trader_yt_ata.clone(),
+                yt_mint.clone(),
+                trader.clone(),
+                yield_token_program.clone(),
+            ],
+        )?;
+        Self::freeze_yt_account(
+            trader_yt_ata,
+            yt_mint,
+            yield_tokenizer,
+            yield_token_program,
+            signer_seeds,
+        )?;
+
+        invoke_signed(
+            &spl_token::instruction::transfer(
+                token_program.key,
+                lsu_vault.key,
+                pool_lsu_vault.key,
+                yield_tokenizer.key,
+                &[],
+                lsu_to_pool,
+            )?,
+            &[
+                lsu_vault.clone(),
+                pool_lsu_vault.clone(),
+                yield_tokenizer.clone(),
+                token_program.clone(),
+            ],
+            &[signer_seeds],
+        )?;
+        invoke_signed(
+            &spl_token::instruction::transfer(
+                token_program.key,
+                lsu_vault.key,
+                trader_lsu_ata.key,
+                yield_tokenizer.key,
+                &[],
+                lsu_amount,
+            )?,
+            &[
+                lsu_vault.clone(),
+                trader_lsu_ata.clone(),
+                yield_tokenizer.clone(),
+                token_program.clone(),
+            ],
+            &[signer_seeds],
+        )?;
+        Self::collect_fee(
+            lsu_vault,
+            treasury,
+            yield_tokenizer,
+            token_program,
+            signer_seeds,
+            yield_fee,
+        )?;
+
+        trader_position.serialize(&mut &mut trader_position_account.data.borrow_mut()[..])?;
+        pool_data.serialize(&mut &mut pool.data.borrow_mut()[..])?;
+        yield_tokenizer_data.serialize(&mut &mut yield_tokenizer.data.borrow_mut()[..])?;
+
+        YieldTokenizerEvent::SwapExactYtForLsu {
+            yield_tokenizer: *yield_tokenizer.key,
+            user: *trader.key,
+            yt_in,
+            lsu_out: lsu_amount,
+            ln_implied_rate: pool_data.last_ln_implied_rate,
+            exchange_rate: yield_tokenizer_data.last_exchange_rate,
+            timestamp: now,
+        }
+        .emit()?;
+
+        Ok(())
+    }
+
+    fn process_rollover(
+        program_id: &Pubkey,
+        accounts: &[AccountInfo],
+        amount: u64,
+    ) -> ProgramResult {
+        let RolloverAccounts {
+            owner,
+            matured_yield_tokenizer,
+            matured_lsu_exchange_rate_account,
+            matured_pt_mint,
+            matured_yt_mint,
+            matured_lsu_vault,
+            matured_treasury,
+            owner_matured_pt_ata,
+            owner_matured_yt_ata,
+            owner_matured_position_account,
+            yield_tokenizer,
+            lsu_exchange_rate_account,
+            pt_mint,
+            yt_mint,
+            lsu_vault,
+            owner_pt_ata,
+            owner_yt_ata,
+            owner_position_account,
+            token_program,
+            matured_yield_token_program,
+            yield_token_program,
+            system_program,
+            config_data,
+            mut matured_data,
+            mut yield_tokenizer_data,
+            ..
+        } = RolloverAccounts::try_from((program_id, accounts))?;
+
+        let clock = clock::Clock::get()?;
+        let now = clock.unix_timestamp;
+        let matured_bump = matured_data.bump;
+        let bump = yield_tokenizer_data.bump;
+
+        if now < matured_data.maturity_date {
+            return Err(YieldTokenizerError::Immature.into());
+        }
+        if now > yield_tokenizer_data.maturity_date {
+            return Err(YieldTokenizerError::Expired.into());
+        }
+
+        // Leaving the matured series is an exit, entering the new one isn't
+        let matured_exchange_rate = if Self::check_pause_state(&config_data, &matured_data, true)?
+            == PauseState::Emergency
+        {
+            matured_data.last_exchange_rate
+        } else {
+            Self::get_exchange_rate(
+                &matured_data,
+                matured_lsu_exchange_rate_account,
+                &clock,
+                true,
+            )?
+        };
+        matured_data.update_exchange_rate(matured_exchange_rate, now);
+        Self::check_pause_state(&config_data, &yield_tokenizer_data, false)?;
+        let exchange_rate = Self::get_exchange_rate(
+            &yield_tokenizer_data,
+            lsu_exchange_rate_account,
+            &clock,
+            false,
+        )?;
+        yield_tokenizer_data.update_exchange_rate(exchange_rate, now);
+
+        // PT redeems at the maturity rate and the YT's final yield comes with it, both net of
+        // the protocol fees
+        let mut matured_position = Self::load_or_create_position(
+            program_id,
+            owner,
+            owner.key,
+            matured_yield_tokenizer,
+            owner_matured_position_account,
+            system_program,
+            matured_data.yield_index,
+        )?;
+        let matured_yt_balance = Self::token_balance(owner_matured_yt_ata)?;
+        matured_position.settle(matured_yt_balance, matured_data.yield_index)?;
+        let yield_fee = config_data.yield_fee(matured_position.accrued_yield);
+        let redeemed_lsu = matured_data.principal_to_lsu(amount)?;
+        let redemption_fee = config_data.redemption_fee(redeemed_lsu);
+        let lsu_amount = (redeemed_lsu - redemption_fee)
+            .checked_add(matured_position.accrued_yield - yield_fee)
+            .ok_or(ProgramError::ArithmeticOverflow)?;
+        matured_position.accrued_yield = 0;
+
+        let mut position = Self::load_or_create_position(
+            program_id,
+            owner,
+            owner.key,
+            yield_tokenizer,
+            owner_position_account,
+            system_program,
+            yield_tokenizer_data.yield_index,
+        )?;
+        position.settle(
+            Self::token_balance(owner_yt_ata)?,
+            yield_tokenizer_data.yield_index,
+        )?;
+        let principal = yield_tokenizer_data.lsu_to_principal(lsu_amount)?;
+
+        let matured_maturity_date = matured_data.maturity_date.to_le_bytes();
+        let matured_signer_seeds: &[&[u8]] = &[
+            crate::LSD_SEED,
+            matured_data.lsu_mint.as_ref(),
+            &matured_maturity_date,
+            &[matured_bump],
+        ];
+
+        invoke(
+            &spl_token_2022::instruction::burn(
+                matured_yield_token_program.key,
+                owner_matured_pt_ata.key,
+                matured_pt_mint.key,
+                owner.key,
+                &[],
+                amount,
+            )?,
+            &[
+                owner_matured_pt_ata.clone(),
+                matured_pt_mint.clone(),
+                owner.clone(),
+                matured_yield_token_program.clone(),
+            ],
+        )?;
+
+        // The YT's yield up to maturity has just been paid out and it earns nothing after, so the
+        // owner's matured YT is burned with the PT
+        if matured_yt_balance > 0 {
+            Self::thaw_yt_account(
+                owner_matured_yt_ata,
+                matured_yt_mint,
+                matured_yield_tokenizer,
+                matured_yield_token_program,
+                matured_signer_seeds,
+            )?;
+            invoke(
+                &spl_token_2022::instruction::burn(
+                    matured_yield_token_program.key,
+                    owner_matured_yt_ata.key,
+                    matured_yt_mint.key,
+                    owner.key,
+                    &[],
+                    matured_yt_balance,
+                )?,
+                &[
+                    owner_matured_yt_ata.clone(),
+                    matured_yt_mint.clone(),
+                    owner.clone(),
+                    matured_yield_token_program.clone(),
+                ],
+            )?;
+            Self::freeze_yt_account(
+                owner_matured_yt_ata,
+                matured_yt_mint,
+                matured_yield_tokenizer,
+                matured_yield_token_program,
+                matured_signer_seeds,
+            )?;
+        }
+
+        invoke_signed(
+            &spl_token::instruction::transfer(
+                token_program.key,
+                matured_lsu_vault.key,
+                lsu_vault.key,
+                matured_yield_tokenizer.key,
+                &[],
+                lsu_amount,
+            )?,
+            &[
+                matured_lsu_vault.clone(),
+                lsu_vault.clone(),
+                matured_yield_tokenizer.clone(),
+                token_program.clone(),
+            ],
+            &[matured_signer_seeds],
+        )?;
+        Self::collect_fee(
+            matured_lsu_vault,
+            matured_treasury,
+            matured_yield_tokenizer,
+            token_program,
+            matured_signer_seeds,
+            redemption_fee + yield_fee,
+        )?;
+
+        let maturity_date = yield_tokenizer_data.maturity_date.to_le_bytes();
+        let signer_seeds: &[&[u8]] = &[
+            crate::LSD_SEED,
+            yield_tokenizer_data.lsu_mint.as_ref(),
+            &maturity_date,
+            &[bump],
+        ];
+
+        invoke_signed(
+            &spl_token_2022::instruction::mint_to(
+                yield_token_program.key,
+                pt_mint.key,
+                owner_pt_ata.key,
+                yield_tokenizer.key,
+                &[],
+                principal,
+            )?,
+            &[
+                pt_mint.clone(),
+                owner_pt_ata.clone(),
+                yield_tokenizer.clone(),
+                yield_token_program.clone(),
+            ],
+            &[signer_seeds],
+        )?;
+
+        Self::thaw_yt_account(
+            owner_yt_ata,
+            yt_mint,
+            yield_tokenizer,
+            yield_token_program,
+            signer_seeds,
+        )?;
+        invoke_signed(
+            &spl_token_2022::instruction::mint_to(
+                yield_token_program.key,
+                yt_mint.key,
+                owner_yt_ata.key,
+                yield_tokenizer.key,
+                &[],
+                principal,
+            )?,
+            &[
+                yt_mint.clone(),
+                owner_yt_ata.clone(),
+                yield_tokenizer.clone(),
+                yield_token_program.clone(),
+            ],
+            &[signer_seeds],
+        )?;
+        Self::freeze_yt_account(
+            owner_yt_ata,
+            yt_mint,
+            yield_tokenizer,
+            yield_token_program,
+            signer_seeds,
+        )?;
+
+        matured_position
+            .serialize(&mut &mut owner_matured_position_account.data.borrow_mut()[..])?;
+        position.serialize(&mut &mut owner_position_account.data.borrow_mut()[..])?;
+        matured_data.serialize(&mut &mut matured_yield_tokenizer.data.borrow_mut()[..])?;
+        yield_tokenizer_data.serialize(&mut &mut yield_tokenizer.data.borrow_mut()[..])?;
+
+        YieldTokenizerEvent::Rollover {
+            matured_yield_tokenizer: *matured_yield_tokenizer.key,
+            yield_tokenizer: *yield_tokenizer.key,
+            user: *owner.key,
+            principal_in: amount,
+            lsu_amount,
+            principal_out: principal,
+            fee: redemption_fee + yield_fee,
+            exchange_rate: yield_tokenizer_data.last_exchange_rate,
+            timestamp: now,
+        }
+        .emit()?;
+
+        Ok(())
+    }
+
+    fn process_close_yield_tokenizer(
+        program_id: &Pubkey,
+        accounts: &[AccountInfo],
+    ) -> ProgramResult {
+        let CloseYieldTokenizerAccounts {
+            yield_tokenizer,
+            initializer,
+            pt_mint,
+            yt_mint,
+            lsu_vault,
+            treasury,
+            destination,
+            token_program,
+            yield_tokenizer_data,
+            ..
+        } = CloseYieldTokenizerAccounts::try_from((program_id, accounts))?;
+
+        let bump = yield_tokenizer_data.bump;
+
+        if clock::Clock::get()?.unix_timestamp < yield_tokenizer_data.maturity_date {
+            return Err(YieldTokenizerError::Immature.into());
+        }
+
+        // Once every PT is burned and the rate is frozen YT earns nothing more, the only claims
+        // on the vault left are the final yield of YT holders and of positions whose YT moved on.
+        // Anything over dust means someone hasn't claimed yet. YT is only burned by `Redeem` and
+        // `Rollover`, so it may still be outstanding.
+        let pt_supply = StateWithExtensions::<Mint>::unpack(&pt_mint.data.borrow())?
+            .base
+            .supply;
+        let yt_supply = StateWithExtensions::<Mint>::unpack(&yt_mint.data.borrow())?
+            .base
+            .supply;
+        let dust = Self::token_balance(lsu_vault)?;
+        if pt_supply != 0
+            || (yt_supply != 0 && !yield_tokenizer_data.is_rate_frozen())
+            || dust > MAX_CLOSE_DUST
+        {
+            return Err(YieldTokenizerError::TokenizerNotEmpty.into());
+        }
+
+        let maturity_date = yield_tokenizer_data.maturity_date.to_le_bytes();
+        let signer_seeds: &[&[u8]] = &[
+            crate::LSD_SEED,
+            yield_tokenizer_data.lsu_mint.as_ref(),
+            &maturity_date,
+            &[bump],
+        ];
+
+        // Dust goes to the treasury, then the treasury is emptied like `WithdrawFees` so both
+        // token accounts can be closed
+        Self::collect_fee(
+            lsu_vault,
+            treasury,
+            yield_tokenizer,
+            token_program,
+            signer_seeds,
+            dust,
+        )?;
+        let fees = Self::token_balance(treasury)?;
+        if fees > 0 {
+            invoke_signed(
+                &spl_token::instruction::transfer(
+                    token_program.key,
+                    treasury.key,
+                    destination.key,
+                    yield_tokenizer.key,
+                    &[],
+                    fees,
+                )?,
+                &[
+                    treasury.clone(),
+                    destination.clone(),
+                    yield_tokenizer.clone(),
+                    token_program.clone(),
+                ],
+                &[signer_seeds],
+            )?;
+        }
+
+        for token_account in [lsu_vault, treasury] {
+            invoke_signed(
+                &spl_token::instruction::close_account(
+                    token_program.key,
+                    token_account.key,
+                    initializer.key,
+                    yield_tokenizer.key,
+                    &[],
+                )?,
+                &[
+                    token_account.clone(),
+                    initializer.clone(),
+                    yield_tokenizer.clone(),
+                    token_program.clone(),
+                ],
+                &[signer_seeds],
+            )?;
+        }
+
+        // The PT/YT mints can't be closed, neither token program lets a mint without the
+        // close-authority extension go
+        let lamports = yield_tokenizer.lamports();
+        **initializer.lamports.borrow_mut() = initializer
+            .lamports()
+            .checked_add(lamports)
+            .ok_or(ProgramError::ArithmeticOverflow)?;
+        **yield_tokenizer.lamports.borrow_mut() = 0;
+        yield_tokenizer.realloc(0, false)?;
+        yield_tokenizer.assign(&system_program::id());
+
+        YieldTokenizerEvent::CloseYieldTokenizer {
+            yield_tokenizer: *yield_tokenizer.key,
+            initializer: *initializer.key,
+            swept_fees: fees,
+            timestamp: clock::Clock::get()?.unix_timestamp,
+        }
+        .emit()?;
+
+        Ok(())
+    }
+
+    fn process_migrate_state(program_id: &Pubkey, accounts: &[AccountInfo]) -> ProgramResult {
+        let MigrateStateAccounts {
+            payer,
+            account,
+            system_program,
+        } = MigrateStateAccounts::try_from((program_id, accounts))?;
+
+        let (discriminator, version, bump) = {
+            let data = account.data.borrow();
+            if data.starts_with(&YIELD_TOKENIZER_DISCRIMINATOR) {
+                // Already on the current layout, nothing to do
+                YieldTokenizerState::unpack(&data)?;
+                return Ok(());
+            }
+            if data.starts_with(&YIELD_POSITION_DISCRIMINATOR) {
+                YieldPosition::unpack(&data)?;
+                return Ok(());
+            }
+            if data.starts_with(&PT_POOL_DISCRIMINATOR) {
+                PtPool::unpack(&data)?;
+                return Ok(());
+            }
+            if data.starts_with(&PROTOCOL_CONFIG_DISCRIMINATOR) {
+                ProtocolConfig::unpack(&data)?;
+                return Ok(());
+            }
+
+            // Version 0 accounts have no header, they're told apart by the PDA their leading
+            // fields derive
+            let leading_key = |offset: usize| {
+                data.get(offset..offset + 32)
+                    .and_then(|bytes| Pubkey::try_from(bytes).ok())
+            };
+            let pool_addr = leading_key(0)
+                .map(|yield_tokenizer| find_pool_address(program_id, &yield_tokenizer));
+            if account.key == &get_config_address(program_id) {
+                (PROTOCOL_CONFIG_DISCRIMINATOR, PROTOCOL_CONFIG_VERSION, None)
+            } else if let Some((_, pool_bump)) =
+                pool_addr.filter(|(pool_addr, _)| account.key == pool_addr)
+            {
+                (PT_POOL_DISCRIMINATOR, PT_POOL_VERSION, Some(pool_bump))
+            } else if leading_key(0)
+                .zip(leading_key(32))
+                .is_some_and(|(yield_tokenizer, owner)| {
+                    account.key == &get_yield_position_address(program_id, &yield_tokenizer, &owner)
+                })
+            {
+                (YIELD_POSITION_DISCRIMINATOR, YIELD_POSITION_VERSION, None)
+            } else if data.len() == LEGACY_YIELD_TOKENIZER_LEN {
+                // Its address is checked against its LSU mint and maturity with the rest of the
+                // accounts it needs
+                drop(data);
+                return Self::migrate_yield_tokenizer(
+                    program_id,
+                    payer,
+                    account,
+                    system_program,
+                    &accounts[3..],
+                );
+            } else {
+                return Err(ProgramError::InvalidAccountData);
+            }
+        };
+
+        // The fields are unchanged, they only move behind the header. A pool gains its bump at
+        // the end.
+        let len = account.data_len();
+        let new_len = len + HEADER_LEN + usize::from(bump.is_some());
+        Self::resize_account(payer, account, system_program, new_len)?;
+        let mut data = account.data.borrow_mut();
+        data.copy_within(..len, HEADER_LEN);
+        data[..discriminator.len()].copy_from_slice(&discriminator);
+        data[HEADER_LEN - 1] = version;
+        if let Some(bump) = bump {
+            data[new_len - 1] = bump;
+        }
+
+        Ok(())
+    }
+
+    /// Upgrades a version 0 tokenizer. The series restarts its accounting at the migration: the
+    /// current rate becomes the initial rate with no yield accrued, which keeps its PT and YT
+    /// redeemable one for one against the LSU they were minted for. It's active, and the payer,
+    /// who funds its treasury and the larger account, becomes its initializer.
+    fn migrate_yield_tokenizer<'a>(
+        program_id: &Pubkey,
+        payer: &AccountInfo<'a>,
+        yield_tokenizer: &AccountInfo<'a>,
+        system_program: &AccountInfo<'a>,
+        accounts: &[AccountInfo<'a>],
+    ) -> ProgramResult {
+        let MigrateYieldTokenizerAccounts {
+            lsu_mint,
+            lsu_exchange_rate_account,
+            treasury,
+            token_program,
+            legacy_data,
+            supported_lsu,
+            bump,
+            pt_bump,
+            yt_bump,
+            treasury_bump,
+            ..
+        } = MigrateYieldTokenizerAccounts::try_from((program_id, yield_tokenizer, accounts))?;
+
+        let clock = clock::Clock::get()?;
+
+        if treasury.owner != token_program.key {
+            Self::create_treasury(
+                payer,
+                treasury,
+                yield_tokenizer,
+                lsu_mint,
+                token_program,
+                system_program,
+                treasury_bump,
+            )?;
+        }
+
+        let exchange_rate = supported_lsu.adapter.get_exchange_rate(
+            lsu_mint.key,
+            lsu_exchange_rate_account,
+            clock.epoch,
+            false,
+        )?;
+
+        let mut yield_tokenizer_data = YieldTokenizerState {
+            discriminator: YIELD_TOKENIZER_DISCRIMINATOR,
+            version: YIELD_TOKENIZER_VERSION,
+            pt: legacy_data.pt,
+            yt: legacy_data.yt,
+            maturity_date: legacy_data.maturity_date,
+            lsu_mint: legacy_data.lsu_mint,
+            lsu_vault: legacy_data.lsu_vault,
+            adapter: supported_lsu.adapter,
+            lsu_exchange_rate_account: supported_lsu.exchange_rate_account,
+            initial_exchange_rate: exchange_rate,
+            last_exchange_rate: exchange_rate,
+            maturity_exchange_rate: 0,
+            yield_index: 0,
+            pause_state: PauseState::Active,
+            initializer: *payer.key,
+            bump,
+            pt_bump,
+            yt_bump,
+            treasury_bump,
+        };
+        // A series migrated after its maturity settles at the rate it's migrated at
+        yield_tokenizer_data.update_exchange_rate(exchange_rate, clock.unix_timestamp);
+
+        Self::resize_account(
+            payer,
+            yield_tokenizer,
+            system_program,
+            borsh::object_length(&yield_tokenizer_data)?,
+        )?;
+        yield_tokenizer_data.serialize(&mut &mut yield_tokenizer.data.borrow_mut()[..])?;
+
+        Ok(())
+    }
+
+    /// Reallocates a migrated account, the payer covers the rent for the added fields
+    fn resize_account<'a>(
+        payer: &AccountInfo<'a>,
+        account: &AccountInfo<'a>,
+        system_program: &AccountInfo<'a>,
+        new_len: usize,
+    ) -> ProgramResult {
+        let required_lamports = rent::Rent::get()?
+            .minimum_balance(new_len)
+            .saturating_sub(account.lamports());
+        if required_lamports > 0 {
+            invoke(
+                &system_instruction::transfer(payer.key, account.key, required_lamports),
+                &[payer.clone(), account.clone(), system_program.clone()],
+            )?;
+        }
+        account.realloc(new_len, false)
+    }
+
+    /// Rejects the instruction if the protocol or the tokenizer is paused, or in emergency mode
+    /// unless it's an exit. Returns the more restrictive of the two states.
+    ///
+    /// The exits are `Redeem`, `RedeemFromPt` and the matured leg of `Rollover`, which settle at
+    /// the last observed rate, and `RemoveLiquidity`. Withdrawing liquidity is proportional to
+    /// the LP's share of both reserves and never reads the rate or the curve, so a broken adapter
+    /// can't skew it and LPs shouldn't be locked in a pool nobody can trade against.
+    fn check_pause_state(
+        config_data: &ProtocolConfig,
+        yield_tokenizer_data: &YieldTokenizerState,
+        is_exit: bool,
+    ) -> Result<PauseState, ProgramError> {
+        let pause_state = config_data
+            .pause_state
+            .max(yield_tokenizer_data.pause_state);
+        match pause_state {
+            PauseState::Active => Ok(pause_state),
+            PauseState::Emergency if is_exit => Ok(pause_state),
+            _ => Err(YieldTokenizerError::Paused.into()),
+        }
+    }
+
+    /// Reads the SOL value of one LSU, scaled by `EXCHANGE_RATE_PRECISION`, through the adapter
+    /// recorded in the tokenizer
+    fn get_exchange_rate(
+        yield_tokenizer_data: &YieldTokenizerState,
+        lsu_exchange_rate_account: &AccountInfo,
+        clock: &clock::Clock,
+        is_exit: bool,
+    ) -> Result<u64, ProgramError> {
+        Self::validate_lsu(yield_tokenizer_data, lsu_exchange_rate_account)?;
+        yield_tokenizer_data.adapter.get_exchange_rate(
+            &yield_tokenizer_data.lsu_mint,
+            lsu_exchange_rate_account,
+            clock.epoch,
+            is_exit,
+        )
+    }
+
+    /// Checks `lsu_exchange_rate_account` is the rate source the tokenizer was initialized with
+    fn validate_lsu(
+        yield_tokenizer_data: &YieldTokenizerState,
+        lsu_exchange_rate_account: &AccountInfo,
+    ) -> Result<(), ProgramError> {
+        if lsu_exchange_rate_account.key != &yield_tokenizer_data.lsu_exchange_rate_account {
+            return Err(YieldTokenizerError::InvalidExchangeRateAccount.into());
+        }
+        Ok(())
+    }
+
+    /// Loads the owner's yield position, creating it checkpointed at `yield_index` if it doesn't
+    /// exist yet
+    fn load_or_create_position<'a>(
+        program_id: &Pubkey,
+        payer: &AccountInfo<'a>,
+        owner: &Pubkey,
+        yield_tokenizer: &AccountInfo<'a>,
+        position: &AccountInfo<'a>,
+        system_program: &AccountInfo<'a>,
+        yield_index: u64,
+    ) -> Result<YieldPosition, ProgramError> {
+        // The program only ever writes a position at the PDA of the pair it records, so an
+        // existing one is checked by its contents and the bump search only runs on creation
+        if position.owner == program_id {
+            let position_data = YieldPosition::unpack(&position.data.borrow())?;
+            if &position_data.yield_tokenizer != yield_tokenizer.key
+                || &position_data.owner != owner
+            {
+                return Err(YieldTokenizerError::InvalidYieldPosition.into());
+            }
+            return Ok(position_data);
+        }
+
+        let (position_addr, bump) =
+            find_yield_position_address(program_id, yield_tokenizer.key, owner);
+        if position.key != &position_addr {
+            return Err(YieldTokenizerError::InvalidYieldPosition.into());
+        }
+
+        Self::create_pda_account(
+            program_id,
+            payer,
+            position,
+            system_program,
+            position_len()?,
+            &[
+                crate::POSITION_SEED,
+                yield_tokenizer.key.as_ref(),
+                owner.as_ref(),
+                &[bump],
+            ],
+        )?;
+
+        Ok(YieldPosition::new(
+            *yield_tokenizer.key,
+            *owner,
+            yield_index,
+        ))
+    }
+
+    /// Creates a tokenizer's treasury, the LSU token account at its treasury PDA
+    fn create_treasury<'a>(
+        payer: &AccountInfo<'a>,
+        treasury: &AccountInfo<'a>,
+        yield_tokenizer: &AccountInfo<'a>,
+        lsu_mint: &AccountInfo<'a>,
+        token_program: &AccountInfo<'a>,
+        system_program: &AccountInfo<'a>,
+        treasury_bump: u8,
+    ) -> ProgramResult {
+        Self::create_pda_account(
+            token_program.key,
+            payer,
+            treasury,
+            system_program,
+            spl_token::state::Account::LEN,
+            &[
+                crate::TREASURY_SEED,
+                yield_tokenizer.key.as_ref(),
+                &[treasury_bump],
+            ],
+        )?;
+        invoke(
+            &spl_token::instruction::initialize_account3(
+                token_program.key,
+                treasury.key,
+                lsu_mint.key,
+                yield_tokenizer.key,
+            )?,
+            &[treasury.clone(), lsu_mint.clone(), token_program.clone()],
+        )
+    }
+
+    /// Funds, allocates and assigns a PDA to `program_id`. Handles accounts that were pre-funded
+    /// by a third party.
+    fn create_pda_account<'a>(
+        program_id: &Pubkey,
+        payer: &AccountInfo<'a>,
+        account: &AccountInfo<'a>,
+        system_program: &AccountInfo<'a>,
+        size: usize,
+        signer_seeds: &[&[u8]],
+    ) -> Result<(), ProgramError> {
+        let rent = rent::Rent::get()?;
+        let required_lamports = rent
+            .minimum_balance(size)
+            .max(1)
+            .saturating_sub(account.lamports());
+
+        if required_lamports > 0 {
+            invoke(
+                &system_instruction::transfer(payer.key, account.key, required_lamports),
+                &[payer.clone(), account.clone(), system_program.clone()],
+            )?;
+        }
+
+        invoke_signed(
+            &system_instruction::allocate(account.key, size as u64),
+            &[account.clone(), system_program.clone()],
+            &[signer_seeds],
+        )?;
+
+        invoke_signed(
+            &system_instruction::assign(account.key, program_id),
+            &[account.clone(), system_program.clone()],
+            &[signer_seeds],
+        )?;
+
+        Ok(())
+    }
+
+    /// Creates a PT or YT mint with the tokenizer as mint authority. Token-2022 mints also get the
+    /// metadata-pointer and token-metadata extensions, pointing at the mint itself, so wallets can
+    /// show the series name.
+    #[allow(clippy::too_many_arguments)]
+    fn create_yield_token_mint<'a>(
+        payer: &AccountInfo<'a>,
+        mint: &AccountInfo<'a>,
+        yield_tokenizer: &AccountInfo<'a>,
+        token_program: &AccountInfo<'a>,
+        system_program: &AccountInfo<'a>,
+        mint_seeds: &[&[u8]],
+        signer_seeds: &[&[u8]],
+        freeze_authority: Option<&Pubkey>,
+        decimals: u8,
+        metadata: &TokenMetadata,
+    ) -> Result<(), ProgramError> {
+        let is_token_2022 = token_program.key == &spl_token_2022::id();
+
+        if is_token_2022 {
+            let mint_len = ExtensionType::try_calculate_account_len::<Mint>(&[
+                ExtensionType::MetadataPointer,
+            ])?;
+            Self::create_pda_account(
+                token_program.key,
+                payer,
+                mint,
+                system_program,
+                mint_len,
+                mint_seeds,
+            )?;
+
+            // Token-metadata reallocs the mint itself but it has to be funded for the final size
+            let required_lamports = rent::Rent::get()?
+                .minimum_balance(mint_len + metadata.tlv_size_of()?)
+                .saturating_sub(mint.lamports());
+            if required_lamports > 0 {
+                invoke(
+                    &system_instruction::transfer(payer.key, mint.key, required_lamports),
+                    &[payer.clone(), mint.clone(), system_program.clone()],
+                )?;
+            }
+
+            invoke(
+                &metadata_pointer::instruction::initialize(
+                    token_program.key,
+                    mint.key,
+                    Some(*yield_tokenizer.key),
+                    Some(*mint.key),
+                )?,
+                &[mint.clone(), token_program.clone()],
+            )?;
+        } else {
+            Self::create_pda_account(
+                token_program.key,
+                payer,
+                mint,
+                system_program,
+                spl_token::state::Mint::LEN,
+                mint_seeds,
+            )?;
+        }
+
+        invoke(
+            &spl_token_2022::instruction::initialize_mint2(
+                token_program.key,
+                mint.key,
+                yield_tokenizer.key,
+                freeze_authority,
+                decimals,
+            )?,
+            &[mint.clone(), token_program.clone()],
+        )?;
+
+        if is_token_2022 {
+            invoke_signed(
+                &spl_token_metadata_interface::instruction::initialize(
+                    token_program.key,
+                    mint.key,
+                    yield_tokenizer.key,
+                    mint.key,
+                    yield_tokenizer.key,
+                    metadata.name.clone(),
+                    metadata.symbol.clone(),
+                    metadata.uri.clone(),
+                ),
+                &[mint.clone(), yield_tokenizer.clone(), token_program.clone()],
+                &[signer_seeds],
+            )?;
+
+            for (key, value) in &metadata.additional_metadata {
+                invoke_signed(
+                    &spl_token_metadata_interface::instruction::update_field(
+                        token_program.key,
+                        mint.key,
+                        yield_tokenizer.key,
+                        Field::Key(key.clone()),
+                        value.clone(),
+                    ),
+                    &[mint.clone(), yield_tokenizer.clone(), token_program.clone()],
+                    &[signer_seeds],
+                )?;
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Metadata for a PT or YT mint, named after the series, e.g. `PT-mSOL-31MAR2027`
+    fn yield_token_metadata(
+        prefix: &str,
+        mint: &Pubkey,
+        yield_tokenizer: &Pubkey,
+        lsu_symbol: &str,
+        maturity_date: i64,
+    ) -> Result<TokenMetadata, ProgramError> {
+        Ok(TokenMetadata {
+            update_authority: Some(*yield_tokenizer).try_into()?,
+            mint: *mint,
+            name: format!(
+                "{}-{}-{}",
+                prefix,
+                lsu_symbol,
+                format_maturity_label(maturity_date)
+            ),
+            symbol: format!("{}-{}", prefix, lsu_symbol),
+            uri: String::new(),
+            additional_metadata: vec![
+                ("underlying".to_string(), lsu_symbol.to_string()),
+                ("maturity".to_string(), format_maturity_date(maturity_date)),
+            ],
+        })
+    }
+
+    /// YT accounts are kept frozen by the tokenizer so YT can only move through instructions that
+    /// settle yield first. Thaws `yt_account` so the program can move its YT.
+    fn thaw_yt_account<'a>(
+        yt_account: &AccountInfo<'a>,
+        yt_mint: &AccountInfo<'a>,
+        yield_tokenizer: &AccountInfo<'a>,
+        token_program: &AccountInfo<'a>,
+        signer_seeds: &[&[u8]],
+    ) -> Result<(), ProgramError> {
+        if !StateWithExtensions::<Account>::unpack(&yt_account.data.borrow())?
+            .base
+            .is_frozen()
+        {
+            return Ok(());
+        }
+
+        invoke_signed(
+            &spl_token_2022::instruction::thaw_account(
+                token_program.key,
+                yt_account.key,
+                yt_mint.key,
+                yield_tokenizer.key,
+                &[],
+            )?,
+            &[
+                yt_account.clone(),
+                yt_mint.clone(),
+                yield_tokenizer.clone(),
+                token_program.clone(),
+            ],
+            &[signer_seeds],
+        )
+    }
+
+    fn freeze_yt_account<'a>(
+        yt_account: &AccountInfo<'a>,
+        yt_mint: &AccountInfo<'a>,
+        yield_tokenizer: &AccountInfo<'a>,
+        token_program: &AccountInfo<'a>,
+        signer_seeds: &[&[u8]],
+    ) -> Result<(), ProgramError> {
+        invoke_signed(
+            &spl_token_2022::instruction::freeze_account(
+                token_program.key,
+                yt_account.key,
+                yt_mint.key,
+                yield_tokenizer.key,
+                &[],
+            )?,
+            &[
+                yt_account.clone(),
+                yt_mint.clone(),
+                yield_tokenizer.clone(),
+                token_program.clone(),
+            ],
+            &[signer_seeds],
+        )
+    }
+
+    /// Moves a protocol fee from the LSU vault into the tokenizer's treasury
+    fn collect_fee<'a>(
+        lsu_vault: &AccountInfo<'a>,
+        treasury: &AccountInfo<'a>,
+        yield_tokenizer: &AccountInfo<'a>,
+        token_program: &AccountInfo<'a>,
+        signer_seeds: &[&[u8]],
+        fee: u64,
+    ) -> Result<(), ProgramError> {
+        if fee == 0 {
+            return Ok(());
+        }
+
+        invoke_signed(
+            &spl_token::instruction::transfer(
+                token_program.key,
+                lsu_vault.key,
+                treasury.key,
+                yield_tokenizer.key,
+                &[],
+                fee,
+            )?,
+            &[
+                lsu_vault.clone(),
+                treasury.clone(),
+                yield_tokenizer.clone(),
+                token_program.clone(),
+            ],
+            &[signer_seeds],
+        )
+    }
+
+    /// Moves PT or YT, which may be SPL token or Token-2022, signed by `authority` or with
+    /// `signers_seeds` when it's a PDA. A user signer takes no seeds, an empty seed set isn't a
+    /// valid PDA and fails the transfer.
+    fn transfer_yield_token<'a>(
+        source: &AccountInfo<'a>,
+        mint: &AccountInfo<'a>,
+        destination: &AccountInfo<'a>,
+        authority: &AccountInfo<'a>,
+        token_program: &AccountInfo<'a>,
+        signers_seeds: &[&[&[u8]]],
+        amount: u64,
+    ) -> Result<(), ProgramError> {
+        let decimals = StateWithExtensions::<Mint>::unpack(&mint.data.borrow())?
+            .base
+            .decimals;
+        invoke_signed(
+            &spl_token_2022::instruction::transfer_checked(
+                token_program.key,
+                source.key,
+                mint.key,
+                destination.key,
+                authority.key,
+                &[],
+                amount,
+                decimals,
+            )?,
+            &[
+                source.clone(),
+                mint.clone(),
+                destination.clone(),
+                authority.clone(),
+                token_program.clone(),
+            ],
+            signers_seeds,
+        )
+    }
+
+    /// Balance of an SPL token or Token-2022 account, zero if the account hasn't been created
+    fn token_balance(token_account: &AccountInfo) -> Result<u64, ProgramError> {
+        if token_account.data_is_empty() {
+            return Ok(0);
+        }
+        Ok(
+            StateWithExtensions::<Account>::unpack(&token_account.data.borrow())?
+                .base
+                .amount,
+        )
     }
 }
@@ -0,0 +1,388 @@
+use {
+    crate::instruction,
+    solana_program::{instruction::Instruction, program_error::ProgramError, pubkey::Pubkey},
+    spl_associated_token_account::{
+        get_associated_token_address, get_associated_token_address_with_program_id,
+    },
+};
+
+/// Every address a client needs to use one series, derived from its LSU and maturity date for a
+/// single user. The exchange rate account comes from the LSU's entry in the protocol config and
+/// the PT/YT token program from the PT mint's owner, neither can be derived.
+///
+/// The user is part of the struct, so the `*_for` builders below take no user and act for
+/// whoever the accounts were resolved for. Use `for_user` to act for someone else.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct TokenizerAccounts {
+    /// Tokenizer program the series lives under, every PDA below is derived from it
+    pub program_id: Pubkey,
+    pub config: Pubkey,
+    pub lsu_mint: Pubkey,
+    pub maturity_date: i64,
+    pub lsu_exchange_rate_account: Pubkey,
+    /// `spl_token` or `spl_token_2022`, whichever the series was initialized with
+    pub yield_token_program: Pubkey,
+    pub yield_tokenizer: Pubkey,
+    pub pt_mint: Pubkey,
+    pub yt_mint: Pubkey,
+    /// Tokenizer's LSU ATA, holding the LSU backing PT and YT
+    pub lsu_vault: Pubkey,
+    /// LSU token account collecting the protocol fees
+    pub treasury: Pubkey,
+    /// PT/LSU pool and its vaults, there is no YT vault since YT is only ever minted and burned
+    pub pool: Pubkey,
+    pub lp_mint: Pubkey,
+    pub pool_lsu_vault: Pubkey,
+    pub pool_pt_vault: Pubkey,
+    pub user: Pubkey,
+    pub user_lsu_ata: Pubkey,
+    pub user_pt_ata: Pubkey,
+    pub user_yt_ata: Pubkey,
+    pub user_lp_ata: Pubkey,
+    pub user_position: Pubkey,
+}
+
+impl TokenizerAccounts {
+    pub fn new(
+        program_id: &Pubkey,
+        lsu_mint: &Pubkey,
+        maturity_date: i64,
+        user: &Pubkey,
+        lsu_exchange_rate_account: &Pubkey,
+        yield_token_program: &Pubkey,
+    ) -> Self {
+        let yield_tokenizer =
+            crate::get_yield_tokenizer_address(program_id, lsu_mint, maturity_date);
+        let pt_mint = crate::get_principal_token_address(program_id, &yield_tokenizer);
+        let yt_mint = crate::get_yield_token_address(program_id, &yield_tokenizer);
+        let pool = crate::get_pool_address(program_id, &yield_tokenizer);
+        let lp_mint = crate::get_lp_mint_address(program_id, &pool);
+
+        Self {
+            program_id: *program_id,
+            config: crate::get_config_address(program_id),
+            lsu_mint: *lsu_mint,
+            maturity_date,
+            lsu_exchange_rate_account: *lsu_exchange_rate_account,
+            yield_token_program: *yield_token_program,
+            yield_tokenizer,
+            pt_mint,
+            yt_mint,
+            lsu_vault: get_associated_token_address(&yield_tokenizer, lsu_mint),
+            treasury: crate::get_treasury_address(program_id, &yield_tokenizer),
+            pool,
+            lp_mint,
+            pool_lsu_vault: get_associated_token_address(&pool, lsu_mint),
+            pool_pt_vault: get_associated_token_address_with_program_id(
+                &pool,
+                &pt_mint,
+                yield_token_program,
+            ),
+            user: *user,
+            user_lsu_ata: get_associated_token_address(user, lsu_mint),
+            user_pt_ata: get_associated_token_address_with_program_id(
+                user,
+                &pt_mint,
+                yield_token_program,
+            ),
+            user_yt_ata: get_associated_token_address_with_program_id(
+                user,
+                &yt_mint,
+                yield_token_program,
+            ),
+            user_lp_ata: get_associated_token_address(user, &lp_mint),
+            user_position: crate::get_yield_position_address(program_id, &yield_tokenizer, user),
+        }
+    }
+
+    /// The same series resolved for another user
+    pub fn for_user(&self, user: &Pubkey) -> Self {
+        Self::new(
+            &self.program_id,
+            &self.lsu_mint,
+            self.maturity_date,
+            user,
+            &self.lsu_exchange_rate_account,
+            &self.yield_token_program,
+        )
+    }
+}
+
+/// Initialize the series with the user as authority and rent payer
+pub fn init_yield_tokenizer_for(accounts: &TokenizerAccounts) -> Result<Instruction, ProgramError> {
+    instruction::init_yield_tokenizer(
+        &accounts.program_id,
+        &accounts.user,
+        &accounts.config,
+        &accounts.yield_tokenizer,
+        &accounts.lsu_mint,
+        &accounts.lsu_exchange_rate_account,
+        &accounts.pt_mint,
+        &accounts.yt_mint,
+        &accounts.lsu_vault,
+        &accounts.treasury,
+        &accounts.yield_token_program,
+        accounts.maturity_date,
+    )
+}
+
+pub fn tokenize_yield_for(
+    accounts: &TokenizerAccounts,
+    amount: u64,
+) -> Result<Instruction, ProgramError> {
+    instruction::tokenize_yield(
+        &accounts.program_id,
+        &accounts.user,
+        &accounts.config,
+        &accounts.yield_tokenizer,
+        &accounts.lsu_mint,
+        &accounts.lsu_exchange_rate_account,
+        &accounts.pt_mint,
+        &accounts.yt_mint,
+        &accounts.lsu_vault,
+        &accounts.user_lsu_ata,
+        &accounts.user_pt_ata,
+        &accounts.user_yt_ata,
+        &accounts.user_position,
+        &accounts.yield_token_program,
+        amount,
+    )
+}
+
+pub fn redeem_for(accounts: &TokenizerAccounts, amount: u64) -> Result<Instruction, ProgramError> {
+    instruction::redeem(
+        &accounts.program_id,
+        &accounts.user,
+        &accounts.config,
+        &accounts.yield_tokenizer,
+        &accounts.lsu_mint,
+        &accounts.lsu_exchange_rate_account,
+        &accounts.pt_mint,
+        &accounts.yt_mint,
+        &accounts.lsu_vault,
+        &accounts.treasury,
+        &accounts.user_lsu_ata,
+        &accounts.user_pt_ata,
+        &accounts.user_yt_ata,
+        &accounts.user_position,
+        &accounts.yield_token_program,
+        amount,
+    )
+}
+
+pub fn redeem_from_pt_for(
+    accounts: &TokenizerAccounts,
+    amount: u64,
+) -> Result<Instruction, ProgramError> {
+    instruction::redeem_from_pt(
+        &accounts.program_id,
+        &accounts.user,
+        &accounts.config,
+        &accounts.yield_tokenizer,
+        &accounts.lsu_mint,
+        &accounts.lsu_exchange_rate_account,
+        &accounts.pt_mint,
+        &accounts.lsu_vault,
+        &accounts.treasury,
+        &accounts.user_lsu_ata,
+        &accounts.user_pt_ata,
+        &accounts.yield_token_program,
+        amount,
+    )
+}
+
+pub fn claim_yield_for(accounts: &TokenizerAccounts) -> Result<Instruction, ProgramError> {
+    instruction::claim_yield(
+        &accounts.program_id,
+        &accounts.user,
+        &accounts.config,
+        &accounts.yield_tokenizer,
+        &accounts.lsu_mint,
+        &accounts.lsu_exchange_rate_account,
+        &accounts.yt_mint,
+        &accounts.lsu_vault,
+        &accounts.treasury,
+        &accounts.user_lsu_ata,
+        &accounts.user_yt_ata,
+        &accounts.user_position,
+    )
+}
+
+/// Transfer `amount` YT from the user to `recipient`'s ATA
+pub fn transfer_yt_for(
+    accounts: &TokenizerAccounts,
+    recipient: &Pubkey,
+    amount: u64,
+) -> Result<Instruction, ProgramError> {
+    let recipient_accounts = accounts.for_user(recipient);
+    instruction::transfer_yt(
+        &accounts.program_id,
+        &accounts.user,
+        &accounts.config,
+        &accounts.yield_tokenizer,
+        &accounts.lsu_mint,
+        &accounts.lsu_exchange_rate_account,
+        &accounts.yt_mint,
+        &accounts.user_yt_ata,
+        &accounts.user_position,
+        recipient,
+        &recipient_accounts.user_yt_ata,
+        &recipient_accounts.user_position,
+        &accounts.yield_token_program,
+        amount,
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use {super::*, solana_program::instruction::AccountMeta};
+
+    const MATURITY_DATE: i64 = 1_798_675_200; // 2026-12-31
+
+    /// Resolved under a program other than `crate::id()`, as a program deployed at another
+    /// address would be
+    fn accounts(yield_token_program: &Pubkey) -> TokenizerAccounts {
+        TokenizerAccounts::new(
+            &Pubkey::new_unique(),
+            &Pubkey::new_unique(),
+            MATURITY_DATE,
+            &Pubkey::new_unique(),
+            &Pubkey::new_unique(),
+            yield_token_program,
+        )
+    }
+
+    #[test]
+    fn test_accounts_match_address_helpers() {
+        for yield_token_program in [spl_token::id(), spl_token_2022::id()] {
+            let accounts = accounts(&yield_token_program);
+            let lsu_mint = accounts.lsu_mint;
+            let user = accounts.user;
+
+            let yield_tokenizer =
+                crate::get_yield_tokenizer_address(&accounts.program_id, &lsu_mint, MATURITY_DATE);
+            let pt_mint =
+                crate::get_principal_token_address(&accounts.program_id, &yield_tokenizer);
+            let yt_mint = crate::get_yield_token_address(&accounts.program_id, &yield_tokenizer);
+            let pool = crate::get_pool_address(&accounts.program_id, &yield_tokenizer);
+            let lp_mint = crate::get_lp_mint_address(&accounts.program_id, &pool);
+
+            assert_eq!(
+                accounts.config,
+                crate::get_config_address(&accounts.program_id,)
+            );
+            assert_eq!(accounts.maturity_date, MATURITY_DATE);
+            assert_eq!(accounts.yield_token_program, yield_token_program);
+            assert_eq!(accounts.yield_tokenizer, yield_tokenizer);
+            assert_eq!(accounts.pt_mint, pt_mint);
+            assert_eq!(accounts.yt_mint, yt_mint);
+            assert_eq!(
+                accounts.lsu_vault,
+                get_associated_token_address(&yield_tokenizer, &lsu_mint)
+            );
+            assert_eq!(
+                accounts.treasury,
+                crate::get_treasury_address(&accounts.program_id, &yield_tokenizer)
+            );
+            assert_eq!(accounts.pool, pool);
+            assert_eq!(accounts.lp_mint, lp_mint);
+            // LSU and LP are always classic SPL tokens, PT and YT follow the series
+            assert_eq!(
+                accounts.pool_lsu_vault,
+                get_associated_token_address(&pool, &lsu_mint)
+            );
+            assert_eq!(
+                accounts.pool_pt_vault,
+                get_associated_token_address_with_program_id(&pool, &pt_mint, &yield_token_program)
+            );
+            assert_eq!(
+                accounts.user_lsu_ata,
+                get_associated_token_address(&user, &lsu_mint)
+            );
+            assert_eq!(
+                accounts.user_pt_ata,
+                get_associated_token_address_with_program_id(&user, &pt_mint, &yield_token_program)
+            );
+            assert_eq!(
+                accounts.user_yt_ata,
+                get_associated_token_address_with_program_id(&user, &yt_mint, &yield_token_program)
+            );
+            assert_eq!(
+                accounts.user_lp_ata,
+                get_associated_token_address(&user, &lp_mint)
+            );
+            assert_eq!(
+                accounts.user_position,
+                crate::get_yield_position_address(&accounts.program_id, &yield_tokenizer, &user)
+            );
+        }
+    }
+
+    #[test]
+    fn test_for_user_keeps_the_series() {
+        let accounts = accounts(&spl_token_2022::id());
+        let other = Pubkey::new_unique();
+        let other_accounts = accounts.for_user(&other);
+
+        assert_eq!(
+            other_accounts,
+            TokenizerAccounts::new(
+                &accounts.program_id,
+                &accounts.lsu_mint,
+                MATURITY_DATE,
+                &other,
+                &accounts.lsu_exchange_rate_account,
+                &accounts.yield_token_program,
+            )
+        );
+        assert_eq!(other_accounts.yield_tokenizer, accounts.yield_tokenizer);
+        assert_ne!(other_accounts.user_yt_ata, accounts.user_yt_ata);
+    }
+
+    #[test]
+    fn test_transfer_yt_for_accounts() {
+        for yield_token_program in [spl_token::id(), spl_token_2022::id()] {
+            let accounts = accounts(&yield_token_program);
+            let recipient = Pubkey::new_unique();
+            let ix = transfer_yt_for(&accounts, &recipient, 1).unwrap();
+
+            assert_eq!(ix.program_id, accounts.program_id);
+            assert_eq!(ix.accounts[0], AccountMeta::new(accounts.user, true));
+            assert_eq!(
+                ix.accounts[6],
+                AccountMeta::new(accounts.user_yt_ata, false)
+            );
+            assert_eq!(
+                ix.accounts[7],
+                AccountMeta::new(accounts.user_position, false)
+            );
+            assert_eq!(ix.accounts[8], AccountMeta::new(recipient, false));
+            assert_eq!(
+                ix.accounts[9],
+                AccountMeta::new(
+                    get_associated_token_address_with_program_id(
+                        &recipient,
+                        &accounts.yt_mint,
+                        &yield_token_program,
+                    ),
+                    false,
+                )
+            );
+            assert_eq!(
+                ix.accounts[10],
+                AccountMeta::new(
+                    crate::get_yield_position_address(
+                        &accounts.program_id,
+                        &accounts.yield_tokenizer,
+                        &recipient
+                    ),
+                    false,
+                )
+            );
+            assert_eq!(
+                ix.accounts[11],
+                AccountMeta::new_readonly(yield_token_program, false)
+            );
+        }
+    }
+}
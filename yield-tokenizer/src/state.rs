@@ -1,9 +1,53 @@
 use {
+    crate::{adapter::LsuAdapter, error::YieldTokenizerError, oracle::RateOracle},
     borsh::{BorshDeserialize, BorshSchema, BorshSerialize},
-    solana_program::pubkey::Pubkey,
+    solana_program::{program_error::ProgramError, pubkey::Pubkey},
 };
 
-pub const LSD_LEN: usize = 136;
+pub const MAX_SUPPORTED_LSUS: usize = 16;
+
+/// Longest LSU symbol the config accepts, e.g. `jitoSOL`
+pub const MAX_LSU_SYMBOL_LEN: usize = 10;
+
+pub const BPS_DENOMINATOR: u64 = 10_000;
+/// Upper bound on either protocol fee, 20%
+pub const MAX_FEE_BPS: u16 = 2_000;
+
+/// Fixed-point scale of the SOL per LSU exchange rates stored in `YieldTokenizerState`. Rates are
+/// lamports per LSU base unit, only their ratios enter the yield math so it holds for any LSU
+/// decimals as long as PT and YT mirror them.
+pub const EXCHANGE_RATE_PRECISION: u64 = 1_000_000_000_000;
+
+/// First bytes of every `YieldTokenizerState` account, so no other account can be read as one
+pub const YIELD_TOKENIZER_DISCRIMINATOR: [u8; 8] = *b"lysdtknz";
+/// Layout `YieldTokenizerState` accounts are written with, older accounts are upgraded by
+/// `MigrateState`
+///
+/// 0. No header, only the `LegacyYieldTokenizerState` fields
+/// 1. Discriminator and version, the exchange rate and pause state, and the PDA bumps
+pub const YIELD_TOKENIZER_VERSION: u8 = 1;
+
+/// Discriminator and version, the first bytes of every account the program writes
+pub const HEADER_LEN: usize = 9;
+
+pub const YIELD_POSITION_DISCRIMINATOR: [u8; 8] = *b"lysdposn";
+pub const PT_POOL_DISCRIMINATOR: [u8; 8] = *b"lysdpool";
+pub const PROTOCOL_CONFIG_DISCRIMINATOR: [u8; 8] = *b"lysdconf";
+/// Layouts `YieldPosition`, `PtPool` and `ProtocolConfig` accounts are written with, older
+/// accounts are upgraded by `MigrateState`
+///
+/// 0. No header, and no bump in a pool
+/// 1. Discriminator and version, and the pool's PDA bump
+pub const YIELD_POSITION_VERSION: u8 = 1;
+pub const PT_POOL_VERSION: u8 = 1;
+pub const PROTOCOL_CONFIG_VERSION: u8 = 1;
+
+/// Most LSU, in base units, a tokenizer's vault can still hold when it's closed. Rounding in the
+/// principal conversions leaves a few units behind once every PT and YT has been burned.
+pub const MAX_CLOSE_DUST: u64 = 10_000;
+
+/// Fixed-point scale of the yield index, which is denominated in LSU per YT
+pub const YIELD_INDEX_PRECISION: u64 = 1_000_000_000_000;
 
 // #[derive(BorshSchema, BorshSerialize, BorshDeserialize)]
 // struct YieldTokenWrapper {
@@ -26,11 +70,615 @@ pub const LSD_LEN: usize = 136;
 //     maturity_date: i64,
 // }
 
+/// Operating mode of the protocol or a single tokenizer, ordered from least to most restrictive
+#[derive(
+    Clone,
+    Copy,
+    Debug,
+    PartialEq,
+    Eq,
+    PartialOrd,
+    Ord,
+    BorshDeserialize,
+    BorshSerialize,
+    BorshSchema,
+)]
+pub enum PauseState {
+    Active,
+    /// Only exits, `Redeem`, `RedeemFromPt`, `RemoveLiquidity` and leaving a matured series
+    /// through `Rollover`, are allowed. Those that price LSU settle at the last observed exchange
+    /// rate instead of reading the adapter.
+    Emergency,
+    /// Every user instruction is rejected
+    Paused,
+}
+
 #[derive(BorshDeserialize, BorshSerialize, BorshSchema)]
 pub struct YieldTokenizerState {
+    /// `YIELD_TOKENIZER_DISCRIMINATOR`
+    pub discriminator: [u8; 8],
+    /// `YIELD_TOKENIZER_VERSION` once migrated
+    pub version: u8,
+    pub pt: Pubkey,
+    pub yt: Pubkey,
+    pub maturity_date: i64,
+    pub lsu_mint: Pubkey,
+    pub lsu_vault: Pubkey,
+    /// Copied from the protocol config at initialization so delisting an LSU doesn't strand
+    /// existing series
+    pub adapter: LsuAdapter,
+    pub lsu_exchange_rate_account: Pubkey,
+    /// SOL per LSU when the tokenizer was initialized
+    pub initial_exchange_rate: u64,
+    /// SOL per LSU at the last observation
+    pub last_exchange_rate: u64,
+    /// SOL per LSU at the first observation on or after maturity, zero until then
+    pub maturity_exchange_rate: u64,
+    /// Cumulative LSU yield earned per YT since initialization, `1 - initial / rate`. It never
+    /// decreases so yield that has already been paid out can't be clawed back by a rate drop.
+    pub yield_index: u64,
+    pub pause_state: PauseState,
+    /// Paid the rent for the tokenizer, its vault and treasury, and gets it back when the series
+    /// is closed
+    pub initializer: Pubkey,
+    /// Bumps of the tokenizer, PT mint, YT mint and treasury PDAs, so their addresses can be
+    /// checked with `create_program_address` instead of searched for
+    pub bump: u8,
+    pub pt_bump: u8,
+    pub yt_bump: u8,
+    pub treasury_bump: u8,
+}
+
+impl YieldTokenizerState {
+    /// Deserializes a tokenizer account after checking its discriminator and version
+    pub fn unpack(data: &[u8]) -> Result<Self, ProgramError> {
+        check_header(
+            data,
+            &YIELD_TOKENIZER_DISCRIMINATOR,
+            YIELD_TOKENIZER_VERSION,
+        )?;
+        Ok(Self::try_from_slice(data)?)
+    }
+
+    /// Whether `data` holds a tokenizer written with a header, of any version since 1. Version 0
+    /// accounts have no discriminator and are only recognized by their owner.
+    pub fn is_initialized(data: &[u8]) -> bool {
+        data.len() >= HEADER_LEN
+            && data.starts_with(&YIELD_TOKENIZER_DISCRIMINATOR)
+            && data[HEADER_LEN - 1] != 0
+    }
+
+    /// Records a freshly observed exchange rate, freezing it as the maturity rate once
+    /// `maturity_date` has been reached. Observations after the freeze are ignored.
+    pub fn update_exchange_rate(&mut self, exchange_rate: u64, now: i64) {
+        if self.is_rate_frozen() {
+            return;
+        }
+        self.last_exchange_rate = exchange_rate;
+        if now >= self.maturity_date {
+            self.maturity_exchange_rate = exchange_rate;
+        }
+
+        if exchange_rate > self.initial_exchange_rate {
+            let yield_index = (exchange_rate - self.initial_exchange_rate) as u128
+                * YIELD_INDEX_PRECISION as u128
+                / exchange_rate as u128;
+            self.yield_index = self.yield_index.max(yield_index as u64);
+        }
+    }
+
+    pub fn is_rate_frozen(&self) -> bool {
+        self.maturity_exchange_rate != 0
+    }
+
+    /// Converts an LSU amount into PT/YT, which are denominated in the SOL value of one LSU at
+    /// initialization. Rounds down.
+    pub fn lsu_to_principal(&self, lsu_amount: u64) -> Result<u64, ProgramError> {
+        let principal = lsu_amount as u128 * YIELD_INDEX_PRECISION as u128
+            / (YIELD_INDEX_PRECISION - self.yield_index) as u128;
+        u64::try_from(principal).map_err(|_| ProgramError::ArithmeticOverflow)
+    }
+
+    /// Converts a PT/YT amount into the LSU currently backing its principal. Once the rate is
+    /// frozen this is the maturity redemption value, `principal * initial / maturity`. Rounds
+    /// down.
+    pub fn principal_to_lsu(&self, principal: u64) -> Result<u64, ProgramError> {
+        let lsu_amount = principal as u128 * (YIELD_INDEX_PRECISION - self.yield_index) as u128
+            / YIELD_INDEX_PRECISION as u128;
+        u64::try_from(lsu_amount).map_err(|_| ProgramError::ArithmeticOverflow)
+    }
+
+    /// Like `principal_to_lsu` but rounds up, the LSU that has to be tokenized to mint `principal`
+    pub fn principal_to_lsu_up(&self, principal: u64) -> Result<u64, ProgramError> {
+        let lsu_amount = (principal as u128 * (YIELD_INDEX_PRECISION - self.yield_index) as u128)
+            .div_ceil(YIELD_INDEX_PRECISION as u128);
+        u64::try_from(lsu_amount).map_err(|_| ProgramError::ArithmeticOverflow)
+    }
+}
+
+/// The whole of a version 0 tokenizer, as the first deployment wrote it. Read only by
+/// `MigrateState`, which fills in the fields that came later.
+#[derive(BorshDeserialize, BorshSerialize, BorshSchema)]
+pub struct LegacyYieldTokenizerState {
     pub pt: Pubkey,
     pub yt: Pubkey,
     pub maturity_date: i64,
     pub lsu_mint: Pubkey,
     pub lsu_vault: Pubkey,
 }
+
+/// Size of a version 0 tokenizer account
+pub const LEGACY_YIELD_TOKENIZER_LEN: usize = 136;
+
+#[derive(BorshDeserialize, BorshSerialize, BorshSchema)]
+pub struct YieldPosition {
+    /// `YIELD_POSITION_DISCRIMINATOR`
+    pub discriminator: [u8; 8],
+    /// `YIELD_POSITION_VERSION` once migrated
+    pub version: u8,
+    pub yield_tokenizer: Pubkey,
+    pub owner: Pubkey,
+    /// Tokenizer yield index at the last settlement
+    pub yield_index: u64,
+    /// LSU owed to the owner that hasn't been claimed yet
+    pub accrued_yield: u64,
+}
+
+impl YieldPosition {
+    /// Position checkpointed at `yield_index` with nothing accrued
+    pub fn new(yield_tokenizer: Pubkey, owner: Pubkey, yield_index: u64) -> Self {
+        Self {
+            discriminator: YIELD_POSITION_DISCRIMINATOR,
+            version: YIELD_POSITION_VERSION,
+            yield_tokenizer,
+            owner,
+            yield_index,
+            accrued_yield: 0,
+        }
+    }
+
+    /// Deserializes a position account after checking its discriminator and version
+    pub fn unpack(data: &[u8]) -> Result<Self, ProgramError> {
+        check_header(data, &YIELD_POSITION_DISCRIMINATOR, YIELD_POSITION_VERSION)?;
+        Ok(Self::try_from_slice(data)?)
+    }
+
+    /// Credits the yield earned by `yt_balance` since the last checkpoint and moves the checkpoint
+    /// to `yield_index`. Rounds down.
+    pub fn settle(&mut self, yt_balance: u64, yield_index: u64) -> Result<(), ProgramError> {
+        let owed = yt_balance as u128 * yield_index.saturating_sub(self.yield_index) as u128
+            / YIELD_INDEX_PRECISION as u128;
+        self.accrued_yield = u64::try_from(owed)
+            .ok()
+            .and_then(|owed| self.accrued_yield.checked_add(owed))
+            .ok_or(ProgramError::ArithmeticOverflow)?;
+        self.yield_index = yield_index;
+        Ok(())
+    }
+}
+
+/// PT/LSU pool for a single tokenizer, see `amm::Curve`. Reserves are tracked here rather than
+/// read from the vaults so tokens sent to them directly don't move the price.
+#[derive(BorshDeserialize, BorshSerialize, BorshSchema)]
+pub struct PtPool {
+    /// `PT_POOL_DISCRIMINATOR`
+    pub discriminator: [u8; 8],
+    /// `PT_POOL_VERSION` once migrated
+    pub version: u8,
+    pub yield_tokenizer: Pubkey,
+    pub lp_mint: Pubkey,
+    pub lsu_vault: Pubkey,
+    pub pt_vault: Pubkey,
+    /// Rate scalar one year before maturity, scaled by 1e18. The curve's scalar is
+    /// `scalar_root / years_to_maturity`, so it tightens as maturity approaches.
+    pub scalar_root: i128,
+    /// Exchange rate, PT per unit of asset, the first deposit is priced at for an even pool
+    pub initial_anchor: i128,
+    /// Log of the swap fee multiplier one year before maturity, the fee decays with the time left
+    pub ln_fee_rate_root: i128,
+    /// `ln(1 + implied_rate)` the pool was left at by the last trade
+    pub last_ln_implied_rate: i128,
+    pub pt_reserve: u64,
+    pub lsu_reserve: u64,
+    /// LP supply including the `amm::MINIMUM_LIQUIDITY` locked by the first deposit
+    pub lp_supply: u64,
+    /// TWAP of `last_ln_implied_rate`, see `oracle::get_twap_pt_price`
+    pub oracle: RateOracle,
+    /// Bump of the pool PDA, so its address can be checked with `create_program_address`
+    pub bump: u8,
+}
+
+impl PtPool {
+    /// Empty pool, priced by its first deposit
+    #[allow(clippy::too_many_arguments)]
+    pub fn new(
+        yield_tokenizer: Pubkey,
+        lp_mint: Pubkey,
+        lsu_vault: Pubkey,
+        pt_vault: Pubkey,
+        scalar_root: i128,
+        initial_anchor: i128,
+        ln_fee_rate_root: i128,
+        bump: u8,
+    ) -> Self {
+        Self {
+            discriminator: PT_POOL_DISCRIMINATOR,
+            version: PT_POOL_VERSION,
+            yield_tokenizer,
+            lp_mint,
+            lsu_vault,
+            pt_vault,
+            scalar_root,
+            initial_anchor,
+            ln_fee_rate_root,
+            last_ln_implied_rate: 0,
+            pt_reserve: 0,
+            lsu_reserve: 0,
+            lp_supply: 0,
+            oracle: RateOracle::default(),
+            bump,
+        }
+    }
+
+    /// Deserializes a pool account after checking its discriminator and version
+    pub fn unpack(data: &[u8]) -> Result<Self, ProgramError> {
+        check_header(data, &PT_POOL_DISCRIMINATOR, PT_POOL_VERSION)?;
+        Ok(Self::try_from_slice(data)?)
+    }
+}
+
+/// An LSU that tokenizers can be initialized for
+#[derive(Clone, Debug, PartialEq, Eq, BorshDeserialize, BorshSerialize, BorshSchema)]
+pub struct SupportedLsu {
+    pub mint: Pubkey,
+    pub adapter: LsuAdapter,
+    /// Marinade state or stake pool account the exchange rate is read from
+    pub exchange_rate_account: Pubkey,
+    /// Used in the Token-2022 PT/YT metadata, e.g. `PT-mSOL-31MAR2027`
+    pub symbol: String,
+}
+
+/// Singleton protocol configuration, allocated at `config_len` so the LSU list can grow in place
+#[derive(BorshDeserialize, BorshSerialize, BorshSchema)]
+pub struct ProtocolConfig {
+    /// `PROTOCOL_CONFIG_DISCRIMINATOR`
+    pub discriminator: [u8; 8],
+    /// `PROTOCOL_CONFIG_VERSION` once migrated
+    pub version: u8,
+    pub authority: Pubkey,
+    /// Fee taken from yield paid to YT holders, in basis points
+    pub yield_fee_bps: u16,
+    /// Fee taken from LSU paid out for PT after maturity, in basis points
+    pub redemption_fee_bps: u16,
+    /// Applies to every tokenizer on top of its own pause state
+    pub pause_state: PauseState,
+    pub lsus: Vec<SupportedLsu>,
+}
+
+impl ProtocolConfig {
+    /// Active config with no fees and no LSUs
+    pub fn new(authority: Pubkey) -> Self {
+        Self {
+            discriminator: PROTOCOL_CONFIG_DISCRIMINATOR,
+            version: PROTOCOL_CONFIG_VERSION,
+            authority,
+            yield_fee_bps: 0,
+            redemption_fee_bps: 0,
+            pause_state: PauseState::Active,
+            lsus: Vec::new(),
+        }
+    }
+
+    /// Deserializes the config account after checking its discriminator and version. The account
+    /// is allocated larger than its contents, so it's read without the trailing bytes check.
+    pub fn unpack(data: &[u8]) -> Result<Self, ProgramError> {
+        check_header(
+            data,
+            &PROTOCOL_CONFIG_DISCRIMINATOR,
+            PROTOCOL_CONFIG_VERSION,
+        )?;
+        Ok(Self::deserialize(&mut &data[..])?)
+    }
+
+    pub fn yield_fee(&self, yield_amount: u64) -> u64 {
+        fee(yield_amount, self.yield_fee_bps)
+    }
+
+    pub fn redemption_fee(&self, lsu_amount: u64) -> u64 {
+        fee(lsu_amount, self.redemption_fee_bps)
+    }
+
+    pub fn get_lsu(&self, lsu_mint: &Pubkey) -> Option<&SupportedLsu> {
+        self.lsus.iter().find(|lsu| &lsu.mint == lsu_mint)
+    }
+}
+
+/// Size of a position account
+pub fn position_len() -> Result<usize, ProgramError> {
+    Ok(borsh::object_length(&YieldPosition::new(
+        Pubkey::default(),
+        Pubkey::default(),
+        0,
+    ))?)
+}
+
+/// Size of a pool account, its oracle always holds `ORACLE_CARDINALITY` observations
+pub fn pool_len() -> Result<usize, ProgramError> {
+    Ok(borsh::object_length(&PtPool::new(
+        Pubkey::default(),
+        Pubkey::default(),
+        Pubkey::default(),
+        Pubkey::default(),
+        0,
+        0,
+        0,
+        0,
+    ))?)
+}
+
+/// Size of the config account, enough for `MAX_SUPPORTED_LSUS` LSUs with the longest symbols
+pub fn config_len() -> Result<usize, ProgramError> {
+    let mut config = ProtocolConfig::new(Pubkey::default());
+    config.lsus = vec![
+        SupportedLsu {
+            mint: Pubkey::default(),
+            adapter: LsuAdapter::Marinade,
+            exchange_rate_account: Pubkey::default(),
+            symbol: "_".repeat(MAX_LSU_SYMBOL_LEN),
+        };
+        MAX_SUPPORTED_LSUS
+    ];
+    Ok(borsh::object_length(&config)?)
+}
+
+/// Checks `data` starts with `discriminator` and the current `version`
+fn check_header(data: &[u8], discriminator: &[u8; 8], version: u8) -> Result<(), ProgramError> {
+    if data.len() < HEADER_LEN || data[..discriminator.len()] != *discriminator {
+        return Err(ProgramError::InvalidAccountData);
+    }
+    if data[discriminator.len()] != version {
+        return Err(YieldTokenizerError::OutdatedAccountVersion.into());
+    }
+    Ok(())
+}
+
+/// Rounds down, so dust amounts are fee free
+fn fee(amount: u64, fee_bps: u16) -> u64 {
+    (amount as u128 * fee_bps as u128 / BPS_DENOMINATOR as u128) as u64
+}
+
+#[cfg(test)]
+mod tests {
+    use {super::*, crate::oracle::ORACLE_CARDINALITY};
+
+    fn tokenizer(maturity_date: i64) -> YieldTokenizerState {
+        YieldTokenizerState {
+            discriminator: YIELD_TOKENIZER_DISCRIMINATOR,
+            version: YIELD_TOKENIZER_VERSION,
+            pt: Pubkey::new_unique(),
+            yt: Pubkey::new_unique(),
+            maturity_date,
+            lsu_mint: Pubkey::new_unique(),
+            lsu_vault: Pubkey::new_unique(),
+            adapter: LsuAdapter::Marinade,
+            lsu_exchange_rate_account: Pubkey::new_unique(),
+            initial_exchange_rate: EXCHANGE_RATE_PRECISION,
+            last_exchange_rate: EXCHANGE_RATE_PRECISION,
+            maturity_exchange_rate: 0,
+            yield_index: 0,
+            pause_state: PauseState::Active,
+            initializer: Pubkey::new_unique(),
+            bump: 0,
+            pt_bump: 0,
+            yt_bump: 0,
+            treasury_bump: 0,
+        }
+    }
+
+    #[test]
+    fn test_yield_index_keeps_its_max() {
+        let mut yield_tokenizer = tokenizer(1_000);
+
+        yield_tokenizer.update_exchange_rate(EXCHANGE_RATE_PRECISION / 10 * 11, 10);
+        assert_eq!(
+            yield_tokenizer.last_exchange_rate,
+            EXCHANGE_RATE_PRECISION / 10 * 11
+        );
+        // 1 - 1 / 1.1
+        assert_eq!(yield_tokenizer.yield_index, 90_909_090_909);
+
+        // A drop, even below the initial rate, is recorded but leaves the index where it was
+        for exchange_rate in [
+            EXCHANGE_RATE_PRECISION / 20 * 21,
+            EXCHANGE_RATE_PRECISION / 10 * 9,
+        ] {
+            yield_tokenizer.update_exchange_rate(exchange_rate, 20);
+            assert_eq!(yield_tokenizer.last_exchange_rate, exchange_rate);
+            assert_eq!(yield_tokenizer.yield_index, 90_909_090_909);
+        }
+
+        // 1 - 1 / 1.2
+        yield_tokenizer.update_exchange_rate(EXCHANGE_RATE_PRECISION / 10 * 12, 30);
+        assert_eq!(yield_tokenizer.yield_index, 166_666_666_666);
+        assert!(!yield_tokenizer.is_rate_frozen());
+    }
+
+    #[test]
+    fn test_rate_freezes_at_maturity() {
+        let mut yield_tokenizer = tokenizer(1_000);
+
+        yield_tokenizer.update_exchange_rate(EXCHANGE_RATE_PRECISION / 10 * 11, 999);
+        assert!(!yield_tokenizer.is_rate_frozen());
+
+        let maturity_rate = EXCHANGE_RATE_PRECISION / 10 * 12;
+        yield_tokenizer.update_exchange_rate(maturity_rate, 1_000);
+        assert!(yield_tokenizer.is_rate_frozen());
+        assert_eq!(yield_tokenizer.maturity_exchange_rate, maturity_rate);
+        assert_eq!(yield_tokenizer.yield_index, 166_666_666_666);
+
+        // Later observations, up or down, change nothing
+        for exchange_rate in [EXCHANGE_RATE_PRECISION * 2, EXCHANGE_RATE_PRECISION] {
+            yield_tokenizer.update_exchange_rate(exchange_rate, 2_000);
+            assert_eq!(yield_tokenizer.last_exchange_rate, maturity_rate);
+            assert_eq!(yield_tokenizer.maturity_exchange_rate, maturity_rate);
+            assert_eq!(yield_tokenizer.yield_index, 166_666_666_666);
+        }
+    }
+
+    #[test]
+    fn test_principal_conversions_round_against_the_user() {
+        let mut yield_tokenizer = tokenizer(1_000);
+        assert_eq!(yield_tokenizer.lsu_to_principal(12_345).unwrap(), 12_345);
+        assert_eq!(yield_tokenizer.principal_to_lsu(12_345).unwrap(), 12_345);
+        assert_eq!(yield_tokenizer.principal_to_lsu_up(12_345).unwrap(), 12_345);
+
+        // An LSU is worth three times its initial SOL value, the index is 2/3 rounded down
+        yield_tokenizer.update_exchange_rate(EXCHANGE_RATE_PRECISION * 3, 10);
+        assert_eq!(yield_tokenizer.yield_index, 666_666_666_666);
+        assert_eq!(yield_tokenizer.lsu_to_principal(1).unwrap(), 2);
+        assert_eq!(yield_tokenizer.lsu_to_principal(1_000).unwrap(), 2_999);
+        assert_eq!(yield_tokenizer.principal_to_lsu(1).unwrap(), 0);
+        assert_eq!(yield_tokenizer.principal_to_lsu_up(1).unwrap(), 1);
+        assert_eq!(yield_tokenizer.principal_to_lsu(3_000).unwrap(), 1_000);
+        assert_eq!(yield_tokenizer.principal_to_lsu_up(3_000).unwrap(), 1_001);
+
+        // Round trips never hand out more than went in
+        for amount in [1, 2, 3, 7, 1_000, 999_999_999, 123_456_789_012] {
+            let principal = yield_tokenizer.lsu_to_principal(amount).unwrap();
+            assert!(yield_tokenizer.principal_to_lsu(principal).unwrap() <= amount);
+            let lsu_amount = yield_tokenizer.principal_to_lsu_up(amount).unwrap();
+            assert!(yield_tokenizer.lsu_to_principal(lsu_amount).unwrap() >= amount);
+        }
+    }
+
+    #[test]
+    fn test_principal_conversion_overflow() {
+        let mut yield_tokenizer = tokenizer(1_000);
+        yield_tokenizer.update_exchange_rate(EXCHANGE_RATE_PRECISION * 2, 10);
+
+        assert_eq!(
+            yield_tokenizer.lsu_to_principal(u64::MAX).err(),
+            Some(ProgramError::ArithmeticOverflow)
+        );
+        assert_eq!(
+            yield_tokenizer.lsu_to_principal(u64::MAX / 2).unwrap(),
+            u64::MAX - 1
+        );
+        // PT is never worth more than one LSU, so the other direction can't overflow
+        assert_eq!(
+            yield_tokenizer.principal_to_lsu(u64::MAX).unwrap(),
+            u64::MAX / 2
+        );
+        assert_eq!(
+            yield_tokenizer.principal_to_lsu_up(u64::MAX).unwrap(),
+            u64::MAX / 2 + 1
+        );
+    }
+
+    #[test]
+    fn test_account_lens() {
+        // Four keys and the maturity
+        let legacy = LegacyYieldTokenizerState {
+            pt: Pubkey::default(),
+            yt: Pubkey::default(),
+            maturity_date: 0,
+            lsu_mint: Pubkey::default(),
+            lsu_vault: Pubkey::default(),
+        };
+        assert_eq!(
+            borsh::object_length(&legacy).unwrap(),
+            LEGACY_YIELD_TOKENIZER_LEN
+        );
+        // Header, two keys and two u64
+        assert_eq!(position_len().unwrap(), HEADER_LEN + 80);
+        // Header, four keys, four i128, three u64, the oracle's cumulative, last update, index and
+        // length prefixed observations, and the bump
+        assert_eq!(
+            pool_len().unwrap(),
+            HEADER_LEN
+                + 32 * 4
+                + 16 * 4
+                + 8 * 3
+                + 16
+                + 8
+                + 2
+                + 4
+                + ORACLE_CARDINALITY * (8 + 16)
+                + 1
+        );
+        // Header, authority, fees, pause state and length prefixed LSUs
+        assert_eq!(
+            config_len().unwrap(),
+            HEADER_LEN + 32 + 2 + 2 + 1 + 4 + MAX_SUPPORTED_LSUS * (65 + 4 + MAX_LSU_SYMBOL_LEN)
+        );
+    }
+
+    #[test]
+    fn test_unpack_checks_header() {
+        let position = YieldPosition::new(Pubkey::new_unique(), Pubkey::new_unique(), 7);
+        let mut data = borsh::to_vec(&position).unwrap();
+        let unpacked = YieldPosition::unpack(&data).unwrap();
+        assert_eq!(unpacked.owner, position.owner);
+        assert_eq!(unpacked.yield_index, 7);
+
+        // Another account type with the same length
+        assert_eq!(
+            PtPool::unpack(&data).err(),
+            Some(ProgramError::InvalidAccountData)
+        );
+        data[..8].copy_from_slice(&PT_POOL_DISCRIMINATOR);
+        assert!(PtPool::unpack(&data).is_err());
+
+        data[..8].copy_from_slice(&YIELD_POSITION_DISCRIMINATOR);
+        data[8] = 0;
+        assert_eq!(
+            YieldPosition::unpack(&data).err(),
+            Some(YieldTokenizerError::OutdatedAccountVersion.into())
+        );
+        assert_eq!(
+            YieldPosition::unpack(&data[..HEADER_LEN - 1]).err(),
+            Some(ProgramError::InvalidAccountData)
+        );
+    }
+
+    #[test]
+    fn test_is_initialized() {
+        let mut data = vec![0; HEADER_LEN + 32];
+        assert!(!YieldTokenizerState::is_initialized(&data));
+        // Leftover bytes aren't a tokenizer
+        data[HEADER_LEN..].fill(0xff);
+        assert!(!YieldTokenizerState::is_initialized(&data));
+
+        data[..8].copy_from_slice(&YIELD_TOKENIZER_DISCRIMINATOR);
+        assert!(!YieldTokenizerState::is_initialized(&data));
+        for version in 1..=YIELD_TOKENIZER_VERSION {
+            data[8] = version;
+            assert!(YieldTokenizerState::is_initialized(&data));
+        }
+        assert!(!YieldTokenizerState::is_initialized(&data[..8]));
+
+        data[..8].copy_from_slice(&YIELD_POSITION_DISCRIMINATOR);
+        assert!(!YieldTokenizerState::is_initialized(&data));
+    }
+
+    #[test]
+    fn test_config_unpack_ignores_unused_space() {
+        let mut config = ProtocolConfig::new(Pubkey::new_unique());
+        config.lsus.push(SupportedLsu {
+            mint: Pubkey::new_unique(),
+            adapter: LsuAdapter::SplStakePool,
+            exchange_rate_account: Pubkey::new_unique(),
+            symbol: "jitoSOL".to_string(),
+        });
+        let mut data = vec![0; config_len().unwrap()];
+        config.serialize(&mut &mut data[..]).unwrap();
+
+        let unpacked = ProtocolConfig::unpack(&data).unwrap();
+        assert_eq!(unpacked.authority, config.authority);
+        assert_eq!(unpacked.lsus, config.lsus);
+        assert_eq!(
+            ProtocolConfig::unpack(&[0; 64]).err(),
+            Some(ProgramError::InvalidAccountData)
+        );
+    }
+}
@@ -0,0 +1,80 @@
+mod helpers;
+
+use {
+    helpers::*,
+    solana_program_test::tokio,
+    yield_tokenizer::{resolver, state::YIELD_INDEX_PRECISION},
+};
+
+#[tokio::test]
+async fn test_claim_yield_after_rate_increase() {
+    let mut series = TestSeries::start().await;
+    let (user, accounts) = series.new_tokenizer(10 * LSU).await;
+
+    // At 1 SOL per LSU the principal is the LSU amount
+    assert_eq!(series.token_balance(&accounts.user_lsu_ata).await, 0);
+    assert_eq!(series.token_balance(&accounts.user_pt_ata).await, 10 * LSU);
+    assert_eq!(series.token_balance(&accounts.user_yt_ata).await, 10 * LSU);
+    assert!(series
+        .token_account(&accounts.user_yt_ata)
+        .await
+        .is_frozen());
+    assert_eq!(series.position(&accounts).await.yield_index, 0);
+
+    // 10 SOL of principal is 8 LSU at 1.25, the other 2 LSU are the YT's yield
+    series.set_exchange_rate(rate(5, 4)).await;
+    let claim = resolver::claim_yield_for(&accounts).unwrap();
+    series
+        .process(std::slice::from_ref(&claim), &[&user])
+        .await
+        .unwrap();
+
+    assert_eq!(series.token_balance(&accounts.user_lsu_ata).await, 2 * LSU);
+    assert_eq!(series.token_balance(&accounts.lsu_vault).await, 8 * LSU);
+    assert_eq!(series.token_balance(&accounts.user_yt_ata).await, 10 * LSU);
+    let yield_tokenizer = series.yield_tokenizer().await;
+    assert_eq!(yield_tokenizer.last_exchange_rate, rate(5, 4));
+    assert_eq!(yield_tokenizer.yield_index, YIELD_INDEX_PRECISION / 5);
+    let position = series.position(&accounts).await;
+    assert_eq!(position.yield_index, YIELD_INDEX_PRECISION / 5);
+    assert_eq!(position.accrued_yield, 0);
+
+    // Nothing more has accrued
+    series.process(&[claim], &[&user]).await.unwrap();
+    assert_eq!(series.token_balance(&accounts.user_lsu_ata).await, 2 * LSU);
+}
+
+#[tokio::test]
+async fn test_claim_yield_only_pays_yield_since_tokenizing() {
+    let mut series = TestSeries::start().await;
+    let (early, early_accounts) = series.new_tokenizer(10 * LSU).await;
+    series.set_exchange_rate(rate(5, 4)).await;
+    let (late, late_accounts) = series.new_tokenizer(10 * LSU).await;
+    assert_eq!(
+        series.token_balance(&late_accounts.user_yt_ata).await,
+        25 * LSU / 2
+    );
+
+    series.set_exchange_rate(rate(3, 2)).await;
+    for (user, accounts) in [(&early, &early_accounts), (&late, &late_accounts)] {
+        series
+            .process(&[resolver::claim_yield_for(accounts).unwrap()], &[user])
+            .await
+            .unwrap();
+    }
+
+    // The early YT earned 1 - 1 / 1.5 of its principal, the late YT only 1 / 1.25 - 1 / 1.5
+    assert_eq!(
+        series.token_balance(&early_accounts.user_lsu_ata).await,
+        3_333_333_333
+    );
+    assert_eq!(
+        series.token_balance(&late_accounts.user_lsu_ata).await,
+        1_666_666_666
+    );
+    // What's left backs both principals, 22.5 SOL at 1.5
+    assert_eq!(
+        series.token_balance(&early_accounts.lsu_vault).await,
+        15 * LSU + 1
+    );
+}
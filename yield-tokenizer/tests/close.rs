@@ -0,0 +1,134 @@
+mod helpers;
+
+use {
+    helpers::*,
+    solana_program_test::tokio,
+    solana_sdk::{instruction::Instruction, signature::Signer},
+    spl_associated_token_account::instruction::create_associated_token_account_idempotent,
+    yield_tokenizer::{
+        error::YieldTokenizerError, instruction, resolver, resolver::TokenizerAccounts,
+    },
+};
+
+fn close_yield_tokenizer(accounts: &TokenizerAccounts) -> Instruction {
+    instruction::close_yield_tokenizer(
+        &yield_tokenizer::id(),
+        &accounts.user,
+        &accounts.config,
+        &accounts.yield_tokenizer,
+        &accounts.user,
+        &accounts.pt_mint,
+        &accounts.yt_mint,
+        &accounts.lsu_vault,
+        &accounts.treasury,
+        &accounts.user_lsu_ata,
+        &accounts.yield_token_program,
+    )
+    .unwrap()
+}
+
+#[tokio::test]
+async fn test_close_a_redeemed_series() {
+    let mut series = TestSeries::start().await;
+    series.set_fees(1_000, 0).await;
+    let (holder, holder_accounts) = series.new_tokenizer(10 * LSU).await;
+    let (redeemer, redeemer_accounts) = series.new_tokenizer(5 * LSU).await;
+    series.set_exchange_rate(rate(5, 4)).await;
+
+    let authority = series.authority.insecure_clone();
+    let accounts = series.accounts;
+    let payer = series.context.payer.pubkey();
+    series
+        .process(
+            &[create_associated_token_account_idempotent(
+                &payer,
+                &authority.pubkey(),
+                &accounts.lsu_mint,
+                &spl_token::id(),
+            )],
+            &[],
+        )
+        .await
+        .unwrap();
+    let close = close_yield_tokenizer(&accounts);
+
+    assert_eq!(
+        instruction_error(
+            series
+                .process(std::slice::from_ref(&close), &[&authority])
+                .await
+        ),
+        custom_error(YieldTokenizerError::Immature)
+    );
+
+    // One holder redeems the pair, the other only the PT and keeps the YT
+    series.set_time(MATURITY_DATE).await;
+    series
+        .process(
+            &[resolver::redeem_for(&redeemer_accounts, 5 * LSU).unwrap()],
+            &[&redeemer],
+        )
+        .await
+        .unwrap();
+    series
+        .process(
+            &[resolver::redeem_from_pt_for(&holder_accounts, 10 * LSU).unwrap()],
+            &[&holder],
+        )
+        .await
+        .unwrap();
+    assert_eq!(series.mint_supply(&accounts.pt_mint).await, 0);
+    assert_eq!(series.mint_supply(&accounts.yt_mint).await, 10 * LSU);
+
+    // The YT's final yield is still in the vault
+    assert_eq!(series.token_balance(&accounts.lsu_vault).await, 2 * LSU);
+    assert_eq!(
+        instruction_error(
+            series
+                .process(std::slice::from_ref(&close), &[&authority])
+                .await
+        ),
+        custom_error(YieldTokenizerError::TokenizerNotEmpty)
+    );
+
+    series
+        .process(
+            &[resolver::claim_yield_for(&holder_accounts).unwrap()],
+            &[&holder],
+        )
+        .await
+        .unwrap();
+    assert_eq!(series.token_balance(&accounts.lsu_vault).await, 0);
+    assert_eq!(
+        series.token_balance(&accounts.treasury).await,
+        LSU / 10 + LSU / 5
+    );
+
+    // The YT left outstanding has nothing more to claim
+    let lamports = series
+        .get_account(&authority.pubkey())
+        .await
+        .unwrap()
+        .lamports;
+    series.process(&[close], &[&authority]).await.unwrap();
+
+    for address in [
+        accounts.yield_tokenizer,
+        accounts.lsu_vault,
+        accounts.treasury,
+    ] {
+        assert!(series.get_account(&address).await.is_none());
+    }
+    assert_eq!(
+        series.token_balance(&accounts.user_lsu_ata).await,
+        LSU / 10 + LSU / 5
+    );
+    assert!(
+        series
+            .get_account(&authority.pubkey())
+            .await
+            .unwrap()
+            .lamports
+            > lamports
+    );
+}
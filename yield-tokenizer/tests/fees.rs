@@ -0,0 +1,117 @@
+mod helpers;
+
+use {
+    helpers::*,
+    solana_program_test::tokio,
+    solana_sdk::{pubkey::Pubkey, signature::Signer},
+    spl_associated_token_account::instruction::create_associated_token_account_idempotent,
+    yield_tokenizer::{error::YieldTokenizerError, instruction, resolver},
+};
+
+/// 2027-03-31
+const NEXT_MATURITY_DATE: i64 = 1_806_451_200;
+
+#[tokio::test]
+async fn test_fees_reach_the_tokenizer_treasury() {
+    let mut series = TestSeries::start().await;
+    let other_series = series.new_series(NEXT_MATURITY_DATE).await;
+    series.set_fees(1_000, 500).await;
+    let (user, accounts) = series.new_tokenizer(10 * LSU).await;
+
+    // 10% of the 2 LSU of yield
+    series.set_exchange_rate(rate(5, 4)).await;
+    series
+        .process(&[resolver::claim_yield_for(&accounts).unwrap()], &[&user])
+        .await
+        .unwrap();
+    assert_eq!(
+        series.token_balance(&accounts.user_lsu_ata).await,
+        9 * LSU / 5
+    );
+    assert_eq!(series.token_balance(&accounts.treasury).await, LSU / 5);
+
+    // 5% of the 8 LSU the PT redeems for
+    series.set_time(MATURITY_DATE).await;
+    series
+        .process(
+            &[resolver::redeem_from_pt_for(&accounts, 10 * LSU).unwrap()],
+            &[&user],
+        )
+        .await
+        .unwrap();
+    assert_eq!(
+        series.token_balance(&accounts.user_lsu_ata).await,
+        9 * LSU / 5 + 38 * LSU / 5
+    );
+    assert_eq!(series.token_balance(&accounts.treasury).await, 3 * LSU / 5);
+    assert_eq!(series.token_balance(&accounts.lsu_vault).await, 0);
+
+    // Each series keeps its own fees
+    assert_ne!(other_series.treasury, accounts.treasury);
+    assert_eq!(series.token_balance(&other_series.treasury).await, 0);
+}
+
+#[tokio::test]
+async fn test_withdraw_fees_is_admin_only() {
+    let mut series = TestSeries::start().await;
+    series.set_fees(1_000, 0).await;
+    let (user, accounts) = series.new_tokenizer(10 * LSU).await;
+    series.set_exchange_rate(rate(5, 4)).await;
+    series
+        .process(&[resolver::claim_yield_for(&accounts).unwrap()], &[&user])
+        .await
+        .unwrap();
+    assert_eq!(series.token_balance(&accounts.treasury).await, LSU / 5);
+
+    let withdraw_fees = |authority: Pubkey, destination: Pubkey| {
+        instruction::withdraw_fees(
+            &yield_tokenizer::id(),
+            &authority,
+            &accounts.config,
+            &accounts.yield_tokenizer,
+            &accounts.treasury,
+            &destination,
+            LSU / 5,
+        )
+        .unwrap()
+    };
+
+    assert_eq!(
+        instruction_error(
+            series
+                .process(
+                    &[withdraw_fees(user.pubkey(), accounts.user_lsu_ata)],
+                    &[&user]
+                )
+                .await
+        ),
+        custom_error(YieldTokenizerError::InvalidAuthority)
+    );
+    assert_eq!(series.token_balance(&accounts.treasury).await, LSU / 5);
+
+    let authority = series.authority.insecure_clone();
+    let authority_lsu_ata = series.accounts.user_lsu_ata;
+    let payer = series.context.payer.pubkey();
+    series
+        .process(
+            &[
+                create_associated_token_account_idempotent(
+                    &payer,
+                    &authority.pubkey(),
+                    &accounts.lsu_mint,
+                    &spl_token::id(),
+                ),
+                withdraw_fees(authority.pubkey(), authority_lsu_ata),
+            ],
+            &[&authority],
+        )
+        .await
+        .unwrap();
+    assert_eq!(series.token_balance(&accounts.treasury).await, 0);
+    assert_eq!(
+        series
+            .token_balance(&series.accounts.user_lsu_ata.clone())
+            .await,
+        LSU / 5
+    );
+}
@@ -0,0 +1,432 @@
+//! Program-test harness shared by the integration tests. Every test runs against one series of a
+//! Marinade-style LSU whose exchange rate and clock the test controls.
+
+#![allow(dead_code)]
+
+use {
+    solana_program_test::{processor, BanksClientError, ProgramTest, ProgramTestContext},
+    solana_sdk::{
+        account::{Account, AccountSharedData},
+        bpf_loader_upgradeable,
+        clock::Clock,
+        instruction::{Instruction, InstructionError},
+        program_option::COption,
+        program_pack::Pack,
+        pubkey::Pubkey,
+        rent::Rent,
+        signature::{Keypair, Signer},
+        system_instruction,
+        transaction::{Transaction, TransactionError},
+    },
+    spl_associated_token_account::instruction::create_associated_token_account_idempotent,
+    yield_tokenizer::{
+        adapter::{LsuAdapter, MARINADE_PROGRAM_ID},
+        error::YieldTokenizerError,
+        instruction,
+        math::ONE,
+        processor::YieldTokenizerProcessor,
+        resolver::{self, TokenizerAccounts},
+        state::{ProtocolConfig, YieldPosition, YieldTokenizerState, EXCHANGE_RATE_PRECISION},
+    },
+};
+
+/// 2026-12-31, the maturity of the series every test uses
+pub const MATURITY_DATE: i64 = 1_798_675_200;
+/// 2026-06-01, when the series is initialized
+pub const START: i64 = 1_780_272_000;
+/// One LSU, and one PT/YT at the initial rate, in base units
+pub const LSU: u64 = 1_000_000_000;
+
+/// Marinade `State` layout, see `adapter.rs`
+const MARINADE_STATE_DISCRIMINATOR: [u8; 8] = [216, 146, 107, 94, 104, 75, 182, 177];
+const MARINADE_MSOL_MINT_OFFSET: usize = 8;
+const MARINADE_MSOL_PRICE_OFFSET: usize = 512;
+const MARINADE_STATE_LEN: usize = 768;
+
+/// SOL per LSU scaled by `EXCHANGE_RATE_PRECISION`, `numerator / denominator`. Marinade prices are
+/// 32-bit fixed point, so only rates with a power of two denominator read back exactly.
+pub fn rate(numerator: u64, denominator: u64) -> u64 {
+    EXCHANGE_RATE_PRECISION / denominator * numerator
+}
+
+pub struct TestSeries {
+    pub context: ProgramTestContext,
+    /// Upgrade and config authority, LSU mint authority and initializer of the series
+    pub authority: Keypair,
+    /// The series resolved for `authority`
+    pub accounts: TokenizerAccounts,
+}
+
+impl TestSeries {
+    /// Starts a validator with the config, an allowlisted LSU at a rate of 1 SOL and its series
+    /// initialized at `START`, with PT and YT under `spl_token`
+    pub async fn start() -> Self {
+        let authority = Keypair::new();
+        let lsu_mint = Pubkey::new_unique();
+        let lsu_exchange_rate_account = Pubkey::new_unique();
+
+        let mut program_test = ProgramTest::new(
+            "yield_tokenizer",
+            yield_tokenizer::id(),
+            processor!(YieldTokenizerProcessor::process),
+        );
+        program_test.add_account(
+            authority.pubkey(),
+            Account::new(1_000 * LSU, 0, &solana_sdk::system_program::id()),
+        );
+        program_test.add_account(
+            Pubkey::find_program_address(
+                &[yield_tokenizer::id().as_ref()],
+                &bpf_loader_upgradeable::id(),
+            )
+            .0,
+            program_data_account(&authority.pubkey()),
+        );
+        let mut mint_data = vec![0; spl_token::state::Mint::LEN];
+        spl_token::state::Mint {
+            mint_authority: COption::Some(authority.pubkey()),
+            supply: 0,
+            decimals: 9,
+            is_initialized: true,
+            freeze_authority: COption::None,
+        }
+        .pack_into_slice(&mut mint_data);
+        program_test.add_account(lsu_mint, rent_exempt_account(mint_data, &spl_token::id()));
+        program_test.add_account(
+            lsu_exchange_rate_account,
+            marinade_state_account(&lsu_mint, rate(1, 1)),
+        );
+
+        let mut series = Self {
+            context: program_test.start_with_context().await,
+            accounts: TokenizerAccounts::new(
+                &yield_tokenizer::id(),
+                &lsu_mint,
+                MATURITY_DATE,
+                &authority.pubkey(),
+                &lsu_exchange_rate_account,
+                &spl_token::id(),
+            ),
+            authority,
+        };
+        series.set_time(START).await;
+
+        let authority = series.authority.insecure_clone();
+        let config = series.accounts.config;
+        series
+            .process(
+                &[
+                    instruction::init_config(&yield_tokenizer::id(), &authority.pubkey(), &config)
+                        .unwrap(),
+                    instruction::add_lsu(
+                        &yield_tokenizer::id(),
+                        &authority.pubkey(),
+                        &config,
+                        &lsu_mint,
+                        &lsu_exchange_rate_account,
+                        LsuAdapter::Marinade,
+                        "mSOL",
+                    )
+                    .unwrap(),
+                    resolver::init_yield_tokenizer_for(&series.accounts).unwrap(),
+                ],
+                &[&authority],
+            )
+            .await
+            .unwrap();
+
+        series
+    }
+
+    /// Initializes another series of the same LSU maturing at `maturity_date`, resolved for
+    /// `authority`
+    pub async fn new_series(&mut self, maturity_date: i64) -> TokenizerAccounts {
+        let accounts = TokenizerAccounts::new(
+            &yield_tokenizer::id(),
+            &self.accounts.lsu_mint,
+            maturity_date,
+            &self.authority.pubkey(),
+            &self.accounts.lsu_exchange_rate_account,
+            &self.accounts.yield_token_program,
+        );
+        let authority = self.authority.insecure_clone();
+        self.process(
+            &[resolver::init_yield_tokenizer_for(&accounts).unwrap()],
+            &[&authority],
+        )
+        .await
+        .unwrap();
+        accounts
+    }
+
+    /// Sends `instructions` in one transaction paid for by the context's payer
+    pub async fn process(
+        &mut self,
+        instructions: &[Instruction],
+        signers: &[&Keypair],
+    ) -> Result<(), BanksClientError> {
+        // A fresh blockhash keeps repeated, otherwise identical, transactions apart
+        let blockhash = self.context.get_new_latest_blockhash().await.unwrap();
+        let mut all_signers = vec![&self.context.payer];
+        all_signers.extend_from_slice(signers);
+        let transaction = Transaction::new_signed_with_payer(
+            instructions,
+            Some(&self.context.payer.pubkey()),
+            &all_signers,
+            blockhash,
+        );
+        self.context
+            .banks_client
+            .process_transaction(transaction)
+            .await
+    }
+
+    /// A funded user holding `lsu_amount` LSU, with its LSU, PT and YT ATAs created
+    pub async fn new_user(&mut self, lsu_amount: u64) -> (Keypair, TokenizerAccounts) {
+        let user = Keypair::new();
+        let accounts = self.accounts.for_user(&user.pubkey());
+        let payer = self.context.payer.pubkey();
+        let authority = self.authority.insecure_clone();
+
+        let mut instructions = vec![system_instruction::transfer(&payer, &user.pubkey(), LSU)];
+        let mut signers = vec![];
+        for (mint, token_program) in [
+            (&accounts.lsu_mint, &spl_token::id()),
+            (&accounts.pt_mint, &accounts.yield_token_program),
+            (&accounts.yt_mint, &accounts.yield_token_program),
+        ] {
+            instructions.push(create_associated_token_account_idempotent(
+                &payer,
+                &user.pubkey(),
+                mint,
+                token_program,
+            ));
+        }
+        if lsu_amount > 0 {
+            instructions.push(
+                spl_token::instruction::mint_to(
+                    &spl_token::id(),
+                    &accounts.lsu_mint,
+                    &accounts.user_lsu_ata,
+                    &authority.pubkey(),
+                    &[],
+                    lsu_amount,
+                )
+                .unwrap(),
+            );
+            signers.push(&authority);
+        }
+        self.process(&instructions, &signers).await.unwrap();
+
+        (user, accounts)
+    }
+
+    /// A new user that tokenized `lsu_amount` LSU at the current rate
+    pub async fn new_tokenizer(&mut self, lsu_amount: u64) -> (Keypair, TokenizerAccounts) {
+        let (user, accounts) = self.new_user(lsu_amount).await;
+        self.process(
+            &[resolver::tokenize_yield_for(&accounts, lsu_amount).unwrap()],
+            &[&user],
+        )
+        .await
+        .unwrap();
+        (user, accounts)
+    }
+
+    /// Opens the series' PT pool with the parameters the AMM's own tests use
+    pub async fn init_pool(&mut self) {
+        let authority = self.authority.insecure_clone();
+        let accounts = self.accounts;
+        self.process(
+            &[instruction::init_pool(
+                &yield_tokenizer::id(),
+                &authority.pubkey(),
+                &accounts.config,
+                &accounts.yield_tokenizer,
+                &accounts.lsu_mint,
+                &accounts.pt_mint,
+                &accounts.pool,
+                &accounts.lp_mint,
+                &accounts.pool_lsu_vault,
+                &accounts.pool_pt_vault,
+                &accounts.yield_token_program,
+                50 * ONE,
+                ONE + ONE / 20,
+                ONE * 3 / 1_000,
+            )
+            .unwrap()],
+            &[&authority],
+        )
+        .await
+        .unwrap();
+    }
+
+    /// Moves the LSU's exchange rate, see `rate`
+    pub async fn set_exchange_rate(&mut self, exchange_rate: u64) {
+        let account = marinade_state_account(&self.accounts.lsu_mint, exchange_rate);
+        self.context.set_account(
+            &self.accounts.lsu_exchange_rate_account,
+            &AccountSharedData::from(account),
+        );
+    }
+
+    pub async fn set_time(&mut self, unix_timestamp: i64) {
+        let clock = self
+            .context
+            .banks_client
+            .get_sysvar::<Clock>()
+            .await
+            .unwrap();
+        self.context.set_sysvar(&Clock {
+            unix_timestamp,
+            ..clock
+        });
+    }
+
+    pub async fn set_fees(&mut self, yield_fee_bps: u16, redemption_fee_bps: u16) {
+        let authority = self.authority.insecure_clone();
+        self.process(
+            &[instruction::set_fees(
+                &yield_tokenizer::id(),
+                &authority.pubkey(),
+                &self.accounts.config,
+                yield_fee_bps,
+                redemption_fee_bps,
+            )
+            .unwrap()],
+            &[&authority],
+        )
+        .await
+        .unwrap();
+    }
+
+    pub async fn get_account(&mut self, address: &Pubkey) -> Option<Account> {
+        self.context
+            .banks_client
+            .get_account(*address)
+            .await
+            .unwrap()
+    }
+
+    pub async fn token_account(&mut self, address: &Pubkey) -> spl_token::state::Account {
+        let account = self.get_account(address).await.unwrap();
+        spl_token::state::Account::unpack(&account.data).unwrap()
+    }
+
+    pub async fn token_balance(&mut self, address: &Pubkey) -> u64 {
+        self.token_account(address).await.amount
+    }
+
+    pub async fn mint_supply(&mut self, address: &Pubkey) -> u64 {
+        let account = self.get_account(address).await.unwrap();
+        spl_token::state::Mint::unpack(&account.data)
+            .unwrap()
+            .supply
+    }
+
+    pub async fn yield_tokenizer(&mut self) -> YieldTokenizerState {
+        let address = self.accounts.yield_tokenizer;
+        let account = self.get_account(&address).await.unwrap();
+        YieldTokenizerState::unpack(&account.data).unwrap()
+    }
+
+    pub async fn position(&mut self, accounts: &TokenizerAccounts) -> YieldPosition {
+        let account = self.get_account(&accounts.user_position).await.unwrap();
+        YieldPosition::unpack(&account.data).unwrap()
+    }
+
+    pub async fn config(&mut self) -> ProtocolConfig {
+        let address = self.accounts.config;
+        let account = self.get_account(&address).await.unwrap();
+        ProtocolConfig::unpack(&account.data).unwrap()
+    }
+}
+
+pub fn add_liquidity(accounts: &TokenizerAccounts, pt_amount: u64, lsu_amount: u64) -> Instruction {
+    instruction::add_liquidity(
+        &yield_tokenizer::id(),
+        &accounts.user,
+        &accounts.config,
+        &accounts.yield_tokenizer,
+        &accounts.lsu_mint,
+        &accounts.lsu_exchange_rate_account,
+        &accounts.pt_mint,
+        &accounts.pool,
+        &accounts.lp_mint,
+        &accounts.pool_lsu_vault,
+        &accounts.pool_pt_vault,
+        &accounts.user_lsu_ata,
+        &accounts.user_pt_ata,
+        &accounts.user_lp_ata,
+        &accounts.yield_token_program,
+        pt_amount,
+        lsu_amount,
+        0,
+    )
+    .unwrap()
+}
+
+pub fn remove_liquidity(accounts: &TokenizerAccounts, lp_amount: u64) -> Instruction {
+    instruction::remove_liquidity(
+        &yield_tokenizer::id(),
+        &accounts.user,
+        &accounts.config,
+        &accounts.yield_tokenizer,
+        &accounts.lsu_mint,
+        &accounts.pt_mint,
+        &accounts.pool,
+        &accounts.lp_mint,
+        &accounts.pool_lsu_vault,
+        &accounts.pool_pt_vault,
+        &accounts.user_lsu_ata,
+        &accounts.user_pt_ata,
+        &accounts.user_lp_ata,
+        &accounts.yield_token_program,
+        lp_amount,
+        0,
+        0,
+    )
+    .unwrap()
+}
+
+/// The instruction error a failed transaction was rejected with
+pub fn instruction_error(result: Result<(), BanksClientError>) -> InstructionError {
+    match result.unwrap_err().unwrap() {
+        TransactionError::InstructionError(_, error) => error,
+        error => panic!("unexpected transaction error {:?}", error),
+    }
+}
+
+pub fn custom_error(error: YieldTokenizerError) -> InstructionError {
+    InstructionError::Custom(error as u32)
+}
+
+/// `UpgradeableLoaderState::ProgramData` with `upgrade_authority`, as the program data account of
+/// a deployed program
+fn program_data_account(upgrade_authority: &Pubkey) -> Account {
+    let mut data = 3u32.to_le_bytes().to_vec();
+    data.extend(0u64.to_le_bytes());
+    data.push(1);
+    data.extend(upgrade_authority.as_ref());
+    rent_exempt_account(data, &bpf_loader_upgradeable::id())
+}
+
+fn marinade_state_account(msol_mint: &Pubkey, exchange_rate: u64) -> Account {
+    let msol_price =
+        (exchange_rate as u128 * 0x1_0000_0000 / EXCHANGE_RATE_PRECISION as u128) as u64;
+    let mut data = vec![0; MARINADE_STATE_LEN];
+    data[..8].copy_from_slice(&MARINADE_STATE_DISCRIMINATOR);
+    data[MARINADE_MSOL_MINT_OFFSET..][..32].copy_from_slice(msol_mint.as_ref());
+    data[MARINADE_MSOL_PRICE_OFFSET..][..8].copy_from_slice(&msol_price.to_le_bytes());
+    rent_exempt_account(data, &MARINADE_PROGRAM_ID)
+}
+
+pub fn rent_exempt_account(data: Vec<u8>, owner: &Pubkey) -> Account {
+    Account {
+        lamports: Rent::default().minimum_balance(data.len()),
+        data,
+        owner: *owner,
+        executable: false,
+        rent_epoch: 0,
+    }
+}
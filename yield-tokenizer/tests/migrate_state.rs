@@ -0,0 +1,271 @@
+mod helpers;
+
+use {
+    helpers::*,
+    solana_program_test::tokio,
+    solana_sdk::{
+        account::{Account, AccountSharedData},
+        instruction::{Instruction, InstructionError},
+        program_pack::Pack,
+        pubkey::Pubkey,
+        signature::Signer,
+    },
+    yield_tokenizer::{
+        adapter::LsuAdapter,
+        error::YieldTokenizerError,
+        find_principal_token_address, find_treasury_address, find_yield_token_address,
+        find_yield_tokenizer_address, instruction,
+        resolver::TokenizerAccounts,
+        state::{LegacyYieldTokenizerState, PauseState, YieldTokenizerState, HEADER_LEN},
+    },
+};
+
+/// 2027-03-31, a series the first deployment could have left behind
+const LEGACY_MATURITY_DATE: i64 = 1_806_451_200;
+
+fn migrate_yield_tokenizer(payer: &Pubkey, accounts: &TokenizerAccounts) -> Instruction {
+    instruction::migrate_yield_tokenizer(
+        &yield_tokenizer::id(),
+        payer,
+        &accounts.yield_tokenizer,
+        &accounts.config,
+        &accounts.lsu_mint,
+        &accounts.lsu_exchange_rate_account,
+        &accounts.treasury,
+    )
+    .unwrap()
+}
+
+fn migrate_state(payer: &Pubkey, account: &Pubkey) -> Instruction {
+    instruction::migrate_state(&yield_tokenizer::id(), payer, account).unwrap()
+}
+
+/// Writes a version 0 tokenizer, the baseline `pt, yt, maturity_date, lsu_mint, lsu_vault`
+/// layout, at `address`
+fn set_legacy_yield_tokenizer(
+    series: &mut TestSeries,
+    address: &Pubkey,
+    accounts: &TokenizerAccounts,
+) {
+    let legacy = LegacyYieldTokenizerState {
+        pt: accounts.pt_mint,
+        yt: accounts.yt_mint,
+        maturity_date: accounts.maturity_date,
+        lsu_mint: accounts.lsu_mint,
+        lsu_vault: accounts.lsu_vault,
+    };
+    let data = borsh::to_vec(&legacy).unwrap();
+    assert_eq!(data.len(), 136);
+    series.context.set_account(
+        address,
+        &AccountSharedData::from(rent_exempt_account(data, &yield_tokenizer::id())),
+    );
+}
+
+#[tokio::test]
+async fn test_migrate_baseline_yield_tokenizer() {
+    let mut series = TestSeries::start().await;
+    let accounts = TokenizerAccounts::new(
+        &yield_tokenizer::id(),
+        &series.accounts.lsu_mint,
+        LEGACY_MATURITY_DATE,
+        &series.authority.pubkey(),
+        &series.accounts.lsu_exchange_rate_account,
+        &spl_token::id(),
+    );
+    set_legacy_yield_tokenizer(&mut series, &accounts.yield_tokenizer, &accounts);
+    series.set_exchange_rate(rate(5, 4)).await;
+
+    // Without the accounts a version 0 tokenizer needs
+    let payer = series.context.payer.pubkey();
+    assert_eq!(
+        instruction_error(
+            series
+                .process(&[migrate_state(&payer, &accounts.yield_tokenizer)], &[])
+                .await
+        ),
+        InstructionError::NotEnoughAccountKeys
+    );
+
+    series
+        .process(&[migrate_yield_tokenizer(&payer, &accounts)], &[])
+        .await
+        .unwrap();
+
+    let account = series.get_account(&accounts.yield_tokenizer).await.unwrap();
+    let yield_tokenizer = YieldTokenizerState::unpack(&account.data).unwrap();
+    assert_eq!(yield_tokenizer.pt, accounts.pt_mint);
+    assert_eq!(yield_tokenizer.yt, accounts.yt_mint);
+    assert_eq!(yield_tokenizer.maturity_date, LEGACY_MATURITY_DATE);
+    assert_eq!(yield_tokenizer.lsu_mint, accounts.lsu_mint);
+    assert_eq!(yield_tokenizer.lsu_vault, accounts.lsu_vault);
+
+    // The fields the baseline lacked, the adapter from the LSU's config entry and the accounting
+    // restarted at the current rate
+    assert_eq!(yield_tokenizer.adapter, LsuAdapter::Marinade);
+    assert_eq!(
+        yield_tokenizer.lsu_exchange_rate_account,
+        accounts.lsu_exchange_rate_account
+    );
+    assert_eq!(yield_tokenizer.initial_exchange_rate, rate(5, 4));
+    assert_eq!(yield_tokenizer.last_exchange_rate, rate(5, 4));
+    assert_eq!(yield_tokenizer.maturity_exchange_rate, 0);
+    assert_eq!(yield_tokenizer.yield_index, 0);
+    assert_eq!(yield_tokenizer.pause_state, PauseState::Active);
+    assert_eq!(yield_tokenizer.initializer, payer);
+    assert_eq!(
+        yield_tokenizer.bump,
+        find_yield_tokenizer_address(
+            &yield_tokenizer::id(),
+            &accounts.lsu_mint,
+            LEGACY_MATURITY_DATE
+        )
+        .1
+    );
+    assert_eq!(
+        yield_tokenizer.pt_bump,
+        find_principal_token_address(&yield_tokenizer::id(), &accounts.yield_tokenizer).1
+    );
+    assert_eq!(
+        yield_tokenizer.yt_bump,
+        find_yield_token_address(&yield_tokenizer::id(), &accounts.yield_tokenizer).1
+    );
+    assert_eq!(
+        yield_tokenizer.treasury_bump,
+        find_treasury_address(&yield_tokenizer::id(), &accounts.yield_tokenizer).1
+    );
+
+    let treasury = series.token_account(&accounts.treasury).await;
+    assert_eq!(treasury.mint, accounts.lsu_mint);
+    assert_eq!(treasury.owner, accounts.yield_tokenizer);
+    assert_eq!(
+        series
+            .get_account(&accounts.treasury)
+            .await
+            .unwrap()
+            .data
+            .len(),
+        spl_token::state::Account::LEN
+    );
+
+    // Migrating it again leaves it as it is
+    series
+        .process(&[migrate_state(&payer, &accounts.yield_tokenizer)], &[])
+        .await
+        .unwrap();
+    assert_eq!(
+        series.get_account(&accounts.yield_tokenizer).await.unwrap(),
+        account
+    );
+}
+
+#[tokio::test]
+async fn test_migrate_matured_baseline_yield_tokenizer_freezes_rate() {
+    let mut series = TestSeries::start().await;
+    let accounts = TokenizerAccounts::new(
+        &yield_tokenizer::id(),
+        &series.accounts.lsu_mint,
+        LEGACY_MATURITY_DATE,
+        &series.authority.pubkey(),
+        &series.accounts.lsu_exchange_rate_account,
+        &spl_token::id(),
+    );
+    set_legacy_yield_tokenizer(&mut series, &accounts.yield_tokenizer, &accounts);
+    series.set_exchange_rate(rate(3, 2)).await;
+    series.set_time(LEGACY_MATURITY_DATE).await;
+
+    let payer = series.context.payer.pubkey();
+    series
+        .process(&[migrate_yield_tokenizer(&payer, &accounts)], &[])
+        .await
+        .unwrap();
+
+    let account = series.get_account(&accounts.yield_tokenizer).await.unwrap();
+    let yield_tokenizer = YieldTokenizerState::unpack(&account.data).unwrap();
+    assert_eq!(yield_tokenizer.initial_exchange_rate, rate(3, 2));
+    assert_eq!(yield_tokenizer.maturity_exchange_rate, rate(3, 2));
+    assert_eq!(yield_tokenizer.yield_index, 0);
+}
+
+#[tokio::test]
+async fn test_migrate_header_less_accounts() {
+    let mut series = TestSeries::start().await;
+    let (_, user_accounts) = series.new_tokenizer(10 * LSU).await;
+    series.init_pool().await;
+
+    let payer = series.context.payer.pubkey();
+    let accounts = series.accounts;
+    // A version 0 pool also lacks the bump at its end
+    for (address, trailing_len) in [
+        (accounts.config, 0),
+        (accounts.pool, 1),
+        (user_accounts.user_position, 0),
+    ] {
+        // The same fields without the header in front
+        let current = series.get_account(&address).await.unwrap();
+        series.context.set_account(
+            &address,
+            &AccountSharedData::from(Account {
+                data: current.data[HEADER_LEN..current.data.len() - trailing_len].to_vec(),
+                ..current.clone()
+            }),
+        );
+
+        series
+            .process(&[migrate_state(&payer, &address)], &[])
+            .await
+            .unwrap();
+        let migrated = series.get_account(&address).await.unwrap();
+        assert_eq!(migrated.data, current.data);
+        assert_eq!(migrated.owner, yield_tokenizer::id());
+    }
+
+    // The migrated accounts read back as they did
+    assert_eq!(series.config().await.lsus.len(), 1);
+    assert_eq!(series.position(&user_accounts).await.accrued_yield, 0);
+}
+
+#[tokio::test]
+async fn test_migrate_rejects_unrecognized_accounts() {
+    let mut series = TestSeries::start().await;
+    let payer = series.context.payer.pubkey();
+
+    // A program account no PDA accounts for
+    let address = Pubkey::new_unique();
+    series.context.set_account(
+        &address,
+        &AccountSharedData::from(rent_exempt_account(vec![7; 100], &yield_tokenizer::id())),
+    );
+    assert_eq!(
+        instruction_error(
+            series
+                .process(&[migrate_state(&payer, &address)], &[])
+                .await
+        ),
+        InstructionError::InvalidAccountData
+    );
+
+    // A baseline-sized account that isn't at the tokenizer PDA of its own LSU mint and maturity
+    let accounts = TokenizerAccounts::new(
+        &yield_tokenizer::id(),
+        &series.accounts.lsu_mint,
+        LEGACY_MATURITY_DATE,
+        &series.authority.pubkey(),
+        &series.accounts.lsu_exchange_rate_account,
+        &spl_token::id(),
+    );
+    set_legacy_yield_tokenizer(&mut series, &address, &accounts);
+    let misplaced = TokenizerAccounts {
+        yield_tokenizer: address,
+        ..accounts
+    };
+    assert_eq!(
+        instruction_error(
+            series
+                .process(&[migrate_yield_tokenizer(&payer, &misplaced)], &[])
+                .await
+        ),
+        custom_error(YieldTokenizerError::InvalidYieldTokenizerAddress)
+    );
+    assert_eq!(series.get_account(&address).await.unwrap().data.len(), 136);
+}
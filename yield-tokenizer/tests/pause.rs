@@ -0,0 +1,178 @@
+mod helpers;
+
+use {
+    helpers::*,
+    solana_program_test::tokio,
+    solana_sdk::{
+        instruction::Instruction,
+        pubkey::Pubkey,
+        signature::{Keypair, Signer},
+    },
+    yield_tokenizer::{error::YieldTokenizerError, instruction, resolver, state::PauseState},
+};
+
+/// Sends `instruction` and checks it's only rejected if `pause_state` doesn't allow it
+async fn check(
+    series: &mut TestSeries,
+    user: &Keypair,
+    pause_state: PauseState,
+    instruction: Instruction,
+    is_exit: bool,
+) {
+    let result = series.process(&[instruction], &[user]).await;
+    match pause_state {
+        PauseState::Active => result.unwrap(),
+        PauseState::Emergency if is_exit => result.unwrap(),
+        _ => assert_eq!(
+            instruction_error(result),
+            custom_error(YieldTokenizerError::Paused)
+        ),
+    }
+}
+
+/// Runs every user instruction against a series whose protocol and tokenizer pause states are
+/// `protocol` and `tokenizer`, and checks only what the more restrictive of the two allows gets
+/// through
+async fn check_pause_states(protocol: PauseState, tokenizer: PauseState) {
+    let mut series = TestSeries::start().await;
+    series.init_pool().await;
+    let (user, accounts) = series.new_user(20 * LSU).await;
+    series
+        .process(
+            &[
+                resolver::tokenize_yield_for(&accounts, 10 * LSU).unwrap(),
+                add_liquidity(&accounts, 5 * LSU, 5 * LSU),
+            ],
+            &[&user],
+        )
+        .await
+        .unwrap();
+    let lp_amount = series.token_balance(&accounts.user_lp_ata).await / 10;
+
+    let authority = series.authority.insecure_clone();
+    series
+        .process(
+            &[
+                instruction::set_pause(
+                    &yield_tokenizer::id(),
+                    &authority.pubkey(),
+                    &accounts.config,
+                    protocol,
+                )
+                .unwrap(),
+                instruction::set_tokenizer_pause(
+                    &yield_tokenizer::id(),
+                    &authority.pubkey(),
+                    &accounts.config,
+                    &accounts.yield_tokenizer,
+                    tokenizer,
+                )
+                .unwrap(),
+            ],
+            &[&authority],
+        )
+        .await
+        .unwrap();
+    assert_eq!(series.config().await.pause_state, protocol);
+    assert_eq!(series.yield_tokenizer().await.pause_state, tokenizer);
+
+    let pause_state = protocol.max(tokenizer);
+    let recipient = Pubkey::new_unique();
+    check(
+        &mut series,
+        &user,
+        pause_state,
+        resolver::tokenize_yield_for(&accounts, LSU).unwrap(),
+        false,
+    )
+    .await;
+    check(
+        &mut series,
+        &user,
+        pause_state,
+        resolver::claim_yield_for(&accounts).unwrap(),
+        false,
+    )
+    .await;
+    check(
+        &mut series,
+        &user,
+        pause_state,
+        resolver::transfer_yt_for(&accounts, &recipient, LSU).unwrap(),
+        false,
+    )
+    .await;
+    check(
+        &mut series,
+        &user,
+        pause_state,
+        add_liquidity(&accounts, LSU, LSU),
+        false,
+    )
+    .await;
+
+    // Exits don't read the adapter in emergency mode
+    series.set_exchange_rate(rate(5, 4)).await;
+    check(
+        &mut series,
+        &user,
+        pause_state,
+        resolver::redeem_for(&accounts, LSU).unwrap(),
+        true,
+    )
+    .await;
+    check(
+        &mut series,
+        &user,
+        pause_state,
+        remove_liquidity(&accounts, lp_amount),
+        true,
+    )
+    .await;
+    series.set_time(MATURITY_DATE).await;
+    check(
+        &mut series,
+        &user,
+        pause_state,
+        resolver::redeem_from_pt_for(&accounts, LSU).unwrap(),
+        true,
+    )
+    .await;
+
+    let yield_tokenizer = series.yield_tokenizer().await;
+    match pause_state {
+        PauseState::Active => assert_eq!(yield_tokenizer.last_exchange_rate, rate(5, 4)),
+        _ => assert_eq!(yield_tokenizer.last_exchange_rate, rate(1, 1)),
+    }
+}
+
+#[tokio::test]
+async fn test_active() {
+    check_pause_states(PauseState::Active, PauseState::Active).await;
+}
+
+#[tokio::test]
+async fn test_protocol_emergency() {
+    check_pause_states(PauseState::Emergency, PauseState::Active).await;
+}
+
+#[tokio::test]
+async fn test_tokenizer_emergency() {
+    check_pause_states(PauseState::Active, PauseState::Emergency).await;
+}
+
+#[tokio::test]
+async fn test_protocol_paused() {
+    check_pause_states(PauseState::Paused, PauseState::Active).await;
+}
+
+#[tokio::test]
+async fn test_tokenizer_paused() {
+    check_pause_states(PauseState::Active, PauseState::Paused).await;
+}
+
+#[tokio::test]
+async fn test_paused_overrides_emergency() {
+    check_pause_states(PauseState::Emergency, PauseState::Paused).await;
+    check_pause_states(PauseState::Paused, PauseState::Emergency).await;
+}
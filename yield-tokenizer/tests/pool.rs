@@ -0,0 +1,78 @@
+mod helpers;
+
+use {
+    helpers::*,
+    solana_program_test::tokio,
+    solana_sdk::signature::Signer,
+    yield_tokenizer::{instruction, resolver},
+};
+
+#[tokio::test]
+async fn test_provide_liquidity_and_sell_pt() {
+    let mut series = TestSeries::start().await;
+    series.init_pool().await;
+
+    // Both legs of the deposit are signed by the provider
+    let (provider, provider_accounts) = series.new_user(20 * LSU).await;
+    series
+        .process(
+            &[
+                resolver::tokenize_yield_for(&provider_accounts, 10 * LSU).unwrap(),
+                add_liquidity(&provider_accounts, 5 * LSU, 5 * LSU),
+            ],
+            &[&provider],
+        )
+        .await
+        .unwrap();
+    assert_eq!(
+        series.token_balance(&provider_accounts.pool_pt_vault).await,
+        5 * LSU
+    );
+    assert_eq!(
+        series
+            .token_balance(&provider_accounts.pool_lsu_vault)
+            .await,
+        5 * LSU
+    );
+    assert!(series.token_balance(&provider_accounts.user_lp_ata).await > 0);
+
+    // PT trades below its principal before maturity
+    let (trader, trader_accounts) = series.new_tokenizer(LSU).await;
+    series
+        .process(
+            &[instruction::swap_exact_pt_for_lsu(
+                &yield_tokenizer::id(),
+                &trader.pubkey(),
+                &trader_accounts.config,
+                &trader_accounts.yield_tokenizer,
+                &trader_accounts.lsu_mint,
+                &trader_accounts.lsu_exchange_rate_account,
+                &trader_accounts.pt_mint,
+                &trader_accounts.pool,
+                &trader_accounts.pool_lsu_vault,
+                &trader_accounts.pool_pt_vault,
+                &trader_accounts.user_lsu_ata,
+                &trader_accounts.user_pt_ata,
+                &trader_accounts.yield_token_program,
+                LSU,
+                0,
+            )
+            .unwrap()],
+            &[&trader],
+        )
+        .await
+        .unwrap();
+    let lsu_out = series.token_balance(&trader_accounts.user_lsu_ata).await;
+    assert!(lsu_out > 0 && lsu_out < LSU, "{}", lsu_out);
+    assert_eq!(series.token_balance(&trader_accounts.user_pt_ata).await, 0);
+    assert_eq!(
+        series.token_balance(&provider_accounts.pool_pt_vault).await,
+        6 * LSU
+    );
+    assert_eq!(
+        series
+            .token_balance(&provider_accounts.pool_lsu_vault)
+            .await,
+        5 * LSU - lsu_out
+    );
+}
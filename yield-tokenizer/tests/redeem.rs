@@ -0,0 +1,136 @@
+mod helpers;
+
+use {
+    helpers::*,
+    solana_program_test::tokio,
+    yield_tokenizer::{error::YieldTokenizerError, resolver},
+};
+
+#[tokio::test]
+async fn test_redeem_before_maturity() {
+    let mut series = TestSeries::start().await;
+    series.set_fees(1_000, 0).await;
+    let (user, accounts) = series.new_tokenizer(10 * LSU).await;
+    series.set_exchange_rate(rate(5, 4)).await;
+
+    series
+        .process(
+            &[resolver::redeem_for(&accounts, 4 * LSU).unwrap()],
+            &[&user],
+        )
+        .await
+        .unwrap();
+
+    // 4 SOL of principal is 3.2 LSU at 1.25, paid with the 2 LSU of yield the 10 YT earned less
+    // the 10% fee on it
+    assert_eq!(series.token_balance(&accounts.user_lsu_ata).await, 5 * LSU);
+    assert_eq!(series.token_balance(&accounts.treasury).await, LSU / 5);
+    assert_eq!(series.token_balance(&accounts.user_pt_ata).await, 6 * LSU);
+    assert_eq!(series.token_balance(&accounts.user_yt_ata).await, 6 * LSU);
+    assert!(series
+        .token_account(&accounts.user_yt_ata)
+        .await
+        .is_frozen());
+    assert_eq!(series.mint_supply(&accounts.pt_mint).await, 6 * LSU);
+    assert_eq!(series.mint_supply(&accounts.yt_mint).await, 6 * LSU);
+    assert_eq!(series.position(&accounts).await.accrued_yield, 0);
+
+    // What's left backs the other 6 SOL of principal
+    assert_eq!(
+        series.token_balance(&accounts.lsu_vault).await,
+        24 * LSU / 5
+    );
+    assert_eq!(series.yield_tokenizer().await.maturity_exchange_rate, 0);
+}
+
+#[tokio::test]
+async fn test_redeem_after_maturity() {
+    let mut series = TestSeries::start().await;
+    series.set_fees(1_000, 0).await;
+    let (user, accounts) = series.new_tokenizer(10 * LSU).await;
+    series.set_exchange_rate(rate(5, 4)).await;
+    series.set_time(MATURITY_DATE).await;
+
+    // The first redemption after maturity freezes the rate at 1.25
+    series
+        .process(
+            &[resolver::redeem_for(&accounts, 4 * LSU).unwrap()],
+            &[&user],
+        )
+        .await
+        .unwrap();
+    assert_eq!(series.token_balance(&accounts.user_lsu_ata).await, 5 * LSU);
+    assert_eq!(series.token_balance(&accounts.treasury).await, LSU / 5);
+    let yield_tokenizer = series.yield_tokenizer().await;
+    assert_eq!(yield_tokenizer.maturity_exchange_rate, rate(5, 4));
+
+    // Later yield isn't the YT's, the rest of the principal redeems at 1.25 with nothing accrued
+    series.set_exchange_rate(rate(3, 2)).await;
+    series
+        .process(
+            &[resolver::redeem_for(&accounts, 6 * LSU).unwrap()],
+            &[&user],
+        )
+        .await
+        .unwrap();
+    assert_eq!(
+        series.token_balance(&accounts.user_lsu_ata).await,
+        5 * LSU + 24 * LSU / 5
+    );
+    assert_eq!(series.token_balance(&accounts.treasury).await, LSU / 5);
+    assert_eq!(series.token_balance(&accounts.lsu_vault).await, 0);
+    assert_eq!(series.mint_supply(&accounts.pt_mint).await, 0);
+    assert_eq!(series.mint_supply(&accounts.yt_mint).await, 0);
+    let yield_tokenizer = series.yield_tokenizer().await;
+    assert_eq!(yield_tokenizer.maturity_exchange_rate, rate(5, 4));
+    assert_eq!(yield_tokenizer.last_exchange_rate, rate(5, 4));
+}
+
+#[tokio::test]
+async fn test_redeem_from_pt_pays_principal_at_maturity_rate() {
+    let mut series = TestSeries::start().await;
+    series.set_fees(0, 500).await;
+    let (user, accounts) = series.new_tokenizer(10 * LSU).await;
+    series.set_exchange_rate(rate(5, 4)).await;
+
+    let redeem = |amount| resolver::redeem_from_pt_for(&accounts, amount).unwrap();
+    assert_eq!(
+        instruction_error(series.process(&[redeem(4 * LSU)], &[&user]).await),
+        custom_error(YieldTokenizerError::Immature)
+    );
+
+    // 4 SOL of principal is `4 * 1 / 1.25` LSU, less the 5% redemption fee
+    series.set_time(MATURITY_DATE).await;
+    series.process(&[redeem(4 * LSU)], &[&user]).await.unwrap();
+    assert_eq!(
+        series.token_balance(&accounts.user_lsu_ata).await,
+        16 * LSU / 5 - 4 * LSU / 25
+    );
+    assert_eq!(series.token_balance(&accounts.treasury).await, 4 * LSU / 25);
+
+    // Rates observed after the first redemption don't move the price
+    series.set_exchange_rate(rate(3, 2)).await;
+    series.process(&[redeem(6 * LSU)], &[&user]).await.unwrap();
+    assert_eq!(
+        series.token_balance(&accounts.user_lsu_ata).await,
+        8 * LSU - 2 * LSU / 5
+    );
+    assert_eq!(series.token_balance(&accounts.treasury).await, 2 * LSU / 5);
+    assert_eq!(series.token_balance(&accounts.user_pt_ata).await, 0);
+    assert_eq!(series.mint_supply(&accounts.pt_mint).await, 0);
+    let yield_tokenizer = series.yield_tokenizer().await;
+    assert_eq!(yield_tokenizer.maturity_exchange_rate, rate(5, 4));
+    assert_eq!(yield_tokenizer.last_exchange_rate, rate(5, 4));
+
+    // The rest of the vault is the YT's yield up to maturity
+    assert_eq!(series.token_balance(&accounts.lsu_vault).await, 2 * LSU);
+    series
+        .process(&[resolver::claim_yield_for(&accounts).unwrap()], &[&user])
+        .await
+        .unwrap();
+    assert_eq!(series.token_balance(&accounts.lsu_vault).await, 0);
+    assert_eq!(
+        series.token_balance(&accounts.user_lsu_ata).await,
+        10 * LSU - 2 * LSU / 5
+    );
+}
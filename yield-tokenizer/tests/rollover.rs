@@ -0,0 +1,116 @@
+mod helpers;
+
+use {
+    helpers::*,
+    solana_program_test::tokio,
+    solana_sdk::{instruction::Instruction, signature::Signer},
+    spl_associated_token_account::instruction::create_associated_token_account_idempotent,
+    yield_tokenizer::{
+        error::YieldTokenizerError, instruction, resolver::TokenizerAccounts,
+        state::YIELD_INDEX_PRECISION,
+    },
+};
+
+/// 2027-03-31
+const NEXT_MATURITY_DATE: i64 = 1_806_451_200;
+
+fn rollover(matured: &TokenizerAccounts, accounts: &TokenizerAccounts, amount: u64) -> Instruction {
+    instruction::rollover(
+        &yield_tokenizer::id(),
+        &accounts.user,
+        &accounts.config,
+        &accounts.lsu_mint,
+        &matured.yield_tokenizer,
+        &matured.lsu_exchange_rate_account,
+        &matured.pt_mint,
+        &matured.yt_mint,
+        &matured.lsu_vault,
+        &matured.treasury,
+        &matured.user_pt_ata,
+        &matured.user_yt_ata,
+        &matured.user_position,
+        &accounts.yield_tokenizer,
+        &accounts.lsu_exchange_rate_account,
+        &accounts.pt_mint,
+        &accounts.yt_mint,
+        &accounts.lsu_vault,
+        &accounts.user_pt_ata,
+        &accounts.user_yt_ata,
+        &accounts.user_position,
+        &matured.yield_token_program,
+        &accounts.yield_token_program,
+        amount,
+    )
+    .unwrap()
+}
+
+#[tokio::test]
+async fn test_rollover_into_the_next_series() {
+    let mut series = TestSeries::start().await;
+    let next_series = series.new_series(NEXT_MATURITY_DATE).await;
+    series.set_fees(1_000, 500).await;
+    let (user, matured) = series.new_tokenizer(10 * LSU).await;
+    let accounts = next_series.for_user(&user.pubkey());
+    let payer = series.context.payer.pubkey();
+    series
+        .process(
+            &[accounts.pt_mint, accounts.yt_mint].map(|mint| {
+                create_associated_token_account_idempotent(
+                    &payer,
+                    &user.pubkey(),
+                    &mint,
+                    &accounts.yield_token_program,
+                )
+            }),
+            &[],
+        )
+        .await
+        .unwrap();
+    series.set_exchange_rate(rate(5, 4)).await;
+
+    assert_eq!(
+        instruction_error(
+            series
+                .process(&[rollover(&matured, &accounts, 10 * LSU)], &[&user])
+                .await
+        ),
+        custom_error(YieldTokenizerError::Immature)
+    );
+
+    series.set_time(MATURITY_DATE).await;
+    series
+        .process(&[rollover(&matured, &accounts, 10 * LSU)], &[&user])
+        .await
+        .unwrap();
+
+    // 8 LSU of principal less the 5% redemption fee, and 2 LSU of yield less the 10% yield fee
+    let lsu_amount = 8 * LSU - 2 * LSU / 5 + 2 * LSU - LSU / 5;
+    assert_eq!(series.token_balance(&matured.treasury).await, 3 * LSU / 5);
+    assert_eq!(series.token_balance(&matured.lsu_vault).await, 0);
+    assert_eq!(series.token_balance(&accounts.lsu_vault).await, lsu_amount);
+    assert_eq!(series.token_balance(&matured.user_lsu_ata).await, 0);
+
+    // Nothing of the matured series is left outstanding
+    assert_eq!(series.token_balance(&matured.user_pt_ata).await, 0);
+    assert_eq!(series.token_balance(&matured.user_yt_ata).await, 0);
+    assert!(series.token_account(&matured.user_yt_ata).await.is_frozen());
+    assert_eq!(series.mint_supply(&matured.pt_mint).await, 0);
+    assert_eq!(series.mint_supply(&matured.yt_mint).await, 0);
+    assert_eq!(series.position(&matured).await.accrued_yield, 0);
+    assert_eq!(
+        series.yield_tokenizer().await.maturity_exchange_rate,
+        rate(5, 4)
+    );
+
+    // The next series was opened at 1 and is now at 1.25, its principal is 1.25 SOL per LSU
+    let principal = lsu_amount * 5 / 4;
+    assert_eq!(series.token_balance(&accounts.user_pt_ata).await, principal);
+    assert_eq!(series.token_balance(&accounts.user_yt_ata).await, principal);
+    assert!(series
+        .token_account(&accounts.user_yt_ata)
+        .await
+        .is_frozen());
+    let position = series.position(&accounts).await;
+    assert_eq!(position.yield_index, YIELD_INDEX_PRECISION / 5);
+    assert_eq!(position.accrued_yield, 0);
+}
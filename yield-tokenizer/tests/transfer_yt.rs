@@ -0,0 +1,119 @@
+mod helpers;
+
+use {
+    helpers::*,
+    solana_program_test::tokio,
+    solana_sdk::{
+        instruction::InstructionError,
+        signature::{Keypair, Signer},
+    },
+    spl_associated_token_account::instruction::create_associated_token_account_idempotent,
+    spl_token::error::TokenError,
+    yield_tokenizer::{resolver, state::YIELD_INDEX_PRECISION},
+};
+
+#[tokio::test]
+async fn test_transfer_yt_settles_both_positions() {
+    let mut series = TestSeries::start().await;
+    let (sender, sender_accounts) = series.new_tokenizer(10 * LSU).await;
+    series.set_exchange_rate(rate(5, 4)).await;
+
+    // The recipient has neither a YT account nor a position yet
+    let recipient = Keypair::new();
+    let recipient_accounts = sender_accounts.for_user(&recipient.pubkey());
+    assert!(series
+        .get_account(&recipient_accounts.user_yt_ata)
+        .await
+        .is_none());
+    assert!(series
+        .get_account(&recipient_accounts.user_position)
+        .await
+        .is_none());
+
+    series
+        .process(
+            &[resolver::transfer_yt_for(&sender_accounts, &recipient.pubkey(), 4 * LSU).unwrap()],
+            &[&sender],
+        )
+        .await
+        .unwrap();
+
+    let sender_yt = series.token_account(&sender_accounts.user_yt_ata).await;
+    assert_eq!(sender_yt.amount, 6 * LSU);
+    assert!(sender_yt.is_frozen());
+    let recipient_yt = series.token_account(&recipient_accounts.user_yt_ata).await;
+    assert_eq!(recipient_yt.amount, 4 * LSU);
+    assert_eq!(recipient_yt.owner, recipient.pubkey());
+    assert!(recipient_yt.is_frozen());
+
+    // The yield earned before the transfer stays with the sender
+    let sender_position = series.position(&sender_accounts).await;
+    assert_eq!(sender_position.yield_index, YIELD_INDEX_PRECISION / 5);
+    assert_eq!(sender_position.accrued_yield, 2 * LSU);
+    let recipient_position = series.position(&recipient_accounts).await;
+    assert_eq!(recipient_position.owner, recipient.pubkey());
+    assert_eq!(recipient_position.yield_index, YIELD_INDEX_PRECISION / 5);
+    assert_eq!(recipient_position.accrued_yield, 0);
+
+    // From here on each side earns on its own balance, 1 / 1.25 - 1 / 1.5 per YT
+    series.set_exchange_rate(rate(3, 2)).await;
+    let payer = series.context.payer.pubkey();
+    series
+        .process(
+            &[create_associated_token_account_idempotent(
+                &payer,
+                &recipient.pubkey(),
+                &recipient_accounts.lsu_mint,
+                &spl_token::id(),
+            )],
+            &[],
+        )
+        .await
+        .unwrap();
+    for (user, accounts) in [
+        (&sender, &sender_accounts),
+        (&recipient, &recipient_accounts),
+    ] {
+        series
+            .process(&[resolver::claim_yield_for(accounts).unwrap()], &[user])
+            .await
+            .unwrap();
+    }
+    assert_eq!(
+        series.token_balance(&sender_accounts.user_lsu_ata).await,
+        2 * LSU + 799_999_999
+    );
+    assert_eq!(
+        series.token_balance(&recipient_accounts.user_lsu_ata).await,
+        533_333_333
+    );
+}
+
+#[tokio::test]
+async fn test_frozen_yt_cannot_be_transferred_directly() {
+    let mut series = TestSeries::start().await;
+    let (sender, sender_accounts) = series.new_tokenizer(10 * LSU).await;
+    let (_, recipient_accounts) = series.new_user(0).await;
+
+    let transfer = spl_token::instruction::transfer(
+        &spl_token::id(),
+        &sender_accounts.user_yt_ata,
+        &recipient_accounts.user_yt_ata,
+        &sender.pubkey(),
+        &[],
+        LSU,
+    )
+    .unwrap();
+    assert_eq!(
+        instruction_error(series.process(&[transfer], &[&sender]).await),
+        InstructionError::Custom(TokenError::AccountFrozen as u32)
+    );
+    assert_eq!(
+        series.token_balance(&sender_accounts.user_yt_ata).await,
+        10 * LSU
+    );
+    assert_eq!(
+        series.token_balance(&recipient_accounts.user_yt_ata).await,
+        0
+    );
+}